@@ -1,12 +1,40 @@
-use super::{Store, StoreAction};
+use super::{
+    ex_command::{ExCommand, Setting},
+    export,
+    preferences::ThemeConfig,
+    session::Session,
+    workspace::Workspace,
+    Store, StoreAction,
+};
 use crate::{
     components::widgets::fps_counter::FpsCounter,
-    components::*,
-    events::{events::EventsHandling, key::Keys, EventState},
+    components::{layout::Border, tab::Tab, *},
+    events::{
+        events::EventsHandling,
+        key::Keys,
+        keymap::{Keymap, Resolution, Scope},
+        EventState,
+    },
+    sql::{
+        connection::mask_credentials,
+        data_diff::DataDiff,
+        guard::{self, AccessMode, Verdict},
+        parameters::{self, BoundStatement},
+        query::Query,
+        schema_diff::SchemaDiff,
+        statement,
+        transaction::ManualTransaction,
+        tunnel::Tunnel,
+    },
 };
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{prelude::*, widgets::*, Frame};
 use sqlx::{any::AnyRow, Column, Row};
-use std::collections::HashMap;
+use std::mem::swap;
+use tokio::sync::mpsc::{error::TryRecvError, UnboundedReceiver};
+
+/// Cells a pane grows or shrinks by with the keys.
+const RESIZE_STEP: i16 = 2;
 
 #[derive(Clone)]
 pub enum UpdateAction {
@@ -20,8 +48,21 @@ pub enum AppAction {
     SendResetTableList,
     SendResetRecords,
     SendResetAll,
+    SendSaveUserData,
+    SendOpenWorkspace(usize),
+    SendCloseConnection(usize),
+    SendRemoveConnection(usize),
+    /// What the password command of a connection printed, by connection id.
+    SendPassword(String, Result<String, String>),
+    /// A connection being opened reached its server, through the ssh tunnel
+    /// if it has one. By connection id.
+    SendConnected(String, Result<Option<Tunnel>, String>),
 
-    SendRecords((Vec<AnyRow>, Option<i64>)),
+    /// Rows read, with the count and the primary key of the table they come
+    /// from when they do.
+    SendRecords((Vec<AnyRow>, Option<i64>, Vec<String>)),
+    SendDataDiff(Box<DataDiff>),
+    SendSchemaDiff(Box<SchemaDiff>),
 }
 
 pub struct App<'a> {
@@ -31,8 +72,19 @@ pub struct App<'a> {
     table_list: TableListComponent,
     command: CommandComponent,
     records_view: RecordsViewComponent,
+    /// Statements run on every connection, in the History tab.
+    history_view: HistoryViewComponent,
     help_view: HelpViewComponent,
     log_view: LogViewComponent,
+    /// Result of the last comparison of two tables, shown over the panes.
+    data_diff: Option<DataDiffViewComponent>,
+    /// Result of the last comparison of two schemas, shown over the panes.
+    schema_diff: Option<SchemaDiffViewComponent>,
+    /// Values asked for the parameters of the command about to be run.
+    parameter_form: Option<ParameterFormComponent>,
+    /// Asks for the name of the database before destructive statements are
+    /// run on a guarded connection, with the command waiting for it.
+    confirm_popup: Option<(InputPopupComponent, String)>,
 
     #[cfg(not(debug_assertions))]
     help_text: HelpTextComponent,
@@ -41,6 +93,27 @@ pub struct App<'a> {
 
     max_pane_column: [u8; 2],
     pub store: Store<'a>,
+
+    keymap: Keymap,
+    /// Keys typed so far of a sequence such as `gg`.
+    pending_keys: Vec<Keys>,
+    /// Areas of the last frame, where the mouse is looked for.
+    layout: Option<LayoutArea>,
+    /// Border being dragged with the mouse.
+    dragging: Option<Border>,
+
+    /// One per open connection. The slot of the active workspace is a
+    /// placeholder, its state being the one of the app and the store.
+    workspaces: Vec<Workspace>,
+    active_workspace: usize,
+    /// Channels of closed workspaces, kept until every query sent from them
+    /// has returned.
+    orphan_receivers: Vec<UnboundedReceiver<UpdateAction>>,
+    /// What is left to reopen of the previous session, as the databases and
+    /// tables come in.
+    restore: Option<Session>,
+    /// Whether quitting was asked once while a transaction is open.
+    quit_warned: bool,
 }
 
 impl<'a> App<'a> {
@@ -51,11 +124,20 @@ impl<'a> App<'a> {
         let table_list = TableListComponent::new();
         let command = CommandComponent::new();
         let records_view = RecordsViewComponent::new();
-        let help_view =
-            HelpViewComponent::new(0, "Connections list".into(), App::help_view_text((0, 0)));
+        let history_view = HistoryViewComponent::new();
         let log_view = LogViewComponent::new();
 
-        let store = Store::new(event_handler);
+        let mut store = Store::new(event_handler);
+        color::init(store.preference.color_depth);
+        let (keymap, problems) = Keymap::new(&store.preference.keymap);
+        for problem in problems {
+            store.error(&problem);
+        }
+        let help_view = HelpViewComponent::new(
+            0,
+            Scope::Connections.title().into(),
+            keymap.help(Scope::Connections),
+        );
         App {
             tab,
             connection_list,
@@ -63,10 +145,15 @@ impl<'a> App<'a> {
             table_list,
 
             records_view,
+            history_view,
             log_view,
             command,
 
             help_view,
+            data_diff: None,
+            schema_diff: None,
+            parameter_form: None,
+            confirm_popup: None,
 
             #[cfg(not(debug_assertions))]
             help_text: HelpTextComponent::new(),
@@ -75,7 +162,143 @@ impl<'a> App<'a> {
 
             max_pane_column: [3, 3],
             store,
+
+            keymap,
+            pending_keys: Vec::new(),
+            layout: None,
+            dragging: None,
+
+            workspaces: vec![Workspace::new()],
+            active_workspace: 0,
+            orphan_receivers: Vec::new(),
+            restore: None,
+            quit_warned: false,
+        }
+    }
+
+    /// Reopen the connection, database and table of the previous session.
+    pub fn restore_session(&mut self) -> anyhow::Result<()> {
+        if !self.store.preference.restore_session {
+            return Ok(());
+        }
+        let Some(session) = Session::load()? else {
+            return Ok(());
+        };
+
+        self.tab.select(session.tab);
+        self.command
+            .set_text(session.command.clone(), session.command_cursor);
+        // the layout of the preferences wins once it is changed
+        if session.preferred_pane_sizes == Some(self.store.preference.layout) {
+            if let Some(pane_sizes) = session.pane_sizes {
+                self.store.pane_sizes = pane_sizes;
+            }
         }
+
+        let connection = session.connection.as_ref().and_then(|id| {
+            self.store
+                .user_data
+                .connection_list
+                .list
+                .iter()
+                .position(|c| c.id == *id)
+        });
+        match connection {
+            Some(index) => {
+                self.connection_list.select(index, &mut self.store)?;
+                self.restore = Some(session);
+            }
+            None => self.restore_selected_pane(session.selected_pane),
+        }
+        Ok(())
+    }
+
+    pub fn save_session(&self) -> anyhow::Result<()> {
+        if !self.store.preference.restore_session {
+            return Ok(());
+        }
+        let connection_list = &self.store.user_data.connection_list;
+        let database_list = &self.store.database_list;
+        let session = Session {
+            connection: connection_list
+                .current_connection
+                .and_then(|i| connection_list.list.get(i))
+                .map(|c| c.id.clone()),
+            database: database_list
+                .current_database
+                .and_then(|i| database_list.list.get(i))
+                .cloned(),
+            table: self.table_list.current_table(&self.store).cloned(),
+            tab: self.tab.selected_tab,
+            command: self.command.text_input.clone(),
+            command_cursor: self.command.cursor_pos,
+            selected_pane: self.store.selected_pane,
+            pane_sizes: Some(self.store.pane_sizes),
+            preferred_pane_sizes: Some(self.store.preference.layout),
+        };
+        session.save()
+    }
+
+    fn restore_selected_pane(&mut self, selected_pane: (u8, u8)) {
+        // the help popup is not worth coming back to
+        if selected_pane != (100, 100) {
+            self.store.selected_pane = selected_pane;
+        }
+    }
+
+    /// Select the database, then the table, of the session being restored
+    /// once their lists have been loaded.
+    fn continue_restore(&mut self) -> anyhow::Result<()> {
+        let Some(session) = &mut self.restore else {
+            return Ok(());
+        };
+        let connection_list = &self.store.user_data.connection_list;
+        let Some(connection) = connection_list
+            .current_connection
+            .and_then(|i| connection_list.list.get(i))
+        else {
+            // not connected yet, the vault may still be locked
+            return Ok(());
+        };
+        if session.connection.as_ref() != Some(&connection.id) {
+            // another connection has been chosen meanwhile
+            self.restore = None;
+            return Ok(());
+        }
+
+        if let Some(database) = &session.database {
+            if self.store.database_list.list.is_empty() {
+                return Ok(());
+            }
+            let index = self
+                .store
+                .database_list
+                .list
+                .iter()
+                .position(|d| d == database);
+            session.database = None;
+            match index {
+                Some(index) => self.database_list.select(index, &mut self.store)?,
+                None => session.table = None,
+            }
+            return Ok(());
+        }
+
+        if let Some(table) = &session.table {
+            if self.store.tables_list.is_empty() {
+                return Ok(());
+            }
+            if let Some(index) = self.store.tables_list.iter().position(|t| t == table) {
+                self.table_list.select(index, &mut self.store)?;
+            }
+            session.table = None;
+            return Ok(());
+        }
+
+        if let Some(session) = self.restore.take() {
+            self.restore_selected_pane(session.selected_pane);
+        }
+        Ok(())
     }
     pub fn draw(&mut self, frame: &mut Frame) -> anyhow::Result<()> {
         if let Some(paragraph) = self.verify_space_available(frame) {
@@ -95,44 +318,73 @@ impl<'a> App<'a> {
         let select_log_view = self.store.selected_pane.0 == 1 && self.store.selected_pane.1 == 2;
         let select_command = self.store.selected_pane.0 == 1 && self.store.selected_pane.1 == 3;
 
-        let layout = LayoutArea::new(&frame);
+        let layout = LayoutArea::new(frame.size(), &self.store.pane_sizes, self.zoomed_pane());
+        self.layout = Some(layout.clone());
 
-        self.connection_list.draw(
-            frame,
-            layout.left_area[0],
-            select_connection_list,
-            &self.store,
-            &layout,
-        )?;
-        self.database_list.draw(
-            frame,
-            layout.left_area[1],
-            select_database_list,
-            &self.store,
-            &layout,
-        )?;
-        self.table_list.draw(
-            frame,
-            layout.left_area[2],
-            select_table_list,
-            &self.store,
-            &layout,
-        )?;
+        // the panes hidden, or left out by the zoom, have no room
+        let shown = |area: Rect| !area.is_empty();
+        if shown(layout.left_area[0]) {
+            self.connection_list.draw(
+                frame,
+                layout.left_area[0],
+                select_connection_list,
+                &self.store,
+                &layout,
+            )?;
+        }
+        if shown(layout.left_area[1]) {
+            self.database_list.draw(
+                frame,
+                layout.left_area[1],
+                select_database_list,
+                &self.store,
+                &layout,
+            )?;
+        }
+        if shown(layout.left_area[2]) {
+            self.table_list.draw(
+                frame,
+                layout.left_area[2],
+                select_table_list,
+                &self.store,
+                &layout,
+            )?;
+        }
 
-        self.tab.draw(
-            frame,
-            layout.right_area[0],
-            select_tab,
-            &self.store,
-            &layout,
-        )?;
-        self.records_view.draw(
-            frame,
-            layout.right_area[1],
-            select_records_view,
-            &self.store,
-            &layout,
-        )?;
+        if shown(layout.right_area[0]) {
+            self.tab.draw(
+                frame,
+                layout.right_area[0],
+                select_tab,
+                &self.store,
+                &layout,
+            )?;
+            if let Some(bar) = self.workspace_bar() {
+                let area = layout.right_area[0];
+                frame.render_widget(
+                    Paragraph::new(bar).alignment(Alignment::Right),
+                    Rect::new(area.x + 2, area.y, area.width.saturating_sub(4), 1),
+                );
+            }
+        }
+        if shown(layout.right_area[1]) {
+            match self.tab.selected() {
+                Tab::History => self.history_view.draw(
+                    frame,
+                    layout.right_area[1],
+                    select_records_view,
+                    &self.store,
+                    &layout,
+                )?,
+                _ => self.records_view.draw(
+                    frame,
+                    layout.right_area[1],
+                    select_records_view,
+                    &self.store,
+                    &layout,
+                )?,
+            }
+        }
         self.command.draw(
             frame,
             layout.right_area[2],
@@ -140,26 +392,71 @@ impl<'a> App<'a> {
             &self.store,
             &layout,
         )?;
-        self.log_view.draw(
-            frame,
-            layout.right_area[3],
-            select_log_view,
-            &self.store,
-            &layout,
-        )?;
+        if shown(layout.right_area[3]) {
+            self.log_view.draw(
+                frame,
+                layout.right_area[3],
+                select_log_view,
+                &self.store,
+                &layout,
+            )?;
+        }
+
+        self.connection_list
+            .draw_popup(frame, select_connection_list, &self.store, &layout)?;
+        self.database_list
+            .draw_popup(frame, select_database_list, &self.store, &layout)?;
+        self.table_list
+            .draw_popup(frame, select_table_list, &self.store, &layout)?;
+        self.command.draw_popup(frame, &self.store, &layout)?;
+        if let Some(schema_diff) = &mut self.schema_diff {
+            schema_diff.draw(
+                frame,
+                centered_rect(layout.main_area[0], 80, 80),
+                true,
+                &self.store,
+                &layout,
+            )?;
+        }
+        if let Some(data_diff) = &mut self.data_diff {
+            data_diff.draw(
+                frame,
+                centered_rect(layout.main_area[0], 80, 80),
+                true,
+                &self.store,
+                &layout,
+            )?;
+        }
+        if let Some(parameter_form) = &mut self.parameter_form {
+            parameter_form.draw(frame, layout.main_area[0], true, &self.store, &layout)?;
+        }
+        if let Some((popup, _)) = &mut self.confirm_popup {
+            popup.draw(
+                frame,
+                centered_rect_fixed_height(layout.main_area[0], 50, 3),
+                true,
+                &self.store,
+                &layout,
+            )?;
+        }
 
-        #[cfg(not(debug_assertions))]
-        self.help_text
-            .draw(frame, layout.main_area[1], false, &self.store)?;
+        if shown(layout.main_area[1]) {
+            #[cfg(not(debug_assertions))]
+            self.help_text
+                .draw(frame, layout.main_area[1], false, &self.store)?;
 
-        #[cfg(debug_assertions)]
-        self.fps_counter.draw(frame, layout.main_area[1])?;
+            #[cfg(debug_assertions)]
+            {
+                self.fps_counter.app_tick();
+                self.fps_counter.draw(frame, layout.main_area[1])?;
+            }
+        }
 
         match self.store.selected_pane {
             (100, 100) => {
                 self.help_view.draw(
                     frame,
-                    centered_rect(layout.main_area[0], 25, 50),
+                    centered_rect(layout.main_area[0], 40, 50),
                     true,
                     &self.store,
                     &layout,
@@ -171,20 +468,217 @@ impl<'a> App<'a> {
         Ok(())
     }
 
-    pub fn update(&mut self) {
-        #[cfg(debug_assertions)]
-        self.fps_counter.app_tick();
-
+    /// Apply what the queries sent back, and tell whether anything came in.
+    pub fn update(&mut self) -> bool {
+        let mut updated = false;
         while let Ok(action) = self.store.actions_rx.try_recv() {
-            match action {
-                UpdateAction::SendStoreAction(act) => {
-                    self.store.update(act);
-                }
-                UpdateAction::SendAppAction(act) => {
-                    self.update_action(act);
+            self.dispatch(action);
+            updated = true;
+        }
+        if let Err(e) = self.continue_restore() {
+            self.store.error(&e.to_string());
+            self.restore = None;
+        }
+
+        let mut disconnected = Vec::new();
+        for index in 0..self.workspaces.len() {
+            if index != self.active_workspace && !self.workspaces[index].actions_rx.is_empty() {
+                self.update_workspace(index);
+                updated = true;
+                if self.workspaces[index].connection.is_none() {
+                    disconnected.push(index);
                 }
             }
         }
+        for index in disconnected.into_iter().rev() {
+            if let Err(e) = self.close_workspace(index) {
+                self.store.error(&e.to_string());
+            }
+        }
+
+        self.orphan_receivers.retain_mut(|actions_rx| loop {
+            match actions_rx.try_recv() {
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => break true,
+                Err(TryRecvError::Disconnected) => break false,
+            }
+        });
+        updated
+    }
+
+    /// Whether the screen changes with time, the command pane showing for how
+    /// long a transaction is open.
+    pub fn is_ticking(&self) -> bool {
+        !self.open_transactions().is_empty()
+    }
+
+    pub fn dispatch(&mut self, action: UpdateAction) {
+        match action {
+            UpdateAction::SendStoreAction(act) => {
+                self.store.update(act);
+            }
+            UpdateAction::SendAppAction(act) => {
+                self.update_action(act);
+            }
+        }
+    }
+
+    /// Apply what a query sent to a workspace which is not shown, by bringing
+    /// it on screen for the time of the update.
+    fn update_workspace(&mut self, index: usize) {
+        let selected_pane = self.store.selected_pane;
+        let mut workspace = std::mem::take(&mut self.workspaces[index]);
+        self.exchange_workspace(&mut workspace);
+        while let Ok(action) = self.store.actions_rx.try_recv() {
+            self.dispatch(action);
+        }
+        self.exchange_workspace(&mut workspace);
+        self.workspaces[index] = workspace;
+        self.store.selected_pane = selected_pane;
+    }
+
+    fn exchange_workspace(&mut self, workspace: &mut Workspace) {
+        let connection_list = &mut self.store.user_data.connection_list;
+        swap(
+            &mut connection_list.current_connection,
+            &mut workspace.connection,
+        );
+        swap(&mut connection_list.is_loading, &mut workspace.is_loading);
+        swap(&mut self.store.database_list, &mut workspace.database_list);
+        swap(&mut self.store.tables_list, &mut workspace.tables_list);
+        swap(&mut self.store.catalog, &mut workspace.catalog);
+        swap(&mut self.store.actions_tx, &mut workspace.actions_tx);
+        swap(&mut self.store.actions_rx, &mut workspace.actions_rx);
+        swap(&mut self.tab, &mut workspace.tab);
+        swap(
+            &mut self.database_list,
+            &mut workspace.database_list_component,
+        );
+        swap(&mut self.table_list, &mut workspace.table_list_component);
+        swap(&mut self.records_view, &mut workspace.records_view);
+    }
+
+    fn switch_workspace(&mut self, index: usize) {
+        if index == self.active_workspace || index >= self.workspaces.len() {
+            return;
+        }
+        let mut active = std::mem::take(&mut self.workspaces[self.active_workspace]);
+        self.exchange_workspace(&mut active);
+        self.workspaces[self.active_workspace] = active;
+
+        let mut next = std::mem::take(&mut self.workspaces[index]);
+        self.exchange_workspace(&mut next);
+        self.workspaces[index] = next;
+        self.active_workspace = index;
+    }
+
+    /// Index of the workspace showing the connection, if it is open.
+    fn workspace_of(&self, connection: usize) -> Option<usize> {
+        if self.store.user_data.connection_list.current_connection == Some(connection) {
+            return Some(self.active_workspace);
+        }
+        self.workspaces
+            .iter()
+            .enumerate()
+            .find(|(index, workspace)| {
+                *index != self.active_workspace && workspace.connection == Some(connection)
+            })
+            .map(|(index, _)| index)
+    }
+
+    /// Show the workspace of the connection, opening the connection in a new
+    /// one when it isn't open yet.
+    fn open_workspace(&mut self, connection: usize) -> anyhow::Result<()> {
+        if let Some(index) = self.workspace_of(connection) {
+            self.switch_workspace(index);
+            return Ok(());
+        }
+
+        if self
+            .store
+            .user_data
+            .connection_list
+            .current_connection
+            .is_some()
+        {
+            self.workspaces.push(Workspace::new());
+            self.switch_workspace(self.workspaces.len() - 1);
+        }
+        self.connection_list.open(connection, &mut self.store)
+    }
+
+    /// Close the connection of the workspace and drop it. The last workspace
+    /// is emptied instead. Refused while a transaction holds changes.
+    fn close_workspace(&mut self, index: usize) -> anyhow::Result<()> {
+        let connection_list = &self.store.user_data.connection_list;
+        let connection = if index == self.active_workspace {
+            connection_list.current_connection
+        } else {
+            self.workspaces[index].connection
+        };
+        if let Some(connection) = connection.and_then(|i| connection_list.list.get(i)) {
+            connection.ensure_closable()?;
+        }
+
+        if index == self.active_workspace {
+            if self.workspaces.len() == 1 {
+                self.store
+                    .user_data
+                    .connection_list
+                    .reset_current_connection();
+                let mut empty = Workspace::new();
+                self.exchange_workspace(&mut empty);
+                self.orphan_receivers.push(empty.actions_rx);
+                return Ok(());
+            }
+            self.switch_workspace(if index == 0 { 1 } else { index - 1 });
+        }
+
+        let workspace = self.workspaces.remove(index);
+        if index < self.active_workspace {
+            self.active_workspace -= 1;
+        }
+        if let Some(connection) = workspace
+            .connection
+            .and_then(|i| self.store.user_data.connection_list.list.get_mut(i))
+        {
+            connection.close();
+        }
+        self.orphan_receivers.push(workspace.actions_rx);
+        Ok(())
+    }
+
+    fn workspace_bar(&self) -> Option<Line<'static>> {
+        let connection_list = &self.store.user_data.connection_list;
+        if self.workspaces.len() < 2 && connection_list.current_connection.is_none() {
+            return None;
+        }
+
+        let theme_config = self.store.preference.theme_config;
+        let spans = self
+            .workspaces
+            .iter()
+            .enumerate()
+            .map(|(index, workspace)| {
+                let (connection, color) = if index == self.active_workspace {
+                    (
+                        connection_list.current_connection,
+                        theme_config.selected_color,
+                    )
+                } else {
+                    (workspace.connection, theme_config.unselected_color)
+                };
+                let name = connection
+                    .and_then(|i| connection_list.list.get(i))
+                    .map(|c| mask_credentials(&c.name))
+                    .unwrap_or(String::from("no connection"));
+                Span::styled(
+                    format!(" {}:{} ", index + 1, name),
+                    Style::default().fg(color::from_rgb(color)),
+                )
+            })
+            .collect::<Vec<_>>();
+        Some(Line::from(spans))
     }
 
     fn update_action(&mut self, action: AppAction) {
@@ -195,6 +689,7 @@ impl<'a> App<'a> {
                 self.records_view = RecordsViewComponent::new();
             }
             AppAction::SendResetTableList => {
+                self.store.catalog = None;
                 self.table_list = TableListComponent::new();
                 self.records_view = RecordsViewComponent::new();
             }
@@ -208,6 +703,60 @@ impl<'a> App<'a> {
                 self.table_list = TableListComponent::new();
                 self.records_view = RecordsViewComponent::new();
             }
+            AppAction::SendSaveUserData => {
+                if let Err(e) = self.connection_list.save(&mut self.store) {
+                    self.store.error(&e.to_string());
+                }
+            }
+            AppAction::SendOpenWorkspace(connection) => {
+                if let Err(e) = self.open_workspace(connection) {
+                    self.store.error(&e.to_string());
+                }
+            }
+            AppAction::SendCloseConnection(connection) => {
+                if let Some(index) = self.workspace_of(connection) {
+                    if let Err(e) = self.close_workspace(index) {
+                        self.store.error(&e.to_string());
+                    }
+                }
+            }
+            AppAction::SendRemoveConnection(connection) => {
+                if let Some(index) = self.workspace_of(connection) {
+                    if let Err(e) = self.close_workspace(index) {
+                        self.store.error(&e.to_string());
+                        return;
+                    }
+                }
+                self.store.user_data.connection_list.remove(connection);
+                for (index, workspace) in self.workspaces.iter_mut().enumerate() {
+                    if index == self.active_workspace {
+                        continue;
+                    }
+                    if let Some(i) = workspace.connection.filter(|&i| i > connection) {
+                        workspace.connection = Some(i - 1);
+                    }
+                }
+                self.store.log("Connection removed.");
+                if let Err(e) = self.connection_list.save(&mut self.store) {
+                    self.store.error(&e.to_string());
+                }
+            }
+            AppAction::SendPassword(id, password) => {
+                if let Err(e) = self
+                    .connection_list
+                    .receive_password(&id, password, &mut self.store)
+                {
+                    self.store.error(&e.to_string());
+                }
+            }
+            AppAction::SendConnected(id, tunnel) => {
+                if let Err(e) = self
+                    .connection_list
+                    .connected(&id, tunnel, &mut self.store)
+                {
+                    self.store.error(&e.to_string());
+                }
+            }
             AppAction::SendRecords(rows) => {
                 if rows.0.len() > 0 {
                     let header = rows
@@ -221,15 +770,109 @@ impl<'a> App<'a> {
                     self.records_view.set_header(header);
                     self.records_view.set_body(rows.0);
                     self.records_view.set_total(rows.1);
+                    // only the records of a table are counted
+                    let table = rows
+                        .1
+                        .and(self.table_list.current_table(&self.store).cloned());
+                    self.records_view.set_table(table, rows.2);
 
                     self.store.selected_pane = (1, 1);
                 }
             }
+            AppAction::SendDataDiff(diff) => {
+                self.store.success(&format!(
+                    "{} rows differ between {} and {}.",
+                    diff.rows.len(),
+                    diff.left,
+                    diff.right
+                ));
+                self.data_diff = Some(DataDiffViewComponent::new(
+                    *diff,
+                    &self.store.preference.theme_config,
+                ));
+            }
+            AppAction::SendSchemaDiff(diff) => {
+                self.store.success(&format!(
+                    "Schemas of {} and {} read.",
+                    diff.left.name(),
+                    diff.right.name()
+                ));
+                self.schema_diff = Some(SchemaDiffViewComponent::new(*diff));
+            }
         }
     }
 
     pub fn event_handling(&mut self, k: Keys) -> anyhow::Result<()> {
+        if let Some((popup, content)) = &mut self.confirm_popup {
+            let database = match popup.event(&k, &mut self.store)? {
+                EventState::ConfirmedText(database) => Some(database),
+                EventState::Escaped => None,
+                _ => return Ok(()),
+            };
+            let content = std::mem::take(content);
+            self.confirm_popup = None;
+            match database {
+                Some(database) if database.trim() == self.store.database_name() => {
+                    if let Err(e) = self.ask_parameters(statement::split(&content)) {
+                        self.store.error(&e.to_string());
+                    }
+                }
+                Some(_) => {
+                    self.keep_draft(&content);
+                    self.store
+                        .error("The database name doesn't match, nothing was run.");
+                }
+                None => self.keep_draft(&content),
+            }
+            return Ok(());
+        }
+        if let Some(parameter_form) = &mut self.parameter_form {
+            match parameter_form.event(&k, &mut self.store)? {
+                EventState::ConfirmedForm(values) => {
+                    // the form stays open on a value of the wrong type
+                    match parameter_form.bind(values, &mut self.store) {
+                        Ok(statements) => {
+                            self.parameter_form = None;
+                            if let Err(e) = self.send_statements(statements) {
+                                self.store.error(&e.to_string());
+                            }
+                        }
+                        Err(e) => self.store.error(&e.to_string()),
+                    }
+                }
+                EventState::Escaped => {
+                    self.parameter_form = None;
+                    self.store.log("No value was given, nothing was run.");
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+        let k = match self.scope() {
+            Some(scope) => match self.translate(scope, k) {
+                Some(k) => k,
+                None => return Ok(()),
+            },
+            None => k,
+        };
+        if let Some(data_diff) = &mut self.data_diff {
+            if let EventState::Escaped = data_diff.event(&k, &mut self.store)? {
+                self.data_diff = None;
+            }
+            return Ok(());
+        }
+        if let Some(schema_diff) = &mut self.schema_diff {
+            if let EventState::Escaped = schema_diff.event(&k, &mut self.store)? {
+                self.schema_diff = None;
+            }
+            return Ok(());
+        }
+        let pane = self.store.selected_pane;
         self.event(&k)?;
+        // `:` has started a command in the command pane, it isn't typed twice
+        if k == Keys::Char(':') && pane != (1, 3) && self.store.selected_pane == (1, 3) {
+            return Ok(());
+        }
         match self.store.selected_pane {
             (0, 0) => {
                 self.connection_list.event(&k, &mut self.store)?;
@@ -243,17 +886,39 @@ impl<'a> App<'a> {
             (1, 0) => {
                 self.tab.event(&k, &mut self.store)?;
             }
-            (1, 1) => {
-                self.records_view.event(&k, &mut self.store)?;
-            }
+            (1, 1) => match self.tab.selected() {
+                Tab::History => {
+                    let event = self.history_view.event(&k, &mut self.store)?;
+                    if let EventState::ConfirmedText(sql) = event {
+                        let cursor = sql.chars().count();
+                        self.command.set_text(sql, cursor);
+                        self.store.selected_pane = (1, 3);
+                    }
+                }
+                _ => {
+                    let event = self.records_view.event(&k, &mut self.store)?;
+                    if let EventState::ConfirmedText(update) = event {
+                        let cursor = update.chars().count();
+                        self.command.set_text(update, cursor);
+                        self.store.selected_pane = (1, 3);
+                    }
+                }
+            },
             (1, 2) => {
                 self.log_view.event(&k, &mut self.store)?;
             }
             (1, 3) => {
                 let event = self.command.event(&k, &mut self.store)?;
-                if let EventState::ConfirmedText(_content) = event {
-                    // todo
+                if let EventState::ConfirmedText(content) = event {
                     self.store.selected_pane = self.store.previous_selected_pane;
+                    let result = if content.starts_with(':') {
+                        self.run_ex(&content)
+                    } else {
+                        self.run_command(&content)
+                    };
+                    if let Err(e) = result {
+                        self.store.error(&e.to_string());
+                    }
                 }
             }
             (100, 100) => {
@@ -264,7 +929,351 @@ impl<'a> App<'a> {
         Ok(())
     }
 
+    /// Focus the pane clicked and select what is under the mouse, scroll the
+    /// pane under it, or drag the border of a pane.
+    pub fn mouse_handling(&mut self, mouse: MouseEvent) -> anyhow::Result<()> {
+        if self.popup_open() {
+            return Ok(());
+        }
+        let Some(layout) = self.layout.clone() else {
+            return Ok(());
+        };
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.dragging = layout.border_at(mouse.column, mouse.row);
+                if self.dragging.is_some() {
+                    return Ok(());
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some(border) = self.dragging {
+                    self.store
+                        .pane_sizes
+                        .drag(border, &layout, mouse.column, mouse.row);
+                }
+                return Ok(());
+            }
+            MouseEventKind::Up(_) => {
+                self.dragging = None;
+                return Ok(());
+            }
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {}
+            _ => return Ok(()),
+        }
+
+        let Some((pane, area)) = layout.pane_at(mouse.column, mouse.row) else {
+            return Ok(());
+        };
+        if let MouseEventKind::Down(_) = mouse.kind {
+            self.focus(pane);
+        }
+        match pane {
+            (0, 0) => self.connection_list.mouse(&mouse, area, &mut self.store)?,
+            (0, 1) => self.database_list.mouse(&mouse, area, &mut self.store)?,
+            (0, 2) => self.table_list.mouse(&mouse, area, &mut self.store)?,
+            (1, 0) => self.tab.mouse(&mouse, area, &mut self.store)?,
+            (1, 1) => match self.tab.selected() {
+                Tab::History => self.history_view.mouse(&mouse, area, &mut self.store)?,
+                _ => self.records_view.mouse(&mouse, area, &mut self.store)?,
+            },
+            (1, 2) => self.log_view.mouse(&mouse, area, &mut self.store)?,
+            _ => EventState::Wasted,
+        };
+        Ok(())
+    }
+
+    fn focus(&mut self, pane: (u8, u8)) {
+        let selected = self.store.selected_pane;
+        if pane == selected {
+            return;
+        }
+        if selected == (1, 3) {
+            // a `:` command typed halfway is given up like with Esc
+            self.command.cancel_ex();
+        }
+        if pane == (1, 3) {
+            self.store.previous_selected_pane = selected;
+        }
+        self.store.selected_pane = pane;
+    }
+
+    /// Run a `:` command typed in the command pane.
+    fn run_ex(&mut self, line: &str) -> anyhow::Result<()> {
+        match line.parse::<ExCommand>()? {
+            ExCommand::Connect(name) => {
+                let index = self
+                    .store
+                    .user_data
+                    .connection_list
+                    .list
+                    .iter()
+                    .position(|c| c.name == name)
+                    .ok_or(anyhow::anyhow!("Unknown connection : {}", name))?;
+                self.connection_list.select(index, &mut self.store)?;
+            }
+            ExCommand::Use(name) => {
+                let index = self
+                    .store
+                    .database_list
+                    .list
+                    .iter()
+                    .position(|d| *d == name)
+                    .ok_or(anyhow::anyhow!("Unknown database : {}", name))?;
+                self.database_list.select(index, &mut self.store)?;
+            }
+            ExCommand::Open(name) => {
+                let index = self
+                    .store
+                    .tables_list
+                    .iter()
+                    .position(|t| *t == name)
+                    .ok_or(anyhow::anyhow!("Unknown table : {}", name))?;
+                self.table_list.select(index, &mut self.store)?;
+            }
+            ExCommand::Export(format, path) => {
+                let (header, rows, total) = self.records_view.records();
+                if rows.is_empty() {
+                    anyhow::bail!("There are no records to export.");
+                }
+                let path = export::export(format, &path, header, rows)?;
+                let total = match total {
+                    Some(total) if total > rows.len() as i64 => {
+                        format!(", the table holds {}", total)
+                    }
+                    _ => String::new(),
+                };
+                self.store.success(&format!(
+                    "{} rows of the loaded page exported to {}{}.",
+                    rows.len(),
+                    path.display(),
+                    total
+                ));
+            }
+            ExCommand::Set(Setting::PageSize, value) => {
+                let size =
+                    value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|size| *size > 0)
+                        .ok_or(anyhow::anyhow!(
+                            "{} : {} is not a positive number.",
+                            Setting::PageSize,
+                            value
+                        ))?;
+                self.store.preference.page_size = size;
+                self.store.success(&format!(
+                    "{} set to {}, for the tables opened from now on.",
+                    Setting::PageSize,
+                    size
+                ));
+            }
+            ExCommand::Set(setting @ (Setting::Log | Setting::HelpLine), value) => {
+                let show = match value.as_str() {
+                    "on" | "true" => true,
+                    "off" | "false" => false,
+                    _ => anyhow::bail!("{} : {} is neither on nor off.", setting, value),
+                };
+                match setting {
+                    Setting::Log => {
+                        self.store.pane_sizes.show_log = show;
+                        // the command pane gives the focus back to a pane shown
+                        if !show && self.store.previous_selected_pane == (1, 2) {
+                            self.store.previous_selected_pane = (1, 1);
+                        }
+                    }
+                    _ => self.store.pane_sizes.show_help = show,
+                }
+                self.store
+                    .success(&format!("{} set to {}.", setting, value));
+            }
+            ExCommand::Theme(name) => {
+                self.store.preference.theme_config = ThemeConfig::load(&name)?;
+                self.store.success(&format!("Theme {} loaded.", name));
+            }
+            ExCommand::Write(name) => {
+                // the form may be opened in the command pane
+                self.store.selected_pane = (1, 3);
+                self.command.write(name, &mut self.store)?;
+            }
+            ExCommand::Quit(force) => {
+                let open = self.open_transactions();
+                if !open.is_empty() && !force {
+                    anyhow::bail!(
+                        "A transaction is open on {}, :q! quits and rolls it back.",
+                        open.join(", ")
+                    );
+                }
+                self.store.exit = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Names of the connections holding a transaction open.
+    fn open_transactions(&self) -> Vec<String> {
+        self.store
+            .user_data
+            .connection_list
+            .list
+            .iter()
+            .filter(|c| c.transaction.is_some())
+            .map(|c| c.name.clone())
+            .collect()
+    }
+
+    /// Send the statements of the command pane to the current connection once
+    /// its access mode lets them through, the destructive ones being
+    /// confirmed first on a guarded connection.
+    fn run_command(&mut self, content: &str) -> anyhow::Result<()> {
+        let statements = statement::split(content);
+        if statements.is_empty() {
+            return Ok(());
+        }
+        let connection_list = &self.store.user_data.connection_list;
+        // nothing is asked when the connection isn't open
+        connection_list.get_pool()?;
+        let connection = &connection_list.list[connection_list.current_connection.unwrap()];
+        match connection.mode.check(&statements) {
+            Verdict::Allowed => self.ask_parameters(statements),
+            Verdict::Rejected(statement) => {
+                let message = format!(
+                    "{} is read-only, nothing was run : {}",
+                    connection.name, statement
+                );
+                self.keep_draft(content);
+                Err(anyhow::anyhow!(message))
+            }
+            Verdict::Confirm(destructive) => {
+                let popup = InputPopupComponent::new(
+                    format!(
+                        "Type {} to run {} destructive statement(s)",
+                        self.store.database_name(),
+                        destructive.len()
+                    ),
+                    String::new(),
+                    InputAction::Insert,
+                );
+                self.confirm_popup = Some((popup, content.to_string()));
+                Ok(())
+            }
+        }
+    }
+
+    /// Put back the command which wasn't run, to change it.
+    fn keep_draft(&mut self, content: &str) {
+        self.command
+            .set_text(content.to_string(), content.chars().count());
+    }
+
+    /// Ask the values of the parameters of the statements, then run them.
+    fn ask_parameters(&mut self, statements: Vec<String>) -> anyhow::Result<()> {
+        let connection_list = &self.store.user_data.connection_list;
+        connection_list.get_pool()?;
+        let connection = &connection_list.list[connection_list.current_connection.unwrap()];
+        let names = parameters::names(&statements, connection.dialect());
+        if !names.is_empty() {
+            self.parameter_form = Some(ParameterFormComponent::new(
+                statements,
+                names,
+                connection.dialect(),
+            ));
+            return Ok(());
+        }
+        self.send_statements(statements.into_iter().map(BoundStatement::from).collect())
+    }
+
+    /// Run the statements in the transaction of the current connection, or
+    /// on its pool when none is open, the destructive ones being audited
+    /// first on a guarded connection.
+    fn send_statements(&mut self, statements: Vec<BoundStatement>) -> anyhow::Result<()> {
+        let connection_list = &self.store.user_data.connection_list;
+        let pool = connection_list.get_pool()?;
+        let connection = &connection_list.list[connection_list.current_connection.unwrap()];
+        if connection.mode == AccessMode::Guarded {
+            let sql = statements.iter().map(|s| s.sql.clone()).collect::<Vec<_>>();
+            let destructive = guard::destructive(&sql);
+            if !destructive.is_empty() {
+                guard::audit(&connection.name, &self.store.database_name(), &destructive)
+                    .map_err(|e| anyhow::anyhow!("Unable to write the audit file : {}", e))?;
+            }
+        }
+        let count = statements.len();
+        for statement in &statements {
+            self.history_view.push(&connection.name, &statement.sql);
+        }
+        match &connection.transaction {
+            Some(transaction) => {
+                transaction.run(statements)?;
+                self.store.log(&format!(
+                    "Running {} statement(s) in the transaction...",
+                    count
+                ));
+            }
+            None => {
+                Query::run(pool, self.store.actions_tx.clone(), statements);
+                self.store
+                    .log(&format!("Running {} statement(s)...", count));
+            }
+        }
+        Ok(())
+    }
+
+    /// Put the current connection in manual-commit mode, or back to
+    /// auto-commit when nothing is pending in its transaction.
+    fn toggle_transaction(&mut self) -> anyhow::Result<()> {
+        let connection_list = &mut self.store.user_data.connection_list;
+        let index = connection_list
+            .current_connection
+            .ok_or(anyhow::anyhow!("No current connection is set."))?;
+        let pool = connection_list.open_pool(index)?;
+        let connection = &mut connection_list.list[index];
+        let message = match connection.transaction.take() {
+            Some(transaction) if transaction.pending > 0 => {
+                let pending = transaction.pending;
+                connection.transaction = Some(transaction);
+                anyhow::bail!(
+                    "{} statement(s) are pending, commit or roll back the transaction first.",
+                    pending
+                );
+            }
+            Some(transaction) => {
+                transaction.rollback();
+                format!("{} is back in auto-commit mode.", connection.name)
+            }
+            None => {
+                connection.transaction = Some(ManualTransaction::begin(
+                    pool,
+                    self.store.actions_tx.clone(),
+                    connection.id.clone(),
+                ));
+                format!(
+                    "{} is in manual-commit mode, <Alt-c> commits and <Alt-r> rolls back.",
+                    connection.name
+                )
+            }
+        };
+        self.store.log(&message);
+        Ok(())
+    }
+
+    fn end_transaction(&mut self, commit: bool) -> anyhow::Result<()> {
+        let connection_list = &mut self.store.user_data.connection_list;
+        let transaction = connection_list
+            .current_connection
+            .and_then(|i| connection_list.list[i].transaction.take())
+            .ok_or(anyhow::anyhow!("No transaction is open."))?;
+        if commit {
+            transaction.commit();
+        } else {
+            transaction.rollback();
+        }
+        Ok(())
+    }
+
     fn event(&mut self, input: &Keys) -> anyhow::Result<EventState> {
+        if *input != Keys::Char('q') {
+            self.quit_warned = false;
+        }
         if !self.store.is_lock && self.store.selected_pane != (1, 3) {
             match input {
                 Keys::CtrlChar('j') => {
@@ -272,8 +1281,8 @@ impl<'a> App<'a> {
                         self.store.selected_pane = self.store.previous_selected_pane;
                         return Ok(EventState::Consumed);
                     }
-                    let max_pane = self.max_pane_column[self.store.selected_pane.0 as usize];
-                    if self.store.selected_pane.1 == max_pane - 1 {
+                    let max_pane = self.max_pane(self.store.selected_pane.0);
+                    if self.store.selected_pane.1 >= max_pane - 1 {
                         self.store.selected_pane.1 = 0;
                     } else {
                         self.store.selected_pane.1 += 1;
@@ -284,7 +1293,7 @@ impl<'a> App<'a> {
                         self.store.selected_pane = self.store.previous_selected_pane;
                         return Ok(EventState::Consumed);
                     }
-                    let max_pane = self.max_pane_column[self.store.selected_pane.0 as usize];
+                    let max_pane = self.max_pane(self.store.selected_pane.0);
                     if self.store.selected_pane.1 == 0 {
                         self.store.selected_pane.1 = max_pane - 1;
                     } else {
@@ -325,6 +1334,15 @@ impl<'a> App<'a> {
                         return Ok(EventState::Consumed);
                     }
 
+                    let open = self.open_transactions();
+                    if !open.is_empty() && !self.quit_warned {
+                        self.quit_warned = true;
+                        self.store.error(&format!(
+                            "A transaction is open on {}, press q again to quit and roll it back.",
+                            open.join(", ")
+                        ));
+                        return Ok(EventState::Consumed);
+                    }
                     self.store.exit = true;
                 }
                 Keys::Esc => {
@@ -333,34 +1351,175 @@ impl<'a> App<'a> {
                         return Ok(EventState::Consumed);
                     }
                 }
-                Keys::Char(':') => {
-                    if !self.store.is_lock {
-                        self.store.previous_selected_pane = self.store.selected_pane;
-                        self.store.selected_pane = (1, 3);
+                Keys::AltChar('l') if !self.popup_open() => {
+                    self.switch_workspace((self.active_workspace + 1) % self.workspaces.len());
+                }
+                Keys::AltChar('h') if !self.popup_open() => {
+                    let count = self.workspaces.len();
+                    self.switch_workspace((self.active_workspace + count - 1) % count);
+                }
+                Keys::AltChar('w') if !self.popup_open() => {
+                    if let Err(e) = self.close_workspace(self.active_workspace) {
+                        self.store.error(&e.to_string());
+                    }
+                }
+                Keys::AltChar('t') if !self.popup_open() => {
+                    if let Err(e) = self.toggle_transaction() {
+                        self.store.error(&e.to_string());
+                    }
+                }
+                Keys::AltChar(c @ ('c' | 'r')) if !self.popup_open() => {
+                    if let Err(e) = self.end_transaction(*c == 'c') {
+                        self.store.error(&e.to_string());
+                    }
+                }
+                Keys::AltChar(c @ '1'..='9') if !self.popup_open() => {
+                    self.switch_workspace(*c as usize - '1' as usize);
+                }
+                Keys::AltChar('z') => {
+                    self.store.zoomed = !self.store.zoomed;
+                }
+                Keys::AltChar(c @ ('.' | ',' | '=' | '-')) => {
+                    let (direction, delta) = match c {
+                        '.' => (Direction::Horizontal, RESIZE_STEP),
+                        ',' => (Direction::Horizontal, -RESIZE_STEP),
+                        '=' => (Direction::Vertical, RESIZE_STEP),
+                        _ => (Direction::Vertical, -RESIZE_STEP),
+                    };
+                    if let Some(layout) = self.layout.as_ref().filter(|l| !l.zoomed) {
+                        let pane = self.store.selected_pane;
+                        let pane_sizes = &mut self.store.pane_sizes;
+                        pane_sizes.resize(pane, layout, direction, delta);
+                        // keys coming faster than the frames resize from
+                        // where the last one left the panes
+                        self.layout = Some(LayoutArea::new(layout.area(), pane_sizes, None));
                     }
                 }
+                Keys::AltChar('o') if !self.popup_open() => {
+                    self.store.previous_selected_pane = self.store.selected_pane;
+                    self.store.selected_pane = (1, 3);
+                    self.command.open_library(&mut self.store);
+                }
+                Keys::Char('?') => {
+                    if self.store.selected_pane != (100, 100) {
+                        self.open_help();
+                    }
+                }
+                Keys::Char(':') if !self.popup_open() => {
+                    self.store.previous_selected_pane = self.store.selected_pane;
+                    self.store.selected_pane = (1, 3);
+                    self.command.start_ex();
+                }
                 _ => return Ok(EventState::Wasted),
             }
         }
         Ok(EventState::Consumed)
     }
 
-    fn help_view_text(selected_pane: (u8, u8)) -> Option<HashMap<&'static str, &'static str>> {
-        match selected_pane {
-            (0, 0) => return Some(ConnectionListComponent::help_content_text()),
-            (0, 1) => return Some(DatabaseListComponent::help_content_text()),
-            _ => return None,
+    /// A popup, a form or the help takes the keys, the workspaces and panes
+    /// behind it stay as they are.
+    fn popup_open(&self) -> bool {
+        self.parameter_form.is_some()
+            || self.confirm_popup.is_some()
+            || self.data_diff.is_some()
+            || self.schema_diff.is_some()
+            || self.store.is_lock
+            || self.store.selected_pane == (100, 100)
+    }
+
+    /// Keymap section of the comparison shown or of the selected pane, none
+    /// while a popup takes the keys as they are typed.
+    fn scope(&self) -> Option<Scope> {
+        if let Some(data_diff) = &self.data_diff {
+            return data_diff.keymap_scope();
+        }
+        if let Some(schema_diff) = &self.schema_diff {
+            return schema_diff.keymap_scope();
+        }
+        if self.store.selected_pane == (1, 3) {
+            return self.command.keymap_scope();
+        }
+        if self.store.is_lock {
+            return None;
+        }
+        match self.store.selected_pane {
+            (0, 0) => Some(Scope::Connections),
+            (0, 1) => Some(Scope::Databases),
+            (0, 2) => Some(Scope::Tables),
+            (1, 0) => Some(Scope::Tabs),
+            (1, 1) => match self.tab.selected() {
+                Tab::History => Some(Scope::History),
+                _ => Some(Scope::Records),
+            },
+            (1, 2) => Some(Scope::Log),
+            (100, 100) => Some(Scope::Help),
+            _ => None,
+        }
+    }
+
+    /// Key the panes handle for what `key` is bound to, none while a sequence
+    /// is being typed or for a key the pane handles but which was bound
+    /// elsewhere. Keys bound to nothing go through as they are.
+    fn translate(&mut self, scope: Scope, key: Keys) -> Option<Keys> {
+        self.pending_keys.push(key);
+        match self.keymap.resolve(scope, &self.pending_keys) {
+            Resolution::Action(action) => {
+                self.pending_keys.clear();
+                Some(action.key(scope))
+            }
+            Resolution::Pending => None,
+            // the sequence is given up, the key may start another one
+            Resolution::Unbound if self.pending_keys.len() > 1 => {
+                self.pending_keys.clear();
+                self.translate(scope, key)
+            }
+            Resolution::Unbound => {
+                self.pending_keys.clear();
+                (!self.keymap.handles(scope, key)).then_some(key)
+            }
+        }
+    }
+
+    /// Show the keys of the selected pane.
+    fn open_help(&mut self) {
+        let Some(scope) = self.scope() else {
+            return;
+        };
+        self.help_view = HelpViewComponent::new(0, scope.title().into(), self.keymap.help(scope));
+        self.store.previous_selected_pane = self.store.selected_pane;
+        self.store.selected_pane = (100, 100);
+    }
+
+    /// Panes Ctrl-j and Ctrl-k go through in `column`, the log being left
+    /// out while hidden.
+    fn max_pane(&self, column: u8) -> u8 {
+        let max_pane = self.max_pane_column[column as usize];
+        if column == 1 && !self.store.pane_sizes.show_log {
+            max_pane - 1
+        } else {
+            max_pane
+        }
+    }
+
+    /// Pane over the whole screen when zoomed, the one the command pane or
+    /// the help were opened from while they are.
+    fn zoomed_pane(&self) -> Option<(u8, u8)> {
+        if !self.store.zoomed {
+            return None;
+        }
+        match self.store.selected_pane {
+            (1, 3) | (100, 100) => match self.store.previous_selected_pane {
+                (1, 3) | (100, 100) => Some((1, 1)),
+                pane => Some(pane),
+            },
+            pane => Some(pane),
         }
     }
 
     fn verify_space_available(&mut self, frame: &mut Frame) -> Option<Paragraph> {
         let size = frame.size();
         if size.width <= 50 || size.height <= 21 {
-            let color = Color::Rgb(
-                self.store.preference.theme_config.unselected_color[0],
-                self.store.preference.theme_config.unselected_color[1],
-                self.store.preference.theme_config.unselected_color[2],
-            );
+            let color = color::from_rgb(self.store.preference.theme_config.unselected_color);
             let not_enough_space = Paragraph::default().block(
                 Block::default()
                     .title("Not enough space to render")
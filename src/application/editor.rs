@@ -0,0 +1,77 @@
+use super::Store;
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use std::{
+    env, fs,
+    io::{stdout, Write},
+    process::Command,
+};
+
+/// Program the user edits text with, `$VISUAL` before `$EDITOR`, split in
+/// words so `code --wait` works too.
+fn editor() -> anyhow::Result<Vec<String>> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .map(|editor| {
+            editor
+                .split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+        .find(|words| !words.is_empty())
+        .ok_or(anyhow::anyhow!("Neither $VISUAL nor $EDITOR is set."))
+}
+
+/// Open `text` in the editor of the user in a temp file ending in
+/// `extension`, and give back the text saved. The TUI is suspended until
+/// the editor exits.
+pub fn edit(text: &str, extension: &str, store: &mut Store) -> anyhow::Result<String> {
+    let editor = editor()?;
+    // a new file of a random name only the user can read, removed once
+    // dropped
+    let mut file = tempfile::Builder::new()
+        .prefix("dbv-")
+        .suffix(&format!(".{}", extension))
+        .tempfile()?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+    let path = file.path().to_path_buf();
+
+    store.event_handler.pause();
+    if store.preference.mouse {
+        stdout().execute(DisableMouseCapture)?;
+    }
+    stdout().execute(LeaveAlternateScreen)?;
+    disable_raw_mode()?;
+    let status = Command::new(&editor[0])
+        .args(&editor[1..])
+        .arg(&path)
+        .status();
+    enable_raw_mode()?;
+    stdout().execute(EnterAlternateScreen)?;
+    if store.preference.mouse {
+        stdout().execute(EnableMouseCapture)?;
+    }
+    store.event_handler.resume();
+    store.redraw = true;
+
+    let mut edited = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path)?,
+        Ok(status) => {
+            anyhow::bail!("{} exited with {}, nothing was changed.", editor[0], status);
+        }
+        Err(e) => anyhow::bail!("Unable to start {} : {}", editor[0], e),
+    };
+    // editors end the last line, the text may not have
+    if !text.ends_with('\n') && edited.ends_with('\n') {
+        edited.pop();
+        if edited.ends_with('\r') {
+            edited.pop();
+        }
+    }
+    Ok(edited)
+}
@@ -0,0 +1,187 @@
+use super::{export::ExportFormat, preferences::ThemeConfig, Store};
+use crate::sql::completion::{Completion, CompletionKind};
+use std::str::FromStr;
+
+/// Option changed by `:set`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    PageSize,
+    /// Show the log pane, `on` or `off`.
+    Log,
+    /// Show the line of the keys under the panes.
+    HelpLine,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 3] = [Setting::PageSize, Setting::Log, Setting::HelpLine];
+
+    fn value(&self) -> &'static str {
+        match self {
+            Setting::PageSize => "pagesize",
+            Setting::Log => "log",
+            Setting::HelpLine => "helpline",
+        }
+    }
+}
+
+impl std::fmt::Display for Setting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+impl FromStr for Setting {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Setting::ALL
+            .into_iter()
+            .find(|setting| setting.value() == s)
+            .ok_or(anyhow::anyhow!("Unknown option : {}", s))
+    }
+}
+
+/// A command typed after `:` in the command pane, in place of SQL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExCommand {
+    /// Open the connection named so.
+    Connect(String),
+    /// Make the database the current one.
+    Use(String),
+    /// Show the records of the table.
+    Open(String),
+    /// Write the records shown to a file.
+    Export(ExportFormat, String),
+    Set(Setting, String),
+    Theme(String),
+    /// Save the query of the command pane, under the name if one is given.
+    Write(Option<String>),
+    /// Quit, rolling back the open transactions when forced with `:q!`.
+    Quit(bool),
+}
+
+/// Commands with the arguments they take.
+const COMMANDS: [(&str, &str); 8] = [
+    ("connect", "<connection>"),
+    ("use", "<database>"),
+    ("open", "<table>"),
+    ("export", "<csv|json> <path>"),
+    ("set", "<option> <value>"),
+    ("theme", "<name>"),
+    ("w", "[name]"),
+    ("q", ""),
+];
+
+fn usage(command: &str) -> anyhow::Error {
+    let arguments = COMMANDS
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map_or("", |(_, arguments)| arguments);
+    anyhow::anyhow!("Usage : :{} {}", command, arguments)
+}
+
+impl FromStr for ExCommand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = s.trim().trim_start_matches(':').trim_start();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        // names may hold spaces, the whole rest of the line is taken
+        let name = || {
+            if rest.is_empty() {
+                Err(usage(command))
+            } else {
+                Ok(rest.to_string())
+            }
+        };
+        match command {
+            "connect" => Ok(ExCommand::Connect(name()?)),
+            "use" => Ok(ExCommand::Use(name()?)),
+            "open" => Ok(ExCommand::Open(name()?)),
+            "export" => {
+                let (format, path) = rest
+                    .split_once(char::is_whitespace)
+                    .ok_or_else(|| usage(command))?;
+                Ok(ExCommand::Export(format.parse()?, path.trim().to_string()))
+            }
+            "set" => {
+                // `:set pagesize 500` or `:set pagesize=500`
+                let (setting, value) = rest
+                    .split_once(|c: char| c.is_whitespace() || c == '=')
+                    .ok_or_else(|| usage(command))?;
+                Ok(ExCommand::Set(setting.parse()?, value.trim().to_string()))
+            }
+            "theme" => Ok(ExCommand::Theme(name()?)),
+            "w" | "write" => Ok(ExCommand::Write(
+                (!rest.is_empty()).then(|| rest.to_string()),
+            )),
+            "q" | "quit" => Ok(ExCommand::Quit(false)),
+            "q!" | "quit!" => Ok(ExCommand::Quit(true)),
+            "" => anyhow::bail!("No command was given after :"),
+            _ => anyhow::bail!("Unknown command : {}", command),
+        }
+    }
+}
+
+/// What may be typed at `cursor`, a byte in the `:` line, with the byte the
+/// word being typed starts at. Commands come first, then the connections,
+/// databases, tables, options or themes they take.
+pub fn complete(line: &str, cursor: usize, store: &Store) -> (usize, Vec<Completion>) {
+    let before = &line[..cursor];
+    let Some(after_colon) = before.strip_prefix(':') else {
+        return (cursor, Vec::new());
+    };
+    let candidates = |prefix: &str, names: Vec<String>, kind: CompletionKind| {
+        names
+            .into_iter()
+            .filter(|name| {
+                name.len() > prefix.len()
+                    && name
+                        .get(..prefix.len())
+                        .is_some_and(|n| n.eq_ignore_ascii_case(prefix))
+            })
+            .map(|text| Completion { text, kind })
+            .collect::<Vec<_>>()
+    };
+
+    let Some((command, rest)) = after_colon.split_once(char::is_whitespace) else {
+        let names = COMMANDS.iter().map(|(name, _)| name.to_string()).collect();
+        return (1, candidates(after_colon, names, CompletionKind::Command));
+    };
+    let argument = rest.trim_start();
+    let argument_start = cursor - argument.len();
+    let names = match command {
+        "connect" => Some((
+            store
+                .user_data
+                .connection_list
+                .list
+                .iter()
+                .map(|c| c.name.clone())
+                .collect(),
+            CompletionKind::Connection,
+        )),
+        "use" => Some((store.database_list.list.clone(), CompletionKind::Database)),
+        "open" => Some((store.tables_list.clone(), CompletionKind::Table)),
+        "theme" => Some((ThemeConfig::names(), CompletionKind::Theme)),
+        _ => None,
+    };
+    if let Some((names, kind)) = names {
+        return (argument_start, candidates(argument, names, kind));
+    }
+
+    // the first argument only, the value or the path are up to the user
+    if argument.contains(char::is_whitespace) || argument.contains('=') {
+        return (cursor, Vec::new());
+    }
+    let names = match command {
+        "export" => ExportFormat::ALL.iter().map(|f| f.to_string()).collect(),
+        "set" => Setting::ALL.iter().map(|s| s.to_string()).collect(),
+        _ => Vec::new(),
+    };
+    (
+        argument_start,
+        candidates(argument, names, CompletionKind::Option),
+    )
+}
@@ -0,0 +1,211 @@
+use crate::sql::value::Value;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{Map, Number};
+use std::{env, fs, path::PathBuf, str::FromStr};
+
+/// File format records are exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 2] = [ExportFormat::Csv, ExportFormat::Json];
+
+    fn value(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ExportFormat::ALL
+            .into_iter()
+            .find(|format| format.value() == s.to_lowercase())
+            .ok_or(anyhow::anyhow!("Unknown export format : {}", s))
+    }
+}
+
+/// Field quoted when it holds a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Cell of a csv line, empty for NULL and base64 for binary values.
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(b) => b.to_string(),
+        Value::Int(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Text(text) => csv_field(text),
+        Value::Bytes(bytes) => STANDARD.encode(bytes),
+    }
+}
+
+/// Value of a json object, binary values written in base64 and the floats
+/// json has no number for as strings.
+fn json_value(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::Int(i) => serde_json::Value::from(*i),
+        Value::Float(f) => Number::from_f64(*f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::String(f.to_string())),
+        Value::Text(text) => serde_json::Value::String(text.clone()),
+        Value::Bytes(bytes) => serde_json::Value::String(STANDARD.encode(bytes)),
+    }
+}
+
+/// Keys of the json objects, a name given to several columns suffixed with
+/// `_2`, `_3`... from its second one so no column is lost.
+fn json_keys(header: &[String]) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for name in header {
+        let mut key = name.clone();
+        let mut n = 1;
+        while keys.contains(&key) {
+            n += 1;
+            key = format!("{}_{}", name, n);
+        }
+        keys.push(key);
+    }
+    keys
+}
+
+/// Write the records to `path`, `~/` standing for the home folder, and give
+/// back the path written.
+pub fn export(
+    format: ExportFormat,
+    path: &str,
+    header: &[String],
+    rows: &[Vec<Value>],
+) -> anyhow::Result<PathBuf> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => PathBuf::from(env::var("HOME")?).join(rest),
+        None => PathBuf::from(path),
+    };
+    let content = match format {
+        ExportFormat::Csv => {
+            let mut content = String::new();
+            let header = header.iter().map(|f| csv_field(f)).collect::<Vec<_>>();
+            let lines = rows
+                .iter()
+                .map(|row| row.iter().map(csv_value).collect::<Vec<_>>());
+            for line in std::iter::once(header).chain(lines) {
+                content.push_str(&line.join(","));
+                content.push_str("\r\n");
+            }
+            content
+        }
+        ExportFormat::Json => {
+            let keys = json_keys(header);
+            let objects = rows
+                .iter()
+                .map(|row| {
+                    serde_json::Value::Object(
+                        keys.iter()
+                            .cloned()
+                            .zip(row.iter().map(json_value))
+                            .collect::<Map<_, _>>(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            serde_json::to_string_pretty(&objects)?
+        }
+    };
+    fs::write(&path, content)
+        .map_err(|e| anyhow::anyhow!("Unable to write {} : {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> (Vec<String>, Vec<Vec<Value>>) {
+        let header = ["id", "name", "id", "data", "ratio"]
+            .map(String::from)
+            .to_vec();
+        let rows = vec![
+            vec![
+                Value::Int(1),
+                Value::Text("a, \"b\"".to_string()),
+                Value::Bool(true),
+                Value::Bytes(vec![0, 255]),
+                Value::Float(0.5),
+            ],
+            vec![
+                Value::Int(2),
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                Value::Float(f64::NAN),
+            ],
+        ];
+        (header, rows)
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn duplicate_columns_get_their_own_key() {
+        let header = ["id", "id", "id_2", "id"].map(String::from);
+        assert_eq!(json_keys(&header), ["id", "id_2", "id_2_2", "id_3"]);
+    }
+
+    #[test]
+    fn exports_typed_values() {
+        let (header, rows) = records();
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("out.csv");
+        export(ExportFormat::Csv, path.to_str().unwrap(), &header, &rows).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "id,name,id,data,ratio\r\n1,\"a, \"\"b\"\"\",true,AP8=,0.5\r\n2,,,,NaN\r\n"
+        );
+
+        let path = dir.path().join("out.json");
+        export(ExportFormat::Json, path.to_str().unwrap(), &header, &rows).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"id": 1, "name": "a, \"b\"", "id_2": true, "data": "AP8=", "ratio": 0.5},
+                {"id": 2, "name": null, "id_2": null, "data": null, "ratio": "NaN"},
+            ])
+        );
+    }
+
+    #[test]
+    fn unknown_formats_are_refused() {
+        assert_eq!("JSON".parse::<ExportFormat>().unwrap(), ExportFormat::Json);
+        assert!("xml".parse::<ExportFormat>().is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+const LIBRARY_FILENAME: &str = "queries.json";
+
+/// A query kept to be run again, by anyone or on one connection only.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedQuery {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub folder: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Id of the connection the query belongs to, `None` for a global one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<String>,
+    pub sql: String,
+}
+
+impl SavedQuery {
+    /// `folder/name`, or the name alone outside of any folder.
+    pub fn path(&self) -> String {
+        if self.folder.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}/{}", self.folder, self.name)
+        }
+    }
+
+    /// Text the search is made on.
+    pub fn search_text(&self) -> String {
+        let mut text = self.path();
+        for tag in &self.tags {
+            text.push_str(&format!(" #{}", tag));
+        }
+        text
+    }
+
+    /// Whether both queries would be listed under the same name.
+    fn same_place(&self, other: &SavedQuery) -> bool {
+        self.name == other.name
+            && self.folder == other.folder
+            && self.connection == other.connection
+    }
+}
+
+/// Queries saved from the command pane.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryLibrary {
+    #[serde(default)]
+    pub queries: Vec<SavedQuery>,
+}
+
+impl QueryLibrary {
+    /// Indices of the queries which may be run on the connection, the global
+    /// ones included, sorted by folder and name.
+    pub fn visible(&self, connection: Option<&str>) -> Vec<usize> {
+        let mut indices = self
+            .queries
+            .iter()
+            .enumerate()
+            .filter(|(_, q)| q.connection.is_none() || q.connection.as_deref() == connection)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        indices.sort_by_key(|&i| (&self.queries[i].folder, &self.queries[i].name));
+        indices
+    }
+
+    /// Add the query, replacing the one saved under the same name. Tells
+    /// whether one was replaced.
+    pub fn upsert(&mut self, query: SavedQuery) -> bool {
+        match self.queries.iter_mut().find(|q| q.same_place(&query)) {
+            Some(existing) => {
+                *existing = query;
+                true
+            }
+            None => {
+                self.queries.push(query);
+                false
+            }
+        }
+    }
+
+    pub fn rename(&mut self, index: usize, name: &str) -> anyhow::Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("A saved query needs a name.");
+        }
+        let renamed = SavedQuery {
+            name: name.to_string(),
+            ..self.queries[index].clone()
+        };
+        if self
+            .queries
+            .iter()
+            .enumerate()
+            .any(|(i, q)| i != index && q.same_place(&renamed))
+        {
+            anyhow::bail!("A query named {} already exists.", renamed.path());
+        }
+        self.queries[index] = renamed;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> SavedQuery {
+        self.queries.remove(index)
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        if !Path::new(&utils::get_path_app_folder()).exists() {
+            anyhow::bail!("$HOME/.config/dbv/ doesn't exist.");
+        }
+        fs::write(
+            utils::get_path_app_file(LIBRARY_FILENAME),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    pub fn load() -> anyhow::Result<Self> {
+        let filepath = utils::get_path_app_file(LIBRARY_FILENAME);
+        if !Path::new(&filepath).exists() {
+            return Ok(QueryLibrary::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(&filepath)?)?)
+    }
+}
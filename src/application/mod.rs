@@ -1,7 +1,15 @@
 pub mod app;
 pub mod store;
+pub mod editor;
+pub mod ex_command;
+pub mod export;
+pub mod library;
+pub mod parameters;
 pub mod preferences;
+pub mod session;
 pub mod user_data;
+pub mod vault;
+pub mod workspace;
 
 pub use app::{App, UpdateAction, AppAction};
 pub use store::{Store, StoreAction};
@@ -0,0 +1,91 @@
+use crate::{sql::parameters::ParameterType, utils};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+const PARAMETERS_FILENAME: &str = "parameters.json";
+
+/// Value last given to a parameter, offered again the next time it is asked.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RememberedParameter {
+    #[serde(default)]
+    pub value: String,
+    #[serde(default, rename = "type")]
+    pub kind: ParameterType,
+    /// Whether the value is kept out of the file and asked each time.
+    #[serde(default)]
+    pub secret: bool,
+}
+
+/// Values last given to the parameters of queries, by name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParameterHistory {
+    #[serde(default)]
+    pub parameters: BTreeMap<String, RememberedParameter>,
+}
+
+impl ParameterHistory {
+    pub fn get(&self, name: &str) -> Option<&RememberedParameter> {
+        self.parameters.get(name)
+    }
+
+    pub fn remember(&mut self, name: String, value: String, kind: ParameterType) {
+        let parameter = RememberedParameter {
+            value,
+            kind,
+            secret: false,
+        };
+        self.parameters.insert(name, parameter);
+    }
+
+    /// Keep only the type of the parameter, its value being asked again the
+    /// next time.
+    pub fn forget(&mut self, name: String, kind: ParameterType) {
+        let parameter = RememberedParameter {
+            value: String::new(),
+            kind,
+            secret: true,
+        };
+        self.parameters.insert(name, parameter);
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        if !Path::new(&utils::get_path_app_folder()).exists() {
+            anyhow::bail!("$HOME/.config/dbv/ doesn't exist.");
+        }
+        fs::write(
+            utils::get_path_app_file(PARAMETERS_FILENAME),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    pub fn load() -> anyhow::Result<Self> {
+        let filepath = utils::get_path_app_file(PARAMETERS_FILENAME);
+        if !Path::new(&filepath).exists() {
+            return Ok(ParameterHistory::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(&filepath)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_values_are_not_saved() {
+        let mut history: ParameterHistory =
+            serde_json::from_str(r#"{"parameters": {":id": {"value": "1", "type": "integer"}}}"#)
+                .unwrap();
+        assert!(!history.get(":id").unwrap().secret);
+
+        history.remember(":name".to_string(), "ann".to_string(), ParameterType::Text);
+        history.forget(":password".to_string(), ParameterType::Text);
+        let saved = serde_json::to_string(&history).unwrap();
+        let history: ParameterHistory = serde_json::from_str(&saved).unwrap();
+        assert_eq!(history.get(":name").unwrap().value, "ann");
+        let password = history.get(":password").unwrap();
+        assert!(password.secret);
+        assert_eq!(password.value, "");
+    }
+}
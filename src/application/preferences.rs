@@ -1,37 +1,88 @@
-use crate::{log::LogLevel, utils};
+use crate::{components::layout::PaneSizes, log::LogLevel, utils};
 use serde::{Deserialize, Serialize};
-use std::{fs, path};
+use std::{collections::BTreeMap, fs, path};
 
 const CONFIG_FILENAME: &'static str = "dbv.json";
+const THEMES_FOLDER: &str = "themes";
 
 type RGB = [u8; 3];
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preference {
-    #[serde(rename = "theme")]
+    /// The name of a theme, or its colors.
+    #[serde(rename = "theme", deserialize_with = "theme_or_name")]
     pub theme_config: ThemeConfig,
 
+    #[serde(rename = "colorDepth", default)]
+    pub color_depth: ColorDepth,
+
     #[serde(rename = "logLevel")]
     pub log_level: LogLevel,
+
+    /// Reopen the last connection, database and table on startup.
+    #[serde(rename = "restoreSession", default = "default_restore_session")]
+    pub restore_session: bool,
+
+    /// Rows read when the records of a table are shown.
+    #[serde(rename = "pageSize", default = "default_page_size")]
+    pub page_size: u32,
+
+    /// Cells a column of the records takes at most, however long its values.
+    #[serde(rename = "maxColumnWidth", default = "default_max_column_width")]
+    pub max_column_width: u16,
+
+    /// Click to select and focus, scroll with the wheel and drag the borders
+    /// of the panes. The terminal no longer selects text then.
+    #[serde(default)]
+    pub mouse: bool,
+
+    /// Space given to the panes and the ones shown, the session keeping
+    /// them as they were resized.
+    #[serde(default)]
+    pub layout: PaneSizes,
+
+    /// Keys bound to actions, by pane then action, e.g.
+    /// `{ "records": { "first": ["gg", "<Home>"] } }`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keymap: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+fn default_restore_session() -> bool {
+    true
+}
+
+fn default_page_size() -> u32 {
+    100
+}
+
+fn default_max_column_width() -> u16 {
+    40
 }
 
 impl Preference {
     pub fn default() -> Self {
         Preference {
             theme_config: ThemeConfig::default(),
+            color_depth: ColorDepth::default(),
             log_level: LogLevel::default(),
+            restore_session: default_restore_session(),
+            page_size: default_page_size(),
+            max_column_width: default_max_column_width(),
+            mouse: false,
+            layout: PaneSizes::default(),
+            keymap: BTreeMap::new(),
         }
     }
     pub fn load(&self) -> Self {
         let file_content = if let Ok(content) = fs::read_to_string(utils::get_path_app_file(CONFIG_FILENAME)) {
             content
         } else {
-            return *self;
+            return self.clone();
         };
         let value: Preference = if let Ok(content) = serde_json::from_str(&file_content) {
             content
         } else {
-            *self
+            self.clone()
         };
         value
     }
@@ -52,34 +103,287 @@ impl Preference {
     }
 }
 
+/// Colors of dbv. A theme file may give some of them only, the others
+/// being the ones of the dark preset.
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ThemeConfig {
+    /// Borders of the selected pane.
     #[serde(rename = "selectedColor")]
     pub selected_color: RGB,
+    /// Borders of the other panes, and text.
     #[serde(rename = "unselectedColor")]
     pub unselected_color: RGB,
+    /// Status bar and hints.
     #[serde(rename = "helpTextColor")]
     pub help_text_color: RGB,
 
     #[serde(rename = "helpKeyColor")]
     pub help_key_color: RGB,
     #[serde(rename = "helpDescColor")]
-    pub help_desc_color: RGB
+    pub help_desc_color: RGB,
+
+    /// Item or cell under the cursor, the text then the background.
+    #[serde(rename = "selectionColor")]
+    pub selection_color: RGB,
+    #[serde(rename = "selectionBackgroundColor")]
+    pub selection_background_color: RGB,
+    #[serde(rename = "activeTabColor")]
+    pub active_tab_color: RGB,
+
+    /// Values shown in the records.
+    #[serde(rename = "headerColor")]
+    pub header_color: RGB,
+    #[serde(rename = "nullColor")]
+    pub null_color: RGB,
+    #[serde(rename = "numberColor")]
+    pub number_color: RGB,
+    #[serde(rename = "stringColor")]
+    pub string_color: RGB,
+
+    #[serde(rename = "logInfoColor")]
+    pub log_info_color: RGB,
+    #[serde(rename = "logSuccessColor")]
+    pub log_success_color: RGB,
+    #[serde(rename = "logErrorColor")]
+    pub log_error_color: RGB,
+    #[serde(rename = "logDebugColor")]
+    pub log_debug_color: RGB,
+    /// Open transactions and rows changed between two tables.
+    #[serde(rename = "warningColor")]
+    pub warning_color: RGB,
+
+    pub syntax: SyntaxTheme,
+}
+
+/// Themes dbv comes with, `default` being the dark one.
+const PRESETS: [&str; 4] = ["dark", "light", "solarized", "high-contrast"];
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        ThemeConfig {
+            selected_color: [255, 165, 0],
+            unselected_color: [255, 236, 195],
+            help_text_color: [93, 169, 233],
+            help_key_color: [100, 149, 237],
+            help_desc_color: [255, 236, 195],
+            selection_color: [40, 40, 40],
+            selection_background_color: [255, 236, 195],
+            active_tab_color: [100, 149, 237],
+            header_color: [255, 236, 195],
+            null_color: [128, 128, 128],
+            number_color: [209, 154, 102],
+            string_color: [255, 236, 195],
+            log_info_color: [93, 169, 233],
+            log_success_color: [152, 195, 121],
+            log_error_color: [224, 108, 117],
+            log_debug_color: [128, 128, 128],
+            warning_color: [229, 192, 123],
+            syntax: SyntaxTheme::default(),
+        }
+    }
 }
 
 impl ThemeConfig {
-    pub fn default() -> Self {
-        let selected_color = [255, 165, 0];
-        let unselected_color = [255, 236, 195];
-        let help_text_color = [93, 169, 233];
-        let help_key_color = [100,149,237];
-        let help_desc_color = [255, 236, 195];
+    fn light() -> Self {
         ThemeConfig {
-            selected_color,
-            unselected_color,
-            help_text_color,
-            help_key_color,
-            help_desc_color,
+            selected_color: [215, 95, 0],
+            unselected_color: [68, 68, 68],
+            help_text_color: [0, 95, 175],
+            help_key_color: [0, 95, 175],
+            help_desc_color: [68, 68, 68],
+            selection_color: [255, 255, 255],
+            selection_background_color: [0, 95, 175],
+            active_tab_color: [0, 95, 175],
+            header_color: [0, 0, 0],
+            null_color: [148, 148, 148],
+            number_color: [175, 95, 0],
+            string_color: [68, 68, 68],
+            log_info_color: [0, 95, 175],
+            log_success_color: [0, 135, 0],
+            log_error_color: [175, 0, 0],
+            log_debug_color: [128, 128, 128],
+            warning_color: [175, 135, 0],
+            syntax: SyntaxTheme {
+                keyword_color: [0, 0, 175],
+                identifier_color: [68, 68, 68],
+                string_color: [0, 135, 0],
+                number_color: [175, 95, 0],
+                comment_color: [128, 128, 128],
+                operator_color: [135, 0, 135],
+                parameter_color: [175, 135, 0],
+                error_color: [175, 0, 0],
+            },
+        }
+    }
+
+    fn solarized() -> Self {
+        ThemeConfig {
+            selected_color: [181, 137, 0],
+            unselected_color: [131, 148, 150],
+            help_text_color: [42, 161, 152],
+            help_key_color: [38, 139, 210],
+            help_desc_color: [131, 148, 150],
+            selection_color: [147, 161, 161],
+            selection_background_color: [7, 54, 66],
+            active_tab_color: [38, 139, 210],
+            header_color: [147, 161, 161],
+            null_color: [88, 110, 117],
+            number_color: [211, 54, 130],
+            string_color: [131, 148, 150],
+            log_info_color: [38, 139, 210],
+            log_success_color: [133, 153, 0],
+            log_error_color: [220, 50, 47],
+            log_debug_color: [88, 110, 117],
+            warning_color: [181, 137, 0],
+            syntax: SyntaxTheme {
+                keyword_color: [133, 153, 0],
+                identifier_color: [131, 148, 150],
+                string_color: [42, 161, 152],
+                number_color: [211, 54, 130],
+                comment_color: [88, 110, 117],
+                operator_color: [203, 75, 22],
+                parameter_color: [108, 113, 196],
+                error_color: [220, 50, 47],
+            },
+        }
+    }
+
+    fn high_contrast() -> Self {
+        ThemeConfig {
+            selected_color: [255, 255, 0],
+            unselected_color: [255, 255, 255],
+            help_text_color: [0, 255, 255],
+            help_key_color: [0, 255, 255],
+            help_desc_color: [255, 255, 255],
+            selection_color: [0, 0, 0],
+            selection_background_color: [255, 255, 0],
+            active_tab_color: [255, 255, 0],
+            header_color: [255, 255, 255],
+            null_color: [192, 192, 192],
+            number_color: [0, 255, 255],
+            string_color: [255, 255, 255],
+            log_info_color: [0, 255, 255],
+            log_success_color: [0, 255, 0],
+            log_error_color: [255, 0, 0],
+            log_debug_color: [192, 192, 192],
+            warning_color: [255, 255, 0],
+            syntax: SyntaxTheme {
+                keyword_color: [0, 255, 255],
+                identifier_color: [255, 255, 255],
+                string_color: [0, 255, 0],
+                number_color: [255, 0, 255],
+                comment_color: [192, 192, 192],
+                operator_color: [255, 255, 0],
+                parameter_color: [255, 128, 0],
+                error_color: [255, 0, 0],
+            },
+        }
+    }
+
+    /// One of the presets, or the theme saved as `themes/<name>.json` in the
+    /// config folder.
+    pub fn load(name: &str) -> anyhow::Result<Self> {
+        match name {
+            "default" | "dark" => return Ok(ThemeConfig::default()),
+            "light" => return Ok(ThemeConfig::light()),
+            "solarized" => return Ok(ThemeConfig::solarized()),
+            "high-contrast" => return Ok(ThemeConfig::high_contrast()),
+            _ => {}
+        }
+        let filepath = utils::get_path_app_file(&format!("{}/{}.json", THEMES_FOLDER, name));
+        let content =
+            fs::read_to_string(filepath).map_err(|_| anyhow::anyhow!("Unknown theme : {}", name))?;
+        serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Unable to read the theme {} : {}", name, e))
+    }
+
+    /// Names of the themes which may be loaded, the presets first.
+    pub fn names() -> Vec<String> {
+        let mut names = fs::read_dir(utils::get_path_app_file(THEMES_FOLDER))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "json"))
+            .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .filter(|name| !PRESETS.contains(&name.as_str()))
+            .collect::<Vec<_>>();
+        names.sort();
+        names.splice(0..0, PRESETS.iter().map(|name| name.to_string()));
+        names
+    }
+}
+
+/// `"theme"` of dbv.json, the name of a theme or the colors themselves.
+fn theme_or_name<'de, D>(deserializer: D) -> Result<ThemeConfig, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Theme {
+        Name(String),
+        Colors(ThemeConfig),
+    }
+    match Theme::deserialize(deserializer)? {
+        Theme::Name(name) => ThemeConfig::load(&name).map_err(serde::de::Error::custom),
+        Theme::Colors(theme) => Ok(theme),
+    }
+}
+
+/// Colors the terminal is told to show, `auto` looking at `COLORTERM` and
+/// `TERM`. Truecolor ones are brought to the nearest of the 256 or 16 colors.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ColorDepth {
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16,
+    /// Set by `NO_COLOR` too.
+    #[serde(rename = "none")]
+    NoColor,
+}
+
+/// Colors of the pieces of SQL statements.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyntaxTheme {
+    #[serde(rename = "keywordColor")]
+    pub keyword_color: RGB,
+    #[serde(rename = "identifierColor")]
+    pub identifier_color: RGB,
+    #[serde(rename = "stringColor")]
+    pub string_color: RGB,
+    #[serde(rename = "numberColor")]
+    pub number_color: RGB,
+    #[serde(rename = "commentColor")]
+    pub comment_color: RGB,
+    #[serde(rename = "operatorColor")]
+    pub operator_color: RGB,
+    #[serde(rename = "parameterColor")]
+    pub parameter_color: RGB,
+    /// Unterminated literals and unbalanced parentheses.
+    #[serde(rename = "errorColor")]
+    pub error_color: RGB,
+}
+
+impl Default for SyntaxTheme {
+    fn default() -> Self {
+        SyntaxTheme {
+            keyword_color: [100, 149, 237],
+            identifier_color: [255, 236, 195],
+            string_color: [152, 195, 121],
+            number_color: [209, 154, 102],
+            comment_color: [128, 128, 128],
+            operator_color: [198, 120, 221],
+            parameter_color: [229, 192, 123],
+            error_color: [224, 108, 117],
         }
     }
 }
@@ -0,0 +1,51 @@
+use crate::{components::layout::PaneSizes, utils};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+const SESSION_FILENAME: &str = "session.json";
+
+/// Where the user was when dbv was last closed, restored on the next launch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    /// Id of the connection of the workspace on screen.
+    #[serde(default)]
+    pub connection: Option<String>,
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub table: Option<String>,
+    #[serde(default)]
+    pub tab: usize,
+    #[serde(default)]
+    pub command: String,
+    #[serde(default, rename = "commandCursor")]
+    pub command_cursor: usize,
+    #[serde(default, rename = "selectedPane")]
+    pub selected_pane: (u8, u8),
+    /// Panes as they were resized, from the layout of the preferences then.
+    #[serde(default, rename = "paneSizes")]
+    pub pane_sizes: Option<PaneSizes>,
+    #[serde(default, rename = "preferredPaneSizes")]
+    pub preferred_pane_sizes: Option<PaneSizes>,
+}
+
+impl Session {
+    pub fn save(&self) -> anyhow::Result<()> {
+        if !Path::new(&utils::get_path_app_folder()).exists() {
+            anyhow::bail!("$HOME/.config/dbv/ doesn't exist.");
+        }
+        fs::write(
+            utils::get_path_app_file(SESSION_FILENAME),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    pub fn load() -> anyhow::Result<Option<Self>> {
+        let filepath = utils::get_path_app_file(SESSION_FILENAME);
+        if !Path::new(&filepath).exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(&filepath)?)?))
+    }
+}
@@ -1,6 +1,18 @@
-use super::{preferences::Preference, user_data::UserData, UpdateAction};
+use super::{
+    preferences::Preference,
+    user_data::{UserData, USER_DATA_VERSION},
+    vault::{Vault, VaultLocked},
+    AppAction, UpdateAction,
+};
+use crate::sql::{
+    completion::Catalog,
+    connection::PasswordSource,
+    health::{CheckOutcome, CheckStep, ConnectionStatus},
+    transaction::ManualTransaction,
+};
 use crate::{
-    components::LogContent, events::events::EventsHandling, sql::database::DatabaseList, utils,
+    components::{layout::PaneSizes, LogContent},
+    events::events::EventsHandling, sql::database::DatabaseList, utils,
 };
 use std::{fs, path::Path};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
@@ -11,22 +23,44 @@ const STORE_FILENAME: &'static str = "user_data.json";
 pub enum StoreAction {
     SendDatabaseData(Vec<String>),
     SendTablesData(Vec<String>),
+    /// Columns of the tables of the current database, for completion.
+    SendCatalog(Box<Catalog>),
 
     SendEditConnectionItem(usize),
+    SendEncryption(usize, Option<bool>),
+    SendCheckResult(String, CheckStep, CheckOutcome),
+    SendConnectionStatus(String, ConnectionStatus),
+    /// Statements changing data run in the manual transaction of a
+    /// connection.
+    SendTransactionPending(String, usize),
+    /// The manual transaction of a connection couldn't be kept open.
+    SendTransactionEnded(String),
 
     SendError(String),
+    SendSuccess(String),
+    /// A query failed, the connection is kept open.
+    SendQueryError(String),
 }
 
 pub struct Store<'a> {
     pub preference: Preference,
     pub user_data: UserData,
+    pub vault: Vault,
     pub event_handler: &'a EventsHandling,
     pub database_list: DatabaseList,
     pub tables_list: Vec<String>,
+    pub catalog: Option<Catalog>,
     pub exit: bool,
+    /// The screen was drawn over by another program, it is cleared before
+    /// the next frame.
+    pub redraw: bool,
     pub selected_pane: (u8, u8), //x,y
     pub previous_selected_pane: (u8, u8),
     pub is_lock: bool,
+    /// Space given to the panes, changed by dragging their borders.
+    pub pane_sizes: PaneSizes,
+    /// The selected pane takes the whole screen.
+    pub zoomed: bool,
 
     log_contents: Vec<LogContent>,
 
@@ -37,16 +71,22 @@ pub struct Store<'a> {
 impl<'a> Store<'a> {
     pub fn new(event_handler: &'a EventsHandling) -> Self {
         let (actions_tx, actions_rx) = unbounded_channel();
+        let preference = Preference::default().load();
         Store {
-            preference: Preference::default().load(),
+            pane_sizes: preference.layout,
+            preference,
             event_handler,
             user_data: UserData::new(),
+            vault: Vault::new(),
             database_list: DatabaseList::new(),
             tables_list: Vec::new(),
+            catalog: None,
             exit: false,
+            redraw: false,
             selected_pane: (0, 0),
             previous_selected_pane: (0, 0),
             is_lock: false,
+            zoomed: false,
             log_contents: Vec::new(),
             actions_tx,
             actions_rx,
@@ -58,6 +98,7 @@ impl<'a> Store<'a> {
     }
     pub fn reset_tables_list(&mut self) {
         self.tables_list = Vec::new();
+        self.catalog = None;
     }
 
     pub fn update(&mut self, action: StoreAction) {
@@ -73,15 +114,106 @@ impl<'a> Store<'a> {
                 self.tables_list = data;
                 self.selected_pane = (0, 2);
             }
+            // the database may have changed while it was read
+            StoreAction::SendCatalog(catalog)
+                if self.database_list.current() == Some(&catalog.database) =>
+            {
+                self.catalog = Some(*catalog);
+            }
 
+            StoreAction::SendEncryption(index, encrypted) => {
+                if let Some(connection) = self.user_data.connection_list.list.get_mut(index) {
+                    connection.encrypted = encrypted;
+                }
+                match encrypted {
+                    Some(true) => self.log("The session is encrypted."),
+                    Some(false) => self.log("The session is not encrypted."),
+                    None => self.log("Unable to tell whether the session is encrypted."),
+                }
+            }
+            StoreAction::SendCheckResult(name, step, outcome) => match outcome {
+                CheckOutcome::Passed(detail) => {
+                    self.success(&format!("{} - {} : {}", name, step, detail))
+                }
+                CheckOutcome::Failed(detail) => {
+                    self.error(&format!("{} - {} : {}", name, step, detail))
+                }
+                CheckOutcome::Skipped(detail) => {
+                    self.log(&format!("{} - {} : {}", name, step, detail))
+                }
+            },
+            StoreAction::SendConnectionStatus(id, status) => {
+                let Some(connection) = self
+                    .user_data
+                    .connection_list
+                    .list
+                    .iter_mut()
+                    .find(|c| c.id == id && c.pool.is_some())
+                else {
+                    return;
+                };
+                let previous = connection.status.replace(status);
+                let name = connection.name.clone();
+                match status {
+                    ConnectionStatus::Reconnecting { attempt, retry_in } => {
+                        self.error(&format!(
+                            "Lost the connection to {}, retrying in {}s (attempt {}).",
+                            name,
+                            retry_in.as_secs(),
+                            attempt
+                        ));
+                    }
+                    ConnectionStatus::Up(_) => {
+                        if let Some(ConnectionStatus::Reconnecting { .. }) = previous {
+                            self.success(&format!("Reconnected to {}.", name));
+                        }
+                    }
+                }
+            }
+            StoreAction::SendTransactionPending(id, pending) => {
+                if let Some(transaction) = self.transaction_of(&id) {
+                    transaction.pending = pending;
+                }
+            }
+            StoreAction::SendTransactionEnded(id) => {
+                if let Some(connection) = self
+                    .user_data
+                    .connection_list
+                    .list
+                    .iter_mut()
+                    .find(|c| c.id == id)
+                {
+                    connection.transaction = None;
+                }
+            }
             StoreAction::SendError(e) => {
                 self.error(&format!("{:?}", e));
-                self.user_data.connection_list.current_connection = None;
+                // a failed load leaves the transaction and so the connection
+                // open, it's ended by a commit or a rollback only
+                let connection_list = &self.user_data.connection_list;
+                let in_transaction = connection_list
+                    .current_connection
+                    .and_then(|i| connection_list.list.get(i))
+                    .is_some_and(|c| c.transaction.is_some());
+                if !in_transaction {
+                    self.user_data.connection_list.reset_current_connection();
+                }
             }
+            StoreAction::SendSuccess(message) => self.success(&message),
+            StoreAction::SendQueryError(e) => self.error(&e),
             _ => {}
         }
     }
 
+    fn transaction_of(&mut self, id: &str) -> Option<&mut ManualTransaction> {
+        self.user_data
+            .connection_list
+            .list
+            .iter_mut()
+            .find(|c| c.id == id)
+            .and_then(|c| c.transaction.as_mut())
+    }
+
     pub fn log(&mut self, content: &str) {
         log::info!("{}", content);
         self.log_contents.push(LogContent::Info(content.into()))
@@ -102,18 +234,58 @@ impl<'a> Store<'a> {
         &self.log_contents
     }
 
-    pub fn save(&self) -> anyhow::Result<()> {
+    /// Write `user_data.json`, the passwords going to the vault. `VaultLocked`
+    /// is returned when a password has to be stored before the master
+    /// passphrase was given.
+    pub fn save(&mut self) -> anyhow::Result<()> {
         if !Path::new(&utils::get_path_app_folder()).exists() {
             anyhow::bail!("$HOME/.config/dbv/ doesn't exist.");
         }
 
+        let connections = &self.user_data.connection_list.list;
+        for connection in connections.iter() {
+            if connection.password_source == PasswordSource::Vault
+                && !connection.password.is_empty()
+            {
+                self.vault.set(&connection.id, &connection.password)?;
+            }
+        }
+        let ids = connections
+            .iter()
+            .filter(|c| c.password_source == PasswordSource::Vault)
+            .map(|c| c.id.as_str())
+            .collect::<Vec<_>>();
+        self.vault.retain(&ids);
+        self.vault.save()?;
+
         let user_data = serde_json::to_string_pretty(&self.user_data)?;
         fs::write(utils::get_path_app_file(STORE_FILENAME), user_data)?;
 
         Ok(())
     }
 
+    /// Database the statements of the current connection are meant for : the
+    /// selected one, else the one the connection points to.
+    pub fn database_name(&self) -> String {
+        if let Some(database) = self.database_list.current() {
+            return database.clone();
+        }
+        let connection_list = &self.user_data.connection_list;
+        match connection_list.current_connection {
+            Some(i) if !connection_list.list[i].database.is_empty() => {
+                connection_list.list[i].database.clone()
+            }
+            Some(i) => connection_list.list[i].name.clone(),
+            None => String::new(),
+        }
+    }
+
+    pub fn resolve_password(&mut self, index: usize) -> anyhow::Result<()> {
+        self.user_data.connection_list.list[index].resolve_password(&self.vault)
+    }
+
     pub fn load(&mut self) -> anyhow::Result<()> {
+        self.vault.load()?;
         let filepath = utils::get_path_app_file(STORE_FILENAME);
         if !Path::new(&filepath).exists() {
             return Ok(());
@@ -122,6 +294,21 @@ impl<'a> Store<'a> {
         let user_data = fs::read_to_string(&filepath)?;
         self.user_data = serde_json::from_str(&user_data)?;
 
+        if self.user_data.version < USER_DATA_VERSION {
+            // connections have been converted while deserializing, write them
+            // back in the current format
+            self.user_data.version = USER_DATA_VERSION;
+            match self.save() {
+                Ok(()) => self.log("User data migrated to the current format."),
+                Err(e) if e.is::<VaultLocked>() => {
+                    self.log("Choose a master passphrase to move the stored passwords to the vault.");
+                    self.actions_tx
+                        .send(UpdateAction::SendAppAction(AppAction::SendSaveUserData))?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
         Ok(())
     }
 }
@@ -1,8 +1,17 @@
 use serde::{Serialize, Deserialize};
 use crate::sql::connection::ConnectionList;
 
+/// Bumped each time the layout of `user_data.json` changes.
+/// 0 : connections stored as raw connection strings
+/// 1 : connections stored as structured profiles
+/// 2 : passwords moved to the secrets vault
+pub const USER_DATA_VERSION: u32 = 2;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UserData {
+    #[serde(default)]
+    pub version: u32,
+
     #[serde(flatten)]
     pub connection_list: ConnectionList
 }
@@ -10,6 +19,7 @@ pub struct UserData {
 impl UserData {
     pub fn new() -> Self {
         UserData {
+            version: USER_DATA_VERSION,
             connection_list: ConnectionList::new()
         }
     }
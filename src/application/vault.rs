@@ -0,0 +1,229 @@
+use crate::utils;
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
+
+const VAULT_FILENAME: &str = "secrets.json";
+// encrypted with the key when the vault is created, so a wrong passphrase can
+// be told apart from a corrupted secret
+const CHECK_VALUE: &str = "dbv";
+
+/// Returned when a secret is read or written before the master passphrase has
+/// been given.
+#[derive(Debug)]
+pub struct VaultLocked;
+
+impl std::fmt::Display for VaultLocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The secrets vault is locked.")
+    }
+}
+
+impl std::error::Error for VaultLocked {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VaultFile {
+    salt: String,
+    check: Option<Sealed>,
+    secrets: HashMap<String, Sealed>,
+}
+
+/// Passwords of the connections, encrypted with a key derived from a master
+/// passphrase and kept apart from `user_data.json`.
+pub struct Vault {
+    file: VaultFile,
+    cipher: Option<ChaCha20Poly1305>,
+}
+
+impl Default for Vault {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vault {
+    pub fn new() -> Self {
+        Vault {
+            file: VaultFile::default(),
+            cipher: None,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.cipher.is_none()
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.file.check.is_some()
+    }
+
+    /// Derive the key from the passphrase. The first unlock of a new vault
+    /// sets the passphrase.
+    pub fn unlock(&mut self, passphrase: &str) -> anyhow::Result<()> {
+        if !self.is_initialized() {
+            let mut salt = [0u8; 16];
+            OsRng.fill_bytes(&mut salt);
+            self.file.salt = STANDARD.encode(salt);
+        }
+
+        let salt = STANDARD.decode(&self.file.salt)?;
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+
+        if let Some(check) = &self.file.check {
+            if open(&cipher, check).ok().as_deref() != Some(CHECK_VALUE) {
+                anyhow::bail!("Wrong master passphrase.");
+            }
+        } else {
+            self.file.check = Some(seal(&cipher, CHECK_VALUE)?);
+        }
+
+        self.cipher = Some(cipher);
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> anyhow::Result<Option<String>> {
+        let cipher = self.cipher.as_ref().ok_or(VaultLocked)?;
+        match self.file.secrets.get(id) {
+            Some(sealed) => Ok(Some(open(cipher, sealed)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set(&mut self, id: &str, secret: &str) -> anyhow::Result<()> {
+        let cipher = self.cipher.as_ref().ok_or(VaultLocked)?;
+        self.file
+            .secrets
+            .insert(id.to_string(), seal(cipher, secret)?);
+        Ok(())
+    }
+
+    /// Forget the secrets of the connections which no longer exist.
+    pub fn retain(&mut self, ids: &[&str]) {
+        self.file.secrets.retain(|id, _| ids.contains(&id.as_str()));
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        if !self.is_initialized() {
+            return Ok(());
+        }
+
+        let filepath = utils::get_path_app_file(VAULT_FILENAME);
+        fs::write(&filepath, serde_json::to_string_pretty(&self.file)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&filepath, fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn load(&mut self) -> anyhow::Result<()> {
+        let filepath = utils::get_path_app_file(VAULT_FILENAME);
+        if !Path::new(&filepath).exists() {
+            return Ok(());
+        }
+
+        self.file = serde_json::from_str(&fs::read_to_string(&filepath)?)?;
+        Ok(())
+    }
+}
+
+fn seal(cipher: &ChaCha20Poly1305, secret: &str) -> anyhow::Result<Sealed> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, secret.as_bytes())
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok(Sealed {
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+fn open(cipher: &ChaCha20Poly1305, sealed: &Sealed) -> anyhow::Result<String> {
+    let nonce = STANDARD.decode(&sealed.nonce)?;
+    // `Nonce::from_slice` panics on any other length
+    if nonce.len() != Nonce::default().len() {
+        anyhow::bail!("A secret of the vault is corrupted.");
+    }
+    let ciphertext = STANDARD.decode(&sealed.ciphertext)?;
+    let secret = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| anyhow::anyhow!("Unable to decrypt a secret."))?;
+    Ok(String::from_utf8(secret)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A vault reading the file the other one would have saved.
+    fn reopened(vault: &Vault) -> Vault {
+        let saved = serde_json::to_string(&vault.file).unwrap();
+        Vault {
+            file: serde_json::from_str(&saved).unwrap(),
+            cipher: None,
+        }
+    }
+
+    #[test]
+    fn secrets_round_trip() {
+        let mut vault = Vault::new();
+        assert!(vault.get("a").unwrap_err().is::<VaultLocked>());
+        vault.unlock("correct horse").unwrap();
+        vault.set("a", "p@ss wörd").unwrap();
+        vault.set("b", "p@ss wörd").unwrap();
+        assert_eq!(vault.get("a").unwrap().as_deref(), Some("p@ss wörd"));
+        assert_eq!(vault.get("c").unwrap(), None);
+        // a nonce of its own for each secret
+        let (a, b) = (&vault.file.secrets["a"], &vault.file.secrets["b"]);
+        assert_ne!(a.nonce, b.nonce);
+        assert_ne!(a.ciphertext, b.ciphertext);
+        assert!(!serde_json::to_string(&vault.file).unwrap().contains("p@ss"));
+
+        let mut vault = reopened(&vault);
+        assert!(vault.is_initialized() && vault.is_locked());
+        assert!(vault.unlock("wrong horse").is_err());
+        assert!(vault.is_locked());
+        vault.unlock("correct horse").unwrap();
+        assert_eq!(vault.get("b").unwrap().as_deref(), Some("p@ss wörd"));
+
+        vault.retain(&["b"]);
+        assert_eq!(vault.get("a").unwrap(), None);
+    }
+
+    #[test]
+    fn tampered_secrets_are_refused() {
+        let mut vault = Vault::new();
+        vault.unlock("passphrase").unwrap();
+        vault.set("a", "secret").unwrap();
+        let sealed = vault.file.secrets.get_mut("a").unwrap();
+        let mut ciphertext = STANDARD.decode(&sealed.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        sealed.ciphertext = STANDARD.encode(ciphertext);
+        assert!(vault.get("a").is_err());
+
+        let sealed = vault.file.secrets.get_mut("a").unwrap();
+        sealed.nonce = STANDARD.encode([0u8; 5]);
+        assert!(vault.get("a").is_err());
+        // the check value is opened when unlocking
+        let mut reopened = reopened(&vault);
+        reopened.file.check = Some(vault.file.secrets["a"].clone());
+        assert!(reopened.unlock("passphrase").is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use super::UpdateAction;
+use crate::{
+    components::{DatabaseListComponent, RecordsViewComponent, TabComponent, TableListComponent},
+    sql::{completion::Catalog, database::DatabaseList},
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Everything tied to one open connection: the databases, tables and records
+/// loaded from it and where the panes showing them are scrolled to.
+///
+/// The workspace on screen lives in the `App` and the `Store`, the others are
+/// kept here until they are switched to. Each one has its own channel so the
+/// results of a query land in the workspace it was sent from.
+pub struct Workspace {
+    pub connection: Option<usize>,
+    pub is_loading: bool,
+    pub database_list: DatabaseList,
+    pub tables_list: Vec<String>,
+    pub catalog: Option<Catalog>,
+    pub actions_tx: UnboundedSender<UpdateAction>,
+    pub actions_rx: UnboundedReceiver<UpdateAction>,
+
+    pub tab: TabComponent,
+    pub database_list_component: DatabaseListComponent,
+    pub table_list_component: TableListComponent,
+    pub records_view: RecordsViewComponent,
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        let (actions_tx, actions_rx) = unbounded_channel();
+        Workspace {
+            connection: None,
+            is_loading: false,
+            database_list: DatabaseList::new(),
+            tables_list: Vec::new(),
+            catalog: None,
+            actions_tx,
+            actions_rx,
+            tab: TabComponent::new(),
+            database_list_component: DatabaseListComponent::new(),
+            table_list_component: TableListComponent::new(),
+            records_view: RecordsViewComponent::new(),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::application::preferences::{ColorDepth, ThemeConfig};
+use ratatui::style::{Color, Style, Stylize};
+use std::{env, sync::OnceLock};
+
+/// Depth the colors are drawn with, never `Auto` once set.
+static DEPTH: OnceLock<ColorDepth> = OnceLock::new();
+
+/// Levels of the 6x6x6 cube of the 256 colors.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The 16 colors of the terminal, with the values xterm gives them.
+const ANSI_COLORS: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::Red, [205, 0, 0]),
+    (Color::Green, [0, 205, 0]),
+    (Color::Yellow, [205, 205, 0]),
+    (Color::Blue, [0, 0, 238]),
+    (Color::Magenta, [205, 0, 205]),
+    (Color::Cyan, [0, 205, 205]),
+    (Color::Gray, [229, 229, 229]),
+    (Color::DarkGray, [127, 127, 127]),
+    (Color::LightRed, [255, 0, 0]),
+    (Color::LightGreen, [0, 255, 0]),
+    (Color::LightYellow, [255, 255, 0]),
+    (Color::LightBlue, [92, 92, 255]),
+    (Color::LightMagenta, [255, 0, 255]),
+    (Color::LightCyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+/// Depth of the terminal, told by `NO_COLOR`, `COLORTERM` and `TERM`.
+fn detect() -> ColorDepth {
+    let var = |name: &str| env::var(name).unwrap_or_default();
+    if !var("NO_COLOR").is_empty() {
+        return ColorDepth::NoColor;
+    }
+    let colorterm = var("COLORTERM");
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorDepth::TrueColor;
+    }
+    if var("TERM").contains("256") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/// Set the depth the colors are drawn with, the preference winning over
+/// what the terminal tells.
+pub fn init(depth: ColorDepth) {
+    let depth = match depth {
+        ColorDepth::Auto => detect(),
+        depth => depth,
+    };
+    let _ = DEPTH.set(depth);
+}
+
+pub fn depth() -> ColorDepth {
+    *DEPTH.get_or_init(detect)
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(&a, b)| (a as i32 - b as i32).pow(2) as u32)
+        .sum()
+}
+
+/// Nearest of the 256 colors, in the cube or on the ramp of grays.
+fn to_ansi256(rgb: [u8; 3]) -> Color {
+    let level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - value as i32).abs())
+            .unwrap_or(0)
+    };
+    let [r, g, b] = rgb.map(level);
+    let cube = [CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]];
+
+    let average = rgb.iter().map(|&v| v as u32).sum::<u32>() / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23);
+    let gray_value = (8 + gray_index * 10) as u8;
+
+    if distance(rgb, [gray_value; 3]) < distance(rgb, cube) {
+        Color::Indexed(232 + gray_index as u8)
+    } else {
+        Color::Indexed(16 + 36 * r as u8 + 6 * g as u8 + b as u8)
+    }
+}
+
+fn to_ansi16(rgb: [u8; 3]) -> Color {
+    ANSI_COLORS
+        .iter()
+        .min_by_key(|(_, value)| distance(rgb, *value))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
+
+/// Color of the theme as the terminal can show it.
+pub fn from_rgb(rgb: [u8; 3]) -> Color {
+    match depth() {
+        ColorDepth::Auto | ColorDepth::TrueColor => Color::Rgb(rgb[0], rgb[1], rgb[2]),
+        ColorDepth::Ansi256 => to_ansi256(rgb),
+        ColorDepth::Ansi16 => to_ansi16(rgb),
+        ColorDepth::NoColor => Color::Reset,
+    }
+}
+
+/// Item or cell under the cursor. Without colors it is shown reversed.
+pub fn selection_style(theme_config: &ThemeConfig) -> Style {
+    match depth() {
+        ColorDepth::NoColor => Style::new().reversed(),
+        _ => Style::new()
+            .fg(from_rgb(theme_config.selection_color))
+            .bg(from_rgb(theme_config.selection_background_color)),
+    }
+}
@@ -1,40 +1,494 @@
-use super::MutableComponent;
+use super::{centered_rect, centered_rect_fixed_height, MutableComponent};
 use crate::{
-    application::Store,
-    events::{key::Keys, EventState},
-    components::LayoutArea
+    application::{
+        editor, ex_command,
+        library::{QueryLibrary, SavedQuery},
+        Store,
+    },
+    events::{key::Keys, keymap::Scope, EventState},
+    components::{
+        query_library::{LibraryChoice, QueryLibraryComponent},
+        widgets::sql_highlight::highlight,
+        FieldKind, FormField, FormPopupComponent, InputAction, LayoutArea,
+    },
+    sql::completion::{self, Completion, CompletionKind},
 };
 
 use ratatui::{prelude::*, widgets::*};
 
+/// What the word before the cursor may be completed with.
+struct CompletionPopup {
+    /// Byte the word starts at.
+    start: usize,
+    items: Vec<Completion>,
+    list_state: ListState,
+}
+
 pub struct CommandComponent {
     pub text_input: String,
+    /// Position of the cursor, in characters.
+    pub cursor_pos: usize,
+    /// Asks the name the text is saved under in the library.
+    save_form: Option<FormPopupComponent>,
+    library: Option<QueryLibraryComponent>,
+    /// Last query loaded from the library, to save it again in place.
+    loaded: Option<SavedQuery>,
+    completion: Option<CompletionPopup>,
+    /// SQL being typed when `:` was pressed, given back once the `:` command
+    /// is run or left.
+    draft: Option<(String, usize)>,
 }
 
 impl CommandComponent {
     pub fn new() -> Self {
         CommandComponent {
             text_input: String::new(),
+            cursor_pos: 0,
+            save_form: None,
+            library: None,
+            loaded: None,
+            completion: None,
+            draft: None,
+        }
+    }
+
+    /// Keymap section of the pane or of the saved queries, none while the
+    /// name a query is saved under is typed.
+    pub fn keymap_scope(&self) -> Option<Scope> {
+        if self.save_form.is_some() {
+            return None;
+        }
+        match &self.library {
+            Some(library) => library.keymap_scope(),
+            None => Some(Scope::Command),
         }
     }
+
+    pub fn set_text(&mut self, text: String, cursor_pos: usize) {
+        self.cursor_pos = cursor_pos.min(text.chars().count());
+        self.text_input = text;
+    }
+
+    /// Hand the statements over, the app checking them against the access
+    /// mode of the connection. The draft is kept without a connection.
+    fn submit(&mut self, store: &mut Store) -> anyhow::Result<EventState> {
+        if store.user_data.connection_list.current_connection.is_none() {
+            store.error("No current connection is set.");
+            return Ok(EventState::Consumed);
+        }
+        Ok(self.confirm())
+    }
+
+    /// Start a `:` command, the SQL typed so far being kept aside.
+    pub fn start_ex(&mut self) {
+        if self.is_ex() {
+            return;
+        }
+        let text = std::mem::replace(&mut self.text_input, String::from(":"));
+        self.draft = Some((text, self.cursor_pos));
+        self.cursor_pos = 1;
+    }
+
+    /// Whether a `:` command is typed instead of SQL.
+    fn is_ex(&self) -> bool {
+        self.text_input.starts_with(':')
+    }
+
+    /// Leave the `:` command, giving back the SQL kept aside.
+    fn end_ex(&mut self) -> String {
+        let (text, cursor_pos) = self.draft.take().unwrap_or_default();
+        self.cursor_pos = cursor_pos;
+        std::mem::replace(&mut self.text_input, text)
+    }
+
+    /// Give up the `:` command being typed, as Esc does.
+    pub fn cancel_ex(&mut self) {
+        if self.draft.is_some() {
+            self.end_ex();
+        }
+    }
+
+    fn confirm(&mut self) -> EventState {
+        let text = std::mem::take(&mut self.text_input);
+        self.cursor_pos = 0;
+        EventState::ConfirmedText(text)
+    }
+
+    /// Offer the completions of the word before the cursor. Unless `forced`,
+    /// nothing is offered before a word or a `.` is started.
+    fn complete(&mut self, store: &Store, forced: bool) {
+        let cursor = self.byte_pos();
+        let catalog = store
+            .catalog
+            .as_ref()
+            .filter(|c| store.database_list.current() == Some(&c.database));
+        let (start, items) = if self.is_ex() {
+            ex_command::complete(&self.text_input, cursor, store)
+        } else {
+            completion::complete(&self.text_input, cursor, catalog)
+        };
+        let started = start < cursor || self.text_input[..cursor].ends_with('.');
+        self.completion = if items.is_empty() || !(started || forced) {
+            None
+        } else {
+            Some(CompletionPopup {
+                start,
+                items,
+                list_state: ListState::default().with_selected(Some(0)),
+            })
+        };
+    }
+
+    /// Replace the word before the cursor by the selected completion.
+    fn accept_completion(&mut self) {
+        let Some(popup) = self.completion.take() else {
+            return;
+        };
+        let Some(item) = popup.list_state.selected().map(|i| &popup.items[i]) else {
+            return;
+        };
+        let end = self.byte_pos();
+        self.text_input.replace_range(popup.start..end, &item.text);
+        self.cursor_pos =
+            self.text_input[..popup.start].chars().count() + item.text.chars().count();
+    }
+
+    fn event_completion(&mut self, input: &Keys) -> EventState {
+        let Some(popup) = &mut self.completion else {
+            return EventState::Wasted;
+        };
+        let count = popup.items.len();
+        let selected = popup.list_state.selected().unwrap_or(0);
+        match input {
+            Keys::ArrowDown => {
+                popup.list_state.select(Some((selected + 1) % count));
+            }
+            Keys::ArrowUp => {
+                popup.list_state.select(Some((selected + count - 1) % count));
+            }
+            Keys::Tab => self.accept_completion(),
+            Keys::Esc => self.completion = None,
+            _ => return EventState::Wasted,
+        }
+        EventState::Consumed
+    }
+
+    /// Show the saved queries of the current connection and the global ones.
+    pub fn open_library(&mut self, store: &mut Store) {
+        let connection_list = &store.user_data.connection_list;
+        let connection = connection_list
+            .current_connection
+            .map(|i| connection_list.list[i].id.clone());
+        match QueryLibraryComponent::new(connection) {
+            Ok(library) => {
+                self.library = Some(library);
+                store.is_lock = true;
+            }
+            Err(e) => store.error(&format!("Unable to read the saved queries : {}", e)),
+        }
+    }
+
+    fn open_save_form(&mut self, store: &mut Store) {
+        if self.text_input.trim().is_empty() {
+            store.error("There is no query to save.");
+            return;
+        }
+        let connection_list = &store.user_data.connection_list;
+        let current = connection_list
+            .current_connection
+            .map(|i| &connection_list.list[i]);
+        let mut scopes = vec![String::from("global")];
+        scopes.extend(current.map(|c| c.name.clone()));
+        let loaded = self.loaded.clone().unwrap_or_default();
+        // a query of the current connection stays in it
+        let scope = match (&loaded.connection, current) {
+            (Some(id), Some(connection)) if *id == connection.id => connection.name.clone(),
+            _ => scopes[0].clone(),
+        };
+        self.save_form = Some(FormPopupComponent::new(
+            String::from("Save the query"),
+            vec![
+                FormField::new("Name", loaded.name, FieldKind::Text),
+                FormField::new("Folder", loaded.folder, FieldKind::Text),
+                FormField::new("Tags", loaded.tags.join(", "), FieldKind::Text),
+                FormField::new("Scope", scope, FieldKind::Choice(scopes)),
+            ],
+            InputAction::Insert,
+        ));
+        store.is_lock = true;
+    }
+
+    /// Save the query in place when it was loaded from the library, or as
+    /// `name`. The form asks where to save it when neither tells.
+    pub fn write(&mut self, name: Option<String>, store: &mut Store) -> anyhow::Result<()> {
+        if self.text_input.trim().is_empty() {
+            anyhow::bail!("There is no query to save.");
+        }
+        let sql = self.text_input.clone();
+        let query = match (name, &self.loaded) {
+            (Some(name), loaded) => SavedQuery {
+                name,
+                sql,
+                ..loaded.clone().unwrap_or_default()
+            },
+            (None, Some(loaded)) => SavedQuery {
+                sql,
+                ..loaded.clone()
+            },
+            (None, None) => {
+                self.open_save_form(store);
+                return Ok(());
+            }
+        };
+        self.keep(query, store)
+    }
+
+    fn save(&mut self, values: Vec<String>, store: &mut Store) -> anyhow::Result<()> {
+        let [name, folder, tags, scope]: [String; 4] = values
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Unexpected query form."))?;
+        if name.trim().is_empty() {
+            anyhow::bail!("A saved query needs a name.");
+        }
+        let connection_list = &store.user_data.connection_list;
+        let connection = match scope.as_str() {
+            "global" => None,
+            _ => connection_list
+                .current_connection
+                .map(|i| connection_list.list[i].id.clone()),
+        };
+        let query = SavedQuery {
+            name: name.trim().to_string(),
+            folder: folder.trim().trim_matches('/').to_string(),
+            tags: tags
+                .split(',')
+                .map(|t| t.trim().trim_start_matches('#').to_string())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            connection,
+            sql: self.text_input.clone(),
+        };
+        self.keep(query, store)
+    }
+
+    /// Add the query to the library, in place of the one of the same name.
+    fn keep(&mut self, query: SavedQuery, store: &mut Store) -> anyhow::Result<()> {
+        let path = query.path();
+        let mut library = QueryLibrary::load()?;
+        let replaced = library.upsert(query.clone());
+        library.save()?;
+        self.loaded = Some(query);
+        store.success(&if replaced {
+            format!("Saved query {} updated.", path)
+        } else {
+            format!("Query saved as {}.", path)
+        });
+        Ok(())
+    }
+
+    fn event_library(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        let Some(library) = &mut self.library else {
+            return Ok(EventState::Wasted);
+        };
+        if let EventState::Escaped = library.event(input, store)? {
+            self.library = None;
+            store.is_lock = false;
+            return Ok(EventState::Consumed);
+        }
+        let Some(choice) = library.choice.take() else {
+            return Ok(EventState::Consumed);
+        };
+        self.library = None;
+        store.is_lock = false;
+        let (run, query) = match choice {
+            LibraryChoice::Load(query) => (false, query),
+            LibraryChoice::Run(query) => (true, query),
+        };
+        let cursor_pos = query.sql.chars().count();
+        self.set_text(query.sql.clone(), cursor_pos);
+        self.loaded = Some(query);
+        if run {
+            return self.submit(store);
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn event_save_form(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        let Some(form) = &mut self.save_form else {
+            return Ok(EventState::Wasted);
+        };
+        match form.event(input, store)? {
+            EventState::ConfirmedForm(values) => {
+                // the form stays open until the query is saved
+                if let Err(e) = self.save(values, store) {
+                    store.error(&e.to_string());
+                    return Ok(EventState::Consumed);
+                }
+                self.save_form = None;
+                store.is_lock = false;
+            }
+            EventState::Escaped => {
+                self.save_form = None;
+                store.is_lock = false;
+            }
+            _ => {}
+        }
+        Ok(EventState::Consumed)
+    }
+
+    /// Popups are drawn once every pane has been, so they stay on top.
+    pub fn draw_popup(
+        &mut self,
+        frame: &mut Frame,
+        store: &Store,
+        layout: &LayoutArea,
+    ) -> anyhow::Result<()> {
+        let theme_config = store.preference.theme_config;
+        let help_color = self.get_color(theme_config.help_text_color);
+        let border_color = self.get_color(theme_config.selected_color);
+        let text_color = self.get_color(theme_config.unselected_color);
+        let selection_style = self.selection_style(theme_config);
+        if let Some(popup) = &mut self.completion {
+            let command = layout.right_area[2];
+            let height = (popup.items.len().min(8) + 2) as u16;
+            let width = popup
+                .items
+                .iter()
+                .map(|item| item.text.chars().count() + 11)
+                .max()
+                .unwrap_or(0)
+                .max(20) as u16
+                + 4;
+            let column = self.text_input[..popup.start].chars().count() as u16;
+            let x = (command.x + 1 + column).min(command.right().saturating_sub(width));
+            let area = Rect::new(x, command.y.saturating_sub(height), width, height);
+            let items = popup
+                .items
+                .iter()
+                .map(|item| {
+                    let kind = match item.kind {
+                        CompletionKind::Keyword => "keyword",
+                        CompletionKind::Table => "table",
+                        CompletionKind::Column => "column",
+                        CompletionKind::Command => "command",
+                        CompletionKind::Connection => "connection",
+                        CompletionKind::Database => "database",
+                        CompletionKind::Option => "option",
+                        CompletionKind::Theme => "theme",
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::from(format!("{:width$}", item.text, width = width as usize - 12)),
+                        Span::from(kind).style(Style::new().fg(help_color)),
+                    ]))
+                })
+                .collect::<Vec<_>>();
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(border_color)),
+                )
+                .style(Style::new().fg(text_color))
+                .highlight_style(selection_style);
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(list, area, &mut popup.list_state);
+        }
+        if let Some(library) = &mut self.library {
+            library.draw(
+                frame,
+                centered_rect(layout.main_area[0], 70, 60),
+                true,
+                store,
+                layout,
+            )?;
+        }
+        if let Some(form) = &mut self.save_form {
+            let height = form.height();
+            form.draw(
+                frame,
+                centered_rect_fixed_height(layout.main_area[0], 50, height),
+                true,
+                store,
+                layout,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Edit the text in `$VISUAL` or `$EDITOR`, the TUI being suspended
+    /// meanwhile.
+    fn edit_externally(&mut self, store: &mut Store) {
+        match editor::edit(&self.text_input, "sql", store) {
+            Ok(text) => {
+                let cursor_pos = text.chars().count();
+                self.set_text(text, cursor_pos);
+            }
+            Err(e) => store.error(&e.to_string()),
+        }
+    }
+
+    fn byte_pos(&self) -> usize {
+        self.text_input
+            .char_indices()
+            .nth(self.cursor_pos)
+            .map(|(i, _)| i)
+            .unwrap_or(self.text_input.len())
+    }
 }
 
 impl MutableComponent for CommandComponent {
     fn event(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        if self.save_form.is_some() {
+            return self.event_save_form(input, store);
+        }
+        if self.library.is_some() {
+            return self.event_library(input, store);
+        }
+        if let EventState::Consumed = self.event_completion(input) {
+            return Ok(EventState::Consumed);
+        }
+        // any other key closes the completions, Enter runs the text as typed
+        self.completion = None;
         match input {
+            // erasing the `:` leaves the command for the SQL kept aside
+            Keys::Backspace if self.text_input == ":" && self.draft.is_some() => {
+                self.end_ex();
+            }
             Keys::Backspace => {
-                self.text_input.pop();
+                if self.cursor_pos > 0 {
+                    self.cursor_pos -= 1;
+                    let pos = self.byte_pos();
+                    self.text_input.remove(pos);
+                }
+                self.complete(store, false);
+            }
+            Keys::ArrowLeft => {
+                self.cursor_pos = self.cursor_pos.saturating_sub(1);
             }
-            Keys::Enter => {
-                let result = Ok(EventState::ConfirmedText(self.text_input.clone()));
-                self.text_input = String::new();
-                return result;
+            Keys::ArrowRight => {
+                if self.cursor_pos < self.text_input.chars().count() {
+                    self.cursor_pos += 1;
+                }
             }
+            Keys::Tab => self.complete(store, true),
+            Keys::Enter if self.is_ex() => return Ok(EventState::ConfirmedText(self.end_ex())),
+            Keys::Enter => return self.submit(store),
+            Keys::AltChar('s') => self.open_save_form(store),
+            Keys::AltChar('o') => self.open_library(store),
+            Keys::AltChar('e') => self.edit_externally(store),
             Keys::Char(c) => {
-                self.text_input.push_str(&c.to_string());
+                let pos = self.byte_pos();
+                self.text_input.insert(pos, *c);
+                self.cursor_pos += 1;
+                self.complete(store, false);
+            }
+            Keys::Esc if self.draft.is_some() => {
+                self.end_ex();
+                store.selected_pane = store.previous_selected_pane;
             }
             Keys::Esc => {
-                self.text_input = String::new();
+                // the draft is kept, to come back to it later
                 store.selected_pane = store.previous_selected_pane;
             }
             _ => return Ok(EventState::Wasted),
@@ -50,7 +504,7 @@ impl MutableComponent for CommandComponent {
         store: &Store,
         _layout: &LayoutArea
     ) -> anyhow::Result<()> {
-        let container = Block::default()
+        let mut container = Block::default()
             .title("Command")
             .borders(Borders::ALL)
             .border_style(
@@ -59,12 +513,48 @@ impl MutableComponent for CommandComponent {
             .padding(Padding::left(1))
             .border_type(BorderType::Rounded);
 
-        let text = Paragraph::new(&*self.text_input)
+        let connection_list = &store.user_data.connection_list;
+        if let Some(transaction) = connection_list
+            .current_connection
+            .and_then(|i| connection_list.list[i].transaction.as_ref())
+        {
+            let elapsed = transaction.elapsed().as_secs();
+            let warning_color = self.get_color(store.preference.theme_config.warning_color);
+            container = container.title_bottom(
+                Line::from(format!(
+                    " Transaction : {} pending, open {}m {:02}s, <Alt-c>: Commit, <Alt-r>: Rollback ",
+                    transaction.pending,
+                    elapsed / 60,
+                    elapsed % 60
+                ))
+                .style(Style::new().fg(warning_color))
+                .right_aligned(),
+            );
+        }
+
+        if selected {
+            container = container.title_bottom(" <A-e>: Edit in $EDITOR ");
+        }
+
+        // text coming from the editor may span several lines, the one of
+        // the cursor is kept in sight
+        let before = &self.text_input[..self.byte_pos()];
+        let line = before.matches('\n').count();
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count();
+        let height = area.height.saturating_sub(2).max(1) as usize;
+        let scroll = line.saturating_sub(height - 1);
+
+        let syntax = store.preference.theme_config.syntax;
+        let text = Paragraph::new(highlight(&self.text_input, syntax))
             .block(container)
-            .style(Style::new().white())
-            .alignment(Alignment::Left);
+            .style(Style::new().fg(self.get_color(store.preference.theme_config.unselected_color)))
+            .alignment(Alignment::Left)
+            .scroll((scroll as u16, 0));
         if selected {
-            frame.set_cursor(area.x + 2 + self.text_input.len() as u16, area.y + 1);
+            frame.set_cursor(
+                area.x + 2 + column as u16,
+                area.y + 1 + (line - scroll) as u16,
+            );
         }
         frame.render_widget(text, area);
         Ok(())
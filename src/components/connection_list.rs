@@ -1,17 +1,42 @@
-use super::{centered_rect, HelpContentText, MutableComponent};
+use super::{centered_rect, centered_rect_fixed_height, list_mouse, MutableComponent};
 use crate::{
-    application::{AppAction, Store, UpdateAction},
-    components::{InputAction, InputPopupComponent, LayoutArea},
+    application::{vault::VaultLocked, AppAction, Store, UpdateAction},
+    components::{
+        FieldKind, FormField, FormPopupComponent, InputAction, InputPopupComponent, LayoutArea,
+    },
     events::{key::Keys, EventState},
-    sql::{connection::Connection, database::Database},
+    sql::{
+        connection::{mask_credentials, Connection, Driver, PasswordSource},
+        database::Database,
+        guard::AccessMode,
+        health::{CheckTarget, Health},
+        pool::PoolSettings,
+        tls::{SslMode, Tls, TlsConfig},
+        tunnel::{SshConfig, Tunnel},
+    },
 };
+use crossterm::event::MouseEvent;
 use ratatui::{prelude::*, widgets::*};
-use std::collections::HashMap;
+
+/// What to do once the master passphrase has been given.
+#[derive(Debug, Clone, Copy)]
+enum PendingAction {
+    Save,
+    Connect(usize),
+    Test(usize),
+}
 
 #[derive(Debug, Clone)]
 pub struct ConnectionListComponent {
     list_state: ListState,
-    popup: Option<InputPopupComponent>,
+    popup: Option<FormPopupComponent>,
+    passphrase_popup: Option<InputPopupComponent>,
+    pending_action: Option<PendingAction>,
+    /// Connection whose password command is running, and what to do once
+    /// it has printed the password.
+    awaiting_password: Option<(String, PendingAction)>,
+    /// Connection whose password command has just printed the password.
+    fetched_password: Option<String>,
 }
 
 impl ConnectionListComponent {
@@ -19,31 +44,535 @@ impl ConnectionListComponent {
         ConnectionListComponent {
             list_state: ListState::default(),
             popup: None,
+            passphrase_popup: None,
+            pending_action: None,
+            awaiting_password: None,
+            fetched_password: None,
+        }
+    }
+
+    fn ask_passphrase(&mut self, store: &mut Store, action: PendingAction) {
+        let title = if store.vault.is_initialized() {
+            "Master passphrase"
+        } else {
+            "New master passphrase"
+        };
+        store.is_lock = true;
+        self.passphrase_popup = Some(
+            InputPopupComponent::new(title.to_string(), String::new(), InputAction::Insert)
+                .masked(),
+        );
+        self.pending_action = Some(action);
+    }
+
+    /// Save the user data, asking for the master passphrase first if a
+    /// password has to go to the vault.
+    pub fn save(&mut self, store: &mut Store) -> anyhow::Result<()> {
+        if let Err(e) = store.save() {
+            if e.is::<VaultLocked>() {
+                self.ask_passphrase(store, PendingAction::Save);
+                return Ok(());
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Popups are drawn once every pane has been, so they stay on top.
+    pub fn draw_popup(
+        &mut self,
+        frame: &mut Frame,
+        selected: bool,
+        store: &Store,
+        layout: &LayoutArea,
+    ) -> anyhow::Result<()> {
+        if let Some(popup) = &mut self.passphrase_popup {
+            if selected {
+                popup.draw(
+                    frame,
+                    centered_rect_fixed_height(layout.main_area[0], 40, 3),
+                    true,
+                    store,
+                    layout,
+                )?;
+            } else {
+                self.passphrase_popup = None;
+                self.pending_action = None;
+            }
+        }
+
+        if let Some(popup) = &mut self.popup {
+            if selected {
+                let height = popup.height();
+                popup.draw(
+                    frame,
+                    centered_rect_fixed_height(layout.main_area[0], 50, height),
+                    true,
+                    store,
+                    layout,
+                )?;
+            } else {
+                self.popup = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Select the connection and connect to it, as if chosen in the list.
+    pub fn select(&mut self, index: usize, store: &mut Store) -> anyhow::Result<()> {
+        self.list_state.select(Some(index));
+        self.connect(index, store)
+    }
+
+    /// Whether the password of the connection is known. The action is taken
+    /// again once the vault is unlocked, or once the password command has
+    /// printed the password.
+    fn password_ready(&mut self, index: usize, action: PendingAction, store: &mut Store) -> bool {
+        if let Err(e) = store.resolve_password(index) {
+            if e.is::<VaultLocked>() {
+                self.ask_passphrase(store, action);
+            } else {
+                store.error(&e.to_string());
+            }
+            return false;
+        }
+
+        let connection = &store.user_data.connection_list.list[index];
+        if let PasswordSource::Command(_) = connection.password_source {
+            if self.fetched_password.take().as_ref() != Some(&connection.id) {
+                connection.fetch_password(store.actions_tx.clone());
+                self.awaiting_password = Some((connection.id.clone(), action));
+                let message = format!("Running the password command of {}...", connection.name);
+                store.log(&message);
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Go on with what was waiting on the password command of a connection.
+    pub fn receive_password(
+        &mut self,
+        id: &str,
+        password: Result<String, String>,
+        store: &mut Store,
+    ) -> anyhow::Result<()> {
+        let action = match self.awaiting_password.take() {
+            Some((awaited, action)) if awaited == id => action,
+            // a later command has been run meanwhile
+            awaiting => {
+                self.awaiting_password = awaiting;
+                return Ok(());
+            }
+        };
+        let connection_list = &mut store.user_data.connection_list;
+        let Some(index) = connection_list.list.iter().position(|c| c.id == id) else {
+            return Ok(());
+        };
+        match password {
+            Ok(password) => {
+                connection_list.list[index].password = password;
+                self.fetched_password = Some(id.to_string());
+                match action {
+                    PendingAction::Connect(_) => self.connect(index, store),
+                    PendingAction::Test(_) => self.test(index, store),
+                    PendingAction::Save => Ok(()),
+                }
+            }
+            Err(e) => {
+                store.error(&e);
+                Ok(())
+            }
+        }
+    }
+
+    fn connect(&mut self, index: usize, store: &mut Store) -> anyhow::Result<()> {
+        if !self.password_ready(index, PendingAction::Connect(index), store) {
+            return Ok(());
+        }
+
+        store
+            .actions_tx
+            .send(UpdateAction::SendAppAction(AppAction::SendOpenWorkspace(
+                index,
+            )))?;
+        Ok(())
+    }
+
+    /// Open the connection in the current workspace, which has to be empty.
+    /// The server is reached first, the connection going on in `connected`.
+    pub fn open(&mut self, index: usize, store: &mut Store) -> anyhow::Result<()> {
+        let connection = &store.user_data.connection_list.list[index];
+        if let Err(e) = connection.connect(store.actions_tx.clone()) {
+            store.error(&e.to_string());
+            return Ok(());
+        }
+        let message = match &connection.ssh {
+            Some(ssh) if connection.needs_tunnel() => {
+                format!("Trying to connect to the database through {}...", ssh.host)
+            }
+            _ => String::from("Trying to connect to the database..."),
+        };
+        let connection_list = &mut store.user_data.connection_list;
+        connection_list.current_connection = Some(index);
+        connection_list.is_loading = true;
+        store.log(&message);
+        Ok(())
+    }
+
+    /// Build the pool of the connection once its server has answered.
+    pub fn connected(
+        &mut self,
+        id: &str,
+        tunnel: Result<Option<Tunnel>, String>,
+        store: &mut Store,
+    ) -> anyhow::Result<()> {
+        let connection_list = &mut store.user_data.connection_list;
+        // the workspace has been closed meanwhile, the tunnel is dropped
+        let Some(index) = connection_list.current_connection.filter(|&i| {
+            connection_list.list[i].id == id && connection_list.list[i].pool.is_none()
+        }) else {
+            return Ok(());
+        };
+        match tunnel {
+            Ok(tunnel) => self.open_through(index, tunnel, store),
+            Err(e) => {
+                connection_list.reset_current_connection();
+                connection_list.is_loading = false;
+                store.error(&e);
+                Ok(())
+            }
+        }
+    }
+
+    fn open_through(
+        &mut self,
+        index: usize,
+        tunnel: Option<Tunnel>,
+        store: &mut Store,
+    ) -> anyhow::Result<()> {
+        if let Err(e) = &store
+            .user_data
+            .connection_list
+            .set_current_connection(index, tunnel)
+        {
+            store.user_data.connection_list.is_loading = false;
+            store.error(e);
+            return Ok(());
+        }
+        store.user_data.connection_list.is_loading = true;
+        let pool = store.user_data.connection_list.get_pool()?;
+        let dialect = store.user_data.connection_list.get_dialect()?;
+        let actions_tx = store.actions_tx.clone();
+        Database::get_databases(pool.clone(), actions_tx.clone(), dialect);
+        Tls::get_encryption(pool.clone(), actions_tx.clone(), dialect, index);
+        let connection = &mut store.user_data.connection_list.list[index];
+        connection.monitor = Some(Health::monitor(
+            pool,
+            connection.tunnel.clone(),
+            actions_tx,
+            connection.id.clone(),
+        ));
+        Ok(())
+    }
+
+    /// Check each step of the connection apart, without touching the open one.
+    fn test(&mut self, index: usize, store: &mut Store) -> anyhow::Result<()> {
+        if !self.password_ready(index, PendingAction::Test(index), store) {
+            return Ok(());
+        }
+
+        let connection = &store.user_data.connection_list.list[index];
+        let target = match CheckTarget::new(connection) {
+            Ok(target) => target,
+            Err(e) => {
+                store.error(&e.to_string());
+                return Ok(());
+            }
+        };
+        store.log(&format!("Testing the connection to {}...", connection.name));
+        Health::test_connection(target, store.actions_tx.clone());
+        Ok(())
+    }
+
+    fn connection_form(connection: Option<&Connection>, action: InputAction) -> FormPopupComponent {
+        let default = Connection::new(String::new(), Driver::MySql);
+        let connection = connection.unwrap_or(&default);
+        let ssh = connection.ssh.clone().unwrap_or_default();
+        let tls = connection.tls.clone().unwrap_or_default();
+        let settings = &connection.pool_settings;
+        let ssl_modes = std::iter::once(String::from("default"))
+            .chain(SslMode::ALL.iter().map(|m| m.to_string()))
+            .collect();
+        let drivers = Driver::ALL.iter().map(|d| d.to_string()).collect();
+        let modes = AccessMode::ALL.iter().map(|m| m.to_string()).collect();
+        FormPopupComponent::new(
+            String::from("Connection"),
+            vec![
+                FormField::new("Name", connection.name.clone(), FieldKind::Text),
+                FormField::new(
+                    "Driver",
+                    connection.driver.to_string(),
+                    FieldKind::Choice(drivers),
+                ),
+                FormField::new(
+                    "Access",
+                    connection.mode.to_string(),
+                    FieldKind::Choice(modes),
+                ),
+                FormField::new("Host", connection.host.clone(), FieldKind::Text),
+                FormField::new(
+                    "Port",
+                    connection.port.map(|p| p.to_string()).unwrap_or_default(),
+                    FieldKind::Text,
+                ),
+                FormField::new("User", connection.user.clone(), FieldKind::Text),
+                FormField::new(
+                    "Password from",
+                    match connection.password_source {
+                        PasswordSource::None => "none",
+                        PasswordSource::Vault => "vault",
+                        PasswordSource::Env(_) => "env",
+                        PasswordSource::Command(_) => "command",
+                    }
+                    .to_string(),
+                    FieldKind::Choice(
+                        ["none", "vault", "env", "command"]
+                            .iter()
+                            .map(|s| s.to_string())
+                            .collect(),
+                    ),
+                ),
+                FormField::new("Password", connection.password.clone(), FieldKind::Password),
+                FormField::new(
+                    "Env var / command",
+                    match &connection.password_source {
+                        PasswordSource::Env(value) | PasswordSource::Command(value) => {
+                            value.clone()
+                        }
+                        _ => String::new(),
+                    },
+                    FieldKind::Text,
+                ),
+                FormField::new("Database", connection.database.clone(), FieldKind::Text),
+                FormField::new("File path", connection.file_path.clone(), FieldKind::Text),
+                FormField::new("Options", connection.options.clone(), FieldKind::Text),
+                FormField::new("SSH host", ssh.host.clone(), FieldKind::Text),
+                FormField::new(
+                    "SSH port",
+                    ssh.port.map(|p| p.to_string()).unwrap_or_default(),
+                    FieldKind::Text,
+                ),
+                FormField::new("SSH user", ssh.user.clone(), FieldKind::Text),
+                FormField::new("SSH key (or agent)", ssh.key_file.clone(), FieldKind::Text),
+                FormField::new(
+                    "SSL mode",
+                    connection
+                        .tls
+                        .as_ref()
+                        .map(|tls| tls.ssl_mode.to_string())
+                        .unwrap_or(String::from("default")),
+                    FieldKind::Choice(ssl_modes),
+                ),
+                FormField::new("CA certificate", tls.ca_cert.clone(), FieldKind::Text),
+                FormField::new(
+                    "Client certificate",
+                    tls.client_cert.clone(),
+                    FieldKind::Text,
+                ),
+                FormField::new("Client key", tls.client_key.clone(), FieldKind::Text),
+                FormField::new(
+                    "Pool size",
+                    settings.max_connections.to_string(),
+                    FieldKind::Text,
+                ),
+                FormField::new(
+                    "Connect timeout (s)",
+                    settings.connect_timeout.to_string(),
+                    FieldKind::Text,
+                ),
+                FormField::new(
+                    "Acquire timeout (s)",
+                    settings.acquire_timeout.to_string(),
+                    FieldKind::Text,
+                ),
+                FormField::new(
+                    "Statement timeout (s)",
+                    settings
+                        .statement_timeout
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                    FieldKind::Text,
+                ),
+                FormField::new("Init SQL", settings.init_sql.clone(), FieldKind::Text),
+            ],
+            action,
+        )
+    }
+
+    fn connection_from_form(
+        values: Vec<String>,
+        previous: Option<&Connection>,
+    ) -> anyhow::Result<Connection> {
+        let [name, driver, mode, host, port, user, password_from, password, password_ref, database, file_path, options, ssh_host, ssh_port, ssh_user, ssh_key, ssl_mode, ca_cert, client_cert, client_key, max_connections, connect_timeout, acquire_timeout, statement_timeout, init_sql]: [String; 25] =
+            values
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Unexpected connection form."))?;
+
+        if name.trim().is_empty() {
+            anyhow::bail!("A connection needs a name.");
+        }
+        let driver = Driver::from_scheme(&driver)
+            .ok_or(anyhow::anyhow!("Unsupported database driver : {}", driver))?;
+        let mode = mode.parse::<AccessMode>()?;
+        let port = parse_port(&port)?;
+
+        let password_ref = password_ref.trim().to_string();
+        let keeps_vault_password = previous
+            .map(|p| p.password_source == PasswordSource::Vault)
+            .unwrap_or(false);
+        let password_source = match password_from.as_str() {
+            "vault" if password.is_empty() && !keeps_vault_password => PasswordSource::None,
+            "vault" => PasswordSource::Vault,
+            "env" if password_ref.is_empty() => {
+                anyhow::bail!("The name of the environment variable is missing.")
+            }
+            "env" => PasswordSource::Env(password_ref),
+            "command" if password_ref.is_empty() => {
+                anyhow::bail!("The password command is missing.")
+            }
+            "command" => PasswordSource::Command(password_ref),
+            _ => PasswordSource::None,
+        };
+
+        let mut connection = Connection::new(name.trim().to_string(), driver);
+        if let Some(previous) = previous {
+            // the id links the connection to its secret in the vault
+            connection.id = previous.id.clone();
+        }
+        connection.mode = mode;
+        connection.host = host.trim().to_string();
+        connection.port = port;
+        connection.user = user;
+        if password_source == PasswordSource::Vault {
+            connection.password = password;
+        }
+        connection.password_source = password_source;
+        connection.database = database.trim().to_string();
+        connection.file_path = file_path.trim().to_string();
+        connection.options = options.trim().to_string();
+        if !ssh_host.trim().is_empty() {
+            connection.ssh = Some(SshConfig {
+                host: ssh_host.trim().to_string(),
+                port: parse_port(&ssh_port)?,
+                user: ssh_user.trim().to_string(),
+                key_file: ssh_key.trim().to_string(),
+            });
         }
+        let tls = TlsConfig {
+            ssl_mode: match ssl_mode.as_str() {
+                "default" => SslMode::default(),
+                mode => mode.parse()?,
+            },
+            ca_cert: ca_cert.trim().to_string(),
+            client_cert: client_cert.trim().to_string(),
+            client_key: client_key.trim().to_string(),
+        };
+        if ssl_mode != "default" || tls != TlsConfig::default() {
+            connection.tls = Some(tls);
+        }
+        connection.pool_settings = PoolSettings {
+            max_connections: parse_number("pool size", &max_connections)?
+                .filter(|&size| size > 0)
+                .ok_or(anyhow::anyhow!("The pool needs at least one connection."))?,
+            connect_timeout: parse_number("connect timeout", &connect_timeout)?
+                .unwrap_or(PoolSettings::default().connect_timeout),
+            acquire_timeout: parse_number("acquire timeout", &acquire_timeout)?
+                .unwrap_or(PoolSettings::default().acquire_timeout),
+            statement_timeout: parse_number("statement timeout", &statement_timeout)?,
+            init_sql: init_sql.trim().to_string(),
+        };
+        // fail now rather than when connecting
+        connection.connection_string()?;
+
+        Ok(connection)
     }
 
     pub fn event_popup(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        if let Some(popup) = &mut self.passphrase_popup {
+            match popup.event(input, store)? {
+                EventState::ConfirmedText(passphrase) => {
+                    if let Err(e) = store.vault.unlock(&passphrase) {
+                        store.error(&e.to_string());
+                        return Ok(EventState::Consumed);
+                    }
+                    store.success("Vault unlocked for this session.");
+                    self.passphrase_popup = None;
+                    store.is_lock = false;
+                    match self.pending_action.take() {
+                        Some(PendingAction::Save) => self.save(store)?,
+                        Some(PendingAction::Connect(index)) => self.connect(index, store)?,
+                        Some(PendingAction::Test(index)) => self.test(index, store)?,
+                        None => {}
+                    }
+                }
+                EventState::Escaped => {
+                    self.passphrase_popup = None;
+                    self.pending_action = None;
+                    store.is_lock = false;
+                }
+                _ => {}
+            }
+            return Ok(EventState::Consumed);
+        }
+
         if let Some(popup) = &mut self.popup {
             let event = popup.event(&input, store)?;
             match event {
-                EventState::ConfirmedText(content) => {
+                EventState::ConfirmedForm(values) => {
+                    let previous = match popup.action {
+                        InputAction::Insert => None,
+                        InputAction::Edit => self
+                            .list_state
+                            .selected()
+                            .and_then(|i| store.user_data.connection_list.list.get(i)),
+                    };
+                    let connection = match Self::connection_from_form(values, previous) {
+                        Ok(connection) => connection,
+                        Err(e) => {
+                            store.error(&e.to_string());
+                            return Ok(EventState::Consumed);
+                        }
+                    };
                     match popup.action {
                         InputAction::Insert => {
-                            store
-                                .user_data
-                                .connection_list
-                                .list
-                                .push(Connection::new(content));
+                            store.user_data.connection_list.list.push(connection);
                         }
                         InputAction::Edit => {
-                            store.user_data.connection_list.list
-                                [self.list_state.selected().unwrap()]
-                            .connection_string = content;
+                            let index = self.list_state.selected().unwrap();
+                            let previous = &mut store.user_data.connection_list.list[index];
+                            let mut connection = connection;
+                            let reconnects = !previous.connects_like(&connection);
+                            if reconnects {
+                                // the connection is closed to be opened with the new settings
+                                if let Err(e) = previous.ensure_closable() {
+                                    store.error(&e.to_string());
+                                    return Ok(EventState::Consumed);
+                                }
+                            } else {
+                                connection.keep_session(previous);
+                            }
+                            *previous = connection;
+                            if reconnects {
+                                store.actions_tx.send(UpdateAction::SendAppAction(
+                                    AppAction::SendCloseConnection(index),
+                                ))?;
+                            }
                         }
                     }
-                    store.save()?;
                     self.popup = None;
                     store.is_lock = false;
+                    self.save(store)?;
                 }
                 EventState::Escaped => {
                     self.popup = None;
@@ -57,6 +586,21 @@ impl ConnectionListComponent {
     }
 }
 
+fn parse_port(port: &str) -> anyhow::Result<Option<u16>> {
+    parse_number("port", port)
+}
+
+fn parse_number<T: std::str::FromStr>(label: &str, value: &str) -> anyhow::Result<Option<T>> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    value
+        .trim()
+        .parse::<T>()
+        .map(Some)
+        .map_err(|_| anyhow::anyhow!("Invalid {} : {}", label, value))
+}
+
 impl MutableComponent for ConnectionListComponent {
     fn event(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
         match self.event_popup(input, store) {
@@ -68,19 +612,9 @@ impl MutableComponent for ConnectionListComponent {
             Err(e) => anyhow::bail!(e),
         }
 
-        match input {
-            Keys::Char('i') => {
-                store.is_lock = true;
-                self.popup = Some(InputPopupComponent::new(
-                    String::from("Connection string"),
-                    String::new(),
-                    InputAction::Insert,
-                ));
-            }
-            Keys::Char('?') => {
-                store.selected_pane = (100, 100);
-            }
-            _ => {}
+        if let Keys::Char('i') = input {
+            store.is_lock = true;
+            self.popup = Some(Self::connection_form(None, InputAction::Insert));
         }
         if store.user_data.connection_list.list.len() > 0 {
             match input {
@@ -110,11 +644,15 @@ impl MutableComponent for ConnectionListComponent {
                             .select(Some(store.user_data.connection_list.list.len() - 1));
                     }
                 }
+                Keys::Home => self.list_state.select(Some(0)),
+                Keys::End => self
+                    .list_state
+                    .select(Some(store.user_data.connection_list.list.len() - 1)),
                 Keys::Char('d') => {
                     if let Some(index) = self.list_state.selected() {
-                        store.user_data.connection_list.list.remove(index);
-                        store.log("Connection string removed.");
-                        store.save()?;
+                        store.actions_tx.send(UpdateAction::SendAppAction(
+                            AppAction::SendRemoveConnection(index),
+                        ))?;
                     }
                 }
                 Keys::Char('e') => {
@@ -124,34 +662,19 @@ impl MutableComponent for ConnectionListComponent {
                         return Ok(EventState::Consumed);
                     };
                     store.is_lock = true;
-                    self.popup = Some(InputPopupComponent::new(
-                        String::from("Connection string"),
-                        store.user_data.connection_list.list[index]
-                            .connection_string
-                            .clone(),
+                    self.popup = Some(Self::connection_form(
+                        store.user_data.connection_list.list.get(index),
                         InputAction::Edit,
                     ));
-                    store.user_data.connection_list.reset_current_connection();
+                }
+                Keys::Char('t') => {
+                    if let Some(index) = self.list_state.selected() {
+                        self.test(index, store)?;
+                    }
                 }
                 Keys::Enter => {
-                    store
-                        .actions_tx
-                        .send(UpdateAction::SendAppAction(AppAction::SendResetAll))
-                        ?;
-                    store.log("Trying to connect to the database...");
                     if let Some(index) = self.list_state.selected() {
-                        if let Err(e) = &store
-                            .user_data
-                            .connection_list
-                            .set_current_connection(index)
-                        {
-                            store.error(e);
-                            return Ok(EventState::Wasted);
-                        }
-                        store.user_data.connection_list.is_loading = true;
-                        let pool = store.user_data.connection_list.get_pool()?;
-                        let actions_tx = store.actions_tx.clone();
-                        Database::get_databases(pool, actions_tx);
+                        self.connect(index, store)?;
                     }
                 }
                 _ => return Ok(EventState::Wasted),
@@ -161,28 +684,30 @@ impl MutableComponent for ConnectionListComponent {
         Ok(EventState::Consumed)
     }
 
+    fn mouse(
+        &mut self,
+        mouse: &MouseEvent,
+        area: Rect,
+        store: &mut Store,
+    ) -> anyhow::Result<EventState> {
+        let len = store.user_data.connection_list.list.len();
+        match list_mouse(mouse, area, &self.list_state, len) {
+            Some(index) => {
+                self.list_state.select(Some(index));
+                Ok(EventState::Consumed)
+            }
+            None => Ok(EventState::Wasted),
+        }
+    }
+
     fn draw(
         &mut self,
         frame: &mut Frame,
         area: Rect,
         selected: bool,
         store: &Store,
-        layout: &LayoutArea,
+        _layout: &LayoutArea,
     ) -> anyhow::Result<()> {
-        if let Some(popup) = &mut self.popup {
-            if selected {
-                popup.draw(
-                    frame,
-                    centered_rect(layout.main_area[0], 40, 7),
-                    true,
-                    store,
-                    layout,
-                )?;
-            } else {
-                self.popup = None;
-            }
-        }
-
         let container = Block::default()
             .title("Connections")
             .borders(Borders::ALL)
@@ -200,12 +725,35 @@ impl MutableComponent for ConnectionListComponent {
 
             let list = List::new(store.user_data.connection_list.list.iter().enumerate().map(
                 |(index, item)| {
-                    if let Some(i) = store.user_data.connection_list.current_connection {
-                        if i == index && store.user_data.connection_list.is_loading == false {
-                            return format!(" * {}", item.connection_string.clone());
-                        }
+                    let current = store.user_data.connection_list.current_connection == Some(index)
+                        && !store.user_data.connection_list.is_loading;
+                    // connections open in another workspace are marked too
+                    if current || item.pool.is_some() {
+                        let encryption = match item.encrypted {
+                            Some(true) => " [encrypted]",
+                            Some(false) => " [not encrypted]",
+                            None => "",
+                        };
+                        let status = item
+                            .status
+                            .map(|status| format!(" [{}]", status))
+                            .unwrap_or_default();
+                        return format!(
+                            " {} {}{} ({}){}{}",
+                            if current { "*" } else { "+" },
+                            item.mode.marker(),
+                            mask_credentials(&item.name),
+                            item.target(),
+                            encryption,
+                            status
+                        );
                     }
-                    item.connection_string.clone()
+                    format!(
+                        "{}{} ({})",
+                        item.mode.marker(),
+                        mask_credentials(&item.name),
+                        item.target()
+                    )
                 },
             ))
             .block(container.title_bottom(format!(
@@ -216,7 +764,7 @@ impl MutableComponent for ConnectionListComponent {
             .style(
                 Style::default().fg(self.get_color(store.preference.theme_config.unselected_color)),
             )
-            .highlight_style(Style::default().reversed())
+            .highlight_style(self.selection_style(store.preference.theme_config))
             .repeat_highlight_symbol(true);
 
             frame.render_stateful_widget(list, area, &mut self.list_state);
@@ -232,13 +780,3 @@ impl MutableComponent for ConnectionListComponent {
         Ok(())
     }
 }
-
-impl HelpContentText for ConnectionListComponent {
-    fn help_content_text() -> HashMap<&'static str, &'static str> {
-        HashMap::from([
-            ("i", "Insert a new database connection"),
-            ("d", "Delete the database connection"),
-            ("e", "Edit the database connection"),
-        ])
-    }
-}
@@ -0,0 +1,203 @@
+use super::{centered_rect_fixed_height, color, MutableComponent};
+use crate::{
+    application::{preferences::ThemeConfig, Store},
+    components::{InputAction, InputPopupComponent, LayoutArea},
+    events::{key::Keys, keymap::Scope, EventState},
+    sql::{
+        data_diff::{DataDiff, RowDiff, SyncDirection},
+        value::Value,
+    },
+};
+use ratatui::{prelude::*, widgets::*};
+
+/// Popup listing the rows that differ between two tables.
+pub struct DataDiffViewComponent {
+    diff: DataDiff,
+    /// Built once, the diff never changes while it is shown.
+    lines: Vec<Line<'static>>,
+    offset: usize,
+    /// Where to write the sync script, once asked for.
+    path_popup: Option<(InputPopupComponent, SyncDirection)>,
+}
+
+impl DataDiffViewComponent {
+    pub fn new(diff: DataDiff, theme_config: &ThemeConfig) -> Self {
+        let lines = lines(&diff, theme_config);
+        DataDiffViewComponent {
+            diff,
+            lines,
+            offset: 0,
+            path_popup: None,
+        }
+    }
+
+    /// None while the path of a script is typed.
+    pub fn keymap_scope(&self) -> Option<Scope> {
+        self.path_popup.is_none().then_some(Scope::DataDiff)
+    }
+
+    fn export(&self, path: &str, direction: SyncDirection, store: &mut Store) {
+        match std::fs::write(path, self.diff.sync_script(direction)) {
+            Ok(()) => store.success(&format!("Sync script written to {}.", path)),
+            Err(e) => store.error(&format!("Unable to write {} : {}", path, e)),
+        }
+    }
+}
+
+/// Summary of the diff then one line per row that differs.
+fn lines(diff: &DataDiff, theme_config: &ThemeConfig) -> Vec<Line<'static>> {
+    let removed = Style::new().fg(color::from_rgb(theme_config.log_error_color));
+    let added = Style::new().fg(color::from_rgb(theme_config.log_success_color));
+    let changed = Style::new().fg(color::from_rgb(theme_config.warning_color));
+    let mut lines = vec![
+        Line::from(format!("< {}", diff.left)),
+        Line::from(format!("> {}", diff.right)),
+        Line::from(format!(
+            "{} only on the left, {} only on the right, {} changed, out of {} and {} rows matched on ({})",
+            diff.only_left(),
+            diff.only_right(),
+            diff.changed(),
+            diff.counts.0,
+            diff.counts.1,
+            diff.key.join(", ")
+        ))
+        .style(Style::new().italic()),
+        Line::default(),
+    ];
+    if diff.rows.is_empty() {
+        lines.push(Line::from("Both tables hold the same rows.").style(Style::new().italic()));
+    }
+
+    for row in &diff.rows {
+        let line = match row {
+            RowDiff::OnlyLeft(values) => Line::from(vec![
+                Span::from("< ").style(removed),
+                Span::from(pairs(&diff.left_columns, values)),
+            ]),
+            RowDiff::OnlyRight(values) => Line::from(vec![
+                Span::from("> ").style(added),
+                Span::from(pairs(&diff.right_columns, values)),
+            ]),
+            RowDiff::Changed { key, cells } => {
+                let mut spans = vec![
+                    Span::from("~ ").style(changed),
+                    Span::from(format!("{} :", pairs(&diff.key, key))),
+                ];
+                for (column, left, right) in cells {
+                    spans.push(Span::from(format!(" {} ", column)));
+                    spans.push(Span::from(left.to_string()).style(removed));
+                    spans.push(Span::from(" -> "));
+                    spans.push(Span::from(right.to_string()).style(added));
+                }
+                Line::from(spans)
+            }
+        };
+        lines.push(line);
+    }
+    lines
+}
+
+/// `column=value` for each column, in a single line.
+fn pairs(columns: &[String], values: &[Value]) -> String {
+    columns
+        .iter()
+        .zip(values)
+        .map(|(column, value)| format!("{}={}", column, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl MutableComponent for DataDiffViewComponent {
+    fn event(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        if let Some((popup, direction)) = &mut self.path_popup {
+            match popup.event(input, store)? {
+                EventState::ConfirmedText(path) => {
+                    let direction = *direction;
+                    self.path_popup = None;
+                    self.export(path.trim(), direction, store);
+                }
+                EventState::Escaped => {
+                    self.path_popup = None;
+                }
+                _ => {}
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        match input {
+            Keys::Char('j') => {
+                if self.offset + 1 < self.lines.len() {
+                    self.offset += 1;
+                }
+            }
+            Keys::Char('k') => {
+                self.offset = self.offset.saturating_sub(1);
+            }
+            Keys::Char(c @ ('>' | '<')) => {
+                let (direction, side) = if *c == '>' {
+                    (SyncDirection::LeftToRight, "right")
+                } else {
+                    (SyncDirection::RightToLeft, "left")
+                };
+                self.path_popup = Some((
+                    InputPopupComponent::new(
+                        format!("Save the script syncing the {} side to", side),
+                        format!("./sync_{}.sql", self.diff.table()),
+                        InputAction::Insert,
+                    ),
+                    direction,
+                ));
+            }
+            Keys::Esc => return Ok(EventState::Escaped),
+            _ => return Ok(EventState::Wasted),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn draw(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        _selected: bool,
+        store: &Store,
+        layout: &LayoutArea,
+    ) -> anyhow::Result<()> {
+        let container = Block::default()
+            .title("Data diff")
+            .title_bottom(
+                "<j/k>: Scroll, <>>: Sync the right side, <<>: Sync the left side, <Esc>: Close",
+            )
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default().fg(self.get_color(store.preference.theme_config.selected_color)),
+            )
+            .padding(Padding::horizontal(1))
+            .border_type(BorderType::Rounded);
+
+        // only the lines in view are drawn, a paragraph scrolls by u16
+        let visible = self
+            .lines
+            .iter()
+            .skip(self.offset)
+            .take(area.height as usize)
+            .cloned()
+            .collect::<Vec<_>>();
+        let text = Paragraph::new(visible)
+            .block(container)
+            .style(Style::new().fg(self.get_color(store.preference.theme_config.unselected_color)));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(text, area);
+
+        if let Some((popup, _)) = &mut self.path_popup {
+            popup.draw(
+                frame,
+                centered_rect_fixed_height(layout.main_area[0], 50, 3),
+                true,
+                store,
+                layout,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,18 +1,26 @@
-use super::{centered_rect, HelpContentText, MutableComponent};
+use super::{centered_rect, centered_rect_fixed_height, list_mouse, MutableComponent};
 use crate::{
     application::{AppAction, Store, UpdateAction},
-    components::LayoutArea,
+    components::{FieldKind, FormField, FormPopupComponent, InputAction, LayoutArea},
     events::{key::Keys, EventState},
-    sql::{database::Database, tables::Tables},
+    sql::{
+        completion::Catalog,
+        database::Database,
+        schema_diff::{SchemaDiff, SchemaSide},
+        tables::Tables,
+    },
 };
-use std::collections::HashMap;
 
+use crossterm::event::MouseEvent;
 use ratatui::{prelude::*, widgets::*};
 
 #[derive(Debug, Clone)]
 pub struct DatabaseListComponent {
     list_state: ListState,
     selected: isize,
+    diff_popup: Option<FormPopupComponent>,
+    /// Connections offered in the diff form, in the order of its choices.
+    diff_targets: Vec<usize>,
 }
 
 impl DatabaseListComponent {
@@ -20,12 +28,160 @@ impl DatabaseListComponent {
         DatabaseListComponent {
             list_state: ListState::default(),
             selected: -1,
+            diff_popup: None,
+            diff_targets: Vec::new(),
         }
     }
+
+    /// Make the database at `index` the current one and load its tables.
+    pub fn select(&mut self, index: usize, store: &mut Store) -> anyhow::Result<()> {
+        store
+            .actions_tx
+            .send(UpdateAction::SendAppAction(AppAction::SendResetTableList))?;
+        self.list_state.select(Some(index));
+        store.database_list.current_database = Some(index);
+
+        let current_db = store.database_list.list[index].clone();
+        let pool = store.user_data.connection_list.get_pool()?;
+        let dialect = store.user_data.connection_list.get_dialect()?;
+        let actions_tx = store.actions_tx.clone();
+
+        Catalog::load(
+            pool.clone(),
+            actions_tx.clone(),
+            dialect,
+            current_db.clone(),
+        );
+        Tables::get_tables(pool, actions_tx, dialect, current_db);
+        self.selected = index as isize;
+        Ok(())
+    }
+
+    /// Ask which database the highlighted one is compared to, among the open
+    /// connections.
+    fn open_diff_form(&mut self, store: &mut Store) -> anyhow::Result<()> {
+        let Some(database) = self
+            .list_state
+            .selected()
+            .and_then(|i| store.database_list.list.get(i))
+        else {
+            return Ok(());
+        };
+        let (connection, targets) = FormField::open_connections("Connection", store)?;
+
+        self.diff_targets = targets;
+        self.diff_popup = Some(FormPopupComponent::new(
+            format!("Compare the schema of {} with", database),
+            vec![
+                connection,
+                FormField::new("Database", database.clone(), FieldKind::Text),
+            ],
+            InputAction::Insert,
+        ));
+        store.is_lock = true;
+        Ok(())
+    }
+
+    fn start_diff(&self, values: Vec<String>, store: &mut Store) -> anyhow::Result<()> {
+        let Some(database) = self
+            .list_state
+            .selected()
+            .and_then(|i| store.database_list.list.get(i))
+        else {
+            return Ok(());
+        };
+        let connection_list = &store.user_data.connection_list;
+        let side = |index: usize, database: String| {
+            let connection = &connection_list.list[index];
+            anyhow::Ok(SchemaSide {
+                label: connection.name.clone(),
+                pool: connection_list.open_pool(index)?,
+                dialect: connection.dialect(),
+                database,
+                mode: connection.mode,
+            })
+        };
+
+        let current = connection_list
+            .current_connection
+            .ok_or(anyhow::anyhow!("No current connection is set."))?;
+        let left = side(current, database.clone())?;
+
+        let database = values[1].trim();
+        if database.is_empty() {
+            anyhow::bail!("A database is required to compare with.");
+        }
+        let target = self
+            .diff_popup
+            .as_ref()
+            .and_then(|popup| popup.fields[0].choice_position(&values[0]))
+            .and_then(|i| self.diff_targets.get(i))
+            .ok_or(anyhow::anyhow!("No connection to compare with."))?;
+        let right = side(*target, database.to_string())?;
+
+        store.log(&format!(
+            "Comparing the schemas of {} and {}...",
+            left.name(),
+            right.name()
+        ));
+        SchemaDiff::compare(left, right, store.actions_tx.clone());
+        Ok(())
+    }
+
+    pub fn event_popup(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        let Some(popup) = &mut self.diff_popup else {
+            return Ok(EventState::Wasted);
+        };
+        match popup.event(input, store)? {
+            EventState::ConfirmedForm(values) => {
+                if let Err(e) = self.start_diff(values, store) {
+                    store.error(&e.to_string());
+                    return Ok(EventState::Consumed);
+                }
+                self.diff_popup = None;
+                store.is_lock = false;
+            }
+            EventState::Escaped => {
+                self.diff_popup = None;
+                store.is_lock = false;
+            }
+            _ => {}
+        }
+        Ok(EventState::Consumed)
+    }
+
+    /// Popups are drawn once every pane has been, so they stay on top.
+    pub fn draw_popup(
+        &mut self,
+        frame: &mut Frame,
+        selected: bool,
+        store: &Store,
+        layout: &LayoutArea,
+    ) -> anyhow::Result<()> {
+        if let Some(popup) = &mut self.diff_popup {
+            if selected {
+                let height = popup.height();
+                popup.draw(
+                    frame,
+                    centered_rect_fixed_height(layout.main_area[0], 50, height),
+                    true,
+                    store,
+                    layout,
+                )?;
+            } else {
+                self.diff_popup = None;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl MutableComponent for DatabaseListComponent {
     fn event(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        if let EventState::Consumed = self.event_popup(input, store)? {
+            return Ok(EventState::Consumed);
+        }
+
         if store.database_list.list.len() > 0 {
             match input {
                 Keys::Char('j') => {
@@ -54,32 +210,46 @@ impl MutableComponent for DatabaseListComponent {
                             .select(Some(store.database_list.list.len() - 1));
                     }
                 }
+                Keys::Home => self.list_state.select(Some(0)),
+                Keys::End => self
+                    .list_state
+                    .select(Some(store.database_list.list.len() - 1)),
                 Keys::Char('r') => {
                     let pool = store.user_data.connection_list.get_pool().unwrap();
+                    let dialect = store.user_data.connection_list.get_dialect()?;
                     let actions_tx = store.actions_tx.clone();
-                    Database::get_databases(pool, actions_tx);
+                    Database::get_databases(pool, actions_tx, dialect);
                 }
                 Keys::Enter => {
-                    store
-                        .actions_tx
-                        .send(UpdateAction::SendAppAction(AppAction::SendResetTableList))?;
                     if let Some(index) = self.list_state.selected() {
-                        store.database_list.current_database = Some(index);
-
-                        let current_db = store.database_list.list[index].clone();
-                        let pool = store.user_data.connection_list.get_pool().unwrap();
-                        let actions_tx = store.actions_tx.clone();
-
-                        Tables::get_tables(pool, actions_tx, current_db);
-                        self.selected = index as isize;
+                        self.select(index, store)?;
                     }
                 }
+                Keys::Char('D') => {
+                    self.open_diff_form(store)?;
+                }
                 _ => return Ok(EventState::Wasted),
             }
         }
         Ok(EventState::Consumed)
     }
 
+    fn mouse(
+        &mut self,
+        mouse: &MouseEvent,
+        area: Rect,
+        store: &mut Store,
+    ) -> anyhow::Result<EventState> {
+        let len = store.database_list.list.len();
+        match list_mouse(mouse, area, &self.list_state, len) {
+            Some(index) => {
+                self.list_state.select(Some(index));
+                Ok(EventState::Consumed)
+            }
+            None => Ok(EventState::Wasted),
+        }
+    }
+
     fn draw(
         &mut self,
         frame: &mut ratatui::prelude::Frame,
@@ -88,8 +258,13 @@ impl MutableComponent for DatabaseListComponent {
         store: &Store,
         _layout: &LayoutArea,
     ) -> anyhow::Result<()> {
+        let title = store
+            .user_data
+            .connection_list
+            .get_dialect()
+            .map_or("Databases", |dialect| dialect.databases_title());
         let container = Block::default()
-            .title("Databases")
+            .title(title)
             .borders(Borders::ALL)
             .border_style(
                 Style::default().fg(self.selected_color(selected, store.preference.theme_config)),
@@ -120,12 +295,12 @@ impl MutableComponent for DatabaseListComponent {
                 selected_idx,
                 store.database_list.list.len()
             )))
-            .highlight_style(Style::default().reversed())
+            .highlight_style(self.selection_style(store.preference.theme_config))
             .repeat_highlight_symbol(true);
 
             frame.render_stateful_widget(list, area, &mut self.list_state);
         } else {
-            let no_data = Paragraph::new("No databases")
+            let no_data = Paragraph::new(format!("No {}", title.to_lowercase()))
                 .style(Style::new().italic())
                 .centered();
 
@@ -136,9 +311,3 @@ impl MutableComponent for DatabaseListComponent {
         Ok(())
     }
 }
-
-impl HelpContentText for DatabaseListComponent {
-    fn help_content_text() -> HashMap<&'static str, &'static str> {
-        HashMap::from([("r", "Reload the database's list")])
-    }
-}
@@ -0,0 +1,251 @@
+use super::MutableComponent;
+use crate::{
+    application::Store,
+    components::{InputAction, LayoutArea},
+    events::{key::Keys, EventState},
+};
+
+use ratatui::{prelude::*, widgets::*};
+
+#[derive(Debug, Clone)]
+pub enum FieldKind {
+    Text,
+    Password,
+    Choice(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct FormField {
+    pub label: String,
+    pub value: String,
+    pub kind: FieldKind,
+    cursor_pos: usize,
+}
+
+impl FormField {
+    pub fn new(label: &str, value: String, kind: FieldKind) -> Self {
+        let cursor_pos = value.chars().count();
+        FormField {
+            label: label.to_string(),
+            value,
+            kind,
+            cursor_pos,
+        }
+    }
+
+    /// Choice of the open connections, the current one first selected. The
+    /// index of each choice in the connection list comes along.
+    pub fn open_connections(label: &str, store: &Store) -> anyhow::Result<(Self, Vec<usize>)> {
+        let connection_list = &store.user_data.connection_list;
+        let current = connection_list
+            .current_connection
+            .ok_or(anyhow::anyhow!("No current connection is set."))?;
+        let targets = connection_list
+            .list
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.pool.is_some())
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let choices = targets
+            .iter()
+            .map(|&i| format!("{}. {}", i + 1, connection_list.list[i].name))
+            .collect::<Vec<_>>();
+        let value = choices[targets.iter().position(|&i| i == current).unwrap_or(0)].clone();
+        Ok((
+            FormField::new(label, value, FieldKind::Choice(choices)),
+            targets,
+        ))
+    }
+
+    /// Position of the value among the choices.
+    pub fn choice_position(&self, value: &str) -> Option<usize> {
+        match &self.kind {
+            FieldKind::Choice(choices) => choices.iter().position(|c| c == value),
+            _ => None,
+        }
+    }
+
+    fn byte_pos(&self) -> usize {
+        self.value
+            .char_indices()
+            .nth(self.cursor_pos)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+
+    fn displayed_value(&self) -> String {
+        match &self.kind {
+            FieldKind::Password => "*".repeat(self.value.chars().count()),
+            FieldKind::Choice(_) => format!("< {} >", self.value),
+            FieldKind::Text => self.value.clone(),
+        }
+    }
+
+    fn cycle_choice(&mut self, forward: bool) {
+        if let FieldKind::Choice(choices) = &self.kind {
+            let index = choices.iter().position(|c| *c == self.value).unwrap_or(0);
+            let next = if forward {
+                (index + 1) % choices.len()
+            } else {
+                (index + choices.len() - 1) % choices.len()
+            };
+            self.value = choices[next].clone();
+        }
+    }
+}
+
+/// A popup made of several labelled inputs, one per line.
+#[derive(Debug, Clone)]
+pub struct FormPopupComponent {
+    pub title: String,
+    pub fields: Vec<FormField>,
+    pub action: InputAction,
+    selected: usize,
+    offset: usize,
+}
+
+impl FormPopupComponent {
+    pub fn new(title: String, fields: Vec<FormField>, action: InputAction) -> Self {
+        FormPopupComponent {
+            title,
+            fields,
+            action,
+            selected: 0,
+            offset: 0,
+        }
+    }
+
+    pub fn height(&self) -> u16 {
+        self.fields.len() as u16 + 2
+    }
+
+    fn label_width(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|f| f.label.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl MutableComponent for FormPopupComponent {
+    fn event(&mut self, input: &Keys, _store: &mut Store) -> anyhow::Result<EventState> {
+        let field = &mut self.fields[self.selected];
+        match input {
+            Keys::Tab | Keys::ArrowDown => {
+                self.selected = (self.selected + 1) % self.fields.len();
+            }
+            Keys::BackTab | Keys::ArrowUp => {
+                self.selected = (self.selected + self.fields.len() - 1) % self.fields.len();
+            }
+            Keys::ArrowLeft => {
+                if let FieldKind::Choice(_) = field.kind {
+                    field.cycle_choice(false);
+                } else if field.cursor_pos > 0 {
+                    field.cursor_pos -= 1;
+                }
+            }
+            Keys::ArrowRight => {
+                if let FieldKind::Choice(_) = field.kind {
+                    field.cycle_choice(true);
+                } else if field.cursor_pos < field.value.chars().count() {
+                    field.cursor_pos += 1;
+                }
+            }
+            Keys::Backspace => {
+                if let FieldKind::Choice(_) = field.kind {
+                    return Ok(EventState::Consumed);
+                }
+                if field.cursor_pos > 0 {
+                    field.cursor_pos -= 1;
+                    let pos = field.byte_pos();
+                    field.value.remove(pos);
+                }
+            }
+            Keys::Char(c) => {
+                if let FieldKind::Choice(_) = field.kind {
+                    if *c == ' ' {
+                        field.cycle_choice(true);
+                    }
+                    return Ok(EventState::Consumed);
+                }
+                let pos = field.byte_pos();
+                field.value.insert(pos, *c);
+                field.cursor_pos += 1;
+            }
+            Keys::Enter => {
+                return Ok(EventState::ConfirmedForm(
+                    self.fields.iter().map(|f| f.value.clone()).collect(),
+                ));
+            }
+            Keys::Esc => {
+                return Ok(EventState::Escaped);
+            }
+            _ => return Ok(EventState::Wasted),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn draw(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        _selected: bool,
+        store: &Store,
+        _layout: &LayoutArea,
+    ) -> anyhow::Result<()> {
+        let container = Block::default()
+            .title(&*self.title)
+            .title_bottom("<Tab>: Next field, <Enter>: Confirm, <Esc>: Cancel")
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default().fg(self.get_color(store.preference.theme_config.selected_color)),
+            )
+            .padding(Padding::left(1))
+            .border_type(BorderType::Rounded);
+
+        // scroll so the selected field stays visible when they don't all fit
+        let visible = area.height.saturating_sub(2).max(1) as usize;
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + visible {
+            self.offset = self.selected + 1 - visible;
+        }
+
+        let label_width = self.label_width();
+        let lines = self
+            .fields
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .map(|(index, field)| {
+                let label = Span::from(format!("{:width$} : ", field.label, width = label_width));
+                let label = if index == self.selected {
+                    label.style(
+                        Style::default()
+                            .fg(self.get_color(store.preference.theme_config.selected_color)),
+                    )
+                } else {
+                    label
+                };
+                Line::from(vec![label, Span::from(field.displayed_value())])
+            })
+            .collect::<Vec<Line>>();
+
+        let text = Paragraph::new(lines)
+            .block(container)
+            .style(Style::new().fg(self.get_color(store.preference.theme_config.unselected_color)));
+
+        frame.render_widget(Clear, area);
+        let field = &self.fields[self.selected];
+        if !matches!(field.kind, FieldKind::Choice(_)) {
+            frame.set_cursor(
+                area.x + 2 + (label_width + 3 + field.cursor_pos) as u16,
+                area.y + 1 + (self.selected - self.offset) as u16,
+            );
+        }
+        frame.render_widget(text, area);
+        Ok(())
+    }
+}
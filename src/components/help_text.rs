@@ -1,4 +1,4 @@
-use super::Component;
+use super::{color, Component};
 use crate::{
     application::Store,
     events::{key::Keys, EventState},
@@ -26,8 +26,8 @@ impl Component for HelpTextComponent {
         store: &Store,
     ) -> anyhow::Result<()> {
         let color_text = store.preference.theme_config.help_text_color;
-        let help_text = Paragraph::new("<C-h>: Move left, <C-j>: Move down, <C-k>: Move up, <C-l>: Move right, q: Quit, ?: Help")
-            .style(Style::default().fg(Color::Rgb(color_text[0], color_text[1], color_text[2])));
+        let help_text = Paragraph::new("<C-h>: Move left, <C-j>: Move down, <C-k>: Move up, <C-l>: Move right, <A-h>/<A-l>: Switch workspace, <A-w>: Close workspace, <A-o>: Saved queries, q: Quit, ?: Help")
+            .style(Style::default().fg(color::from_rgb(color_text)));
 
         frame.render_widget(help_text, area);
 
@@ -6,40 +6,23 @@ use crate::{
 };
 
 use ratatui::{prelude::*, widgets::*};
-use std::collections::HashMap;
-
-pub trait HelpContentText {
-    fn help_content_text() -> HashMap<&'static str, &'static str>;
-}
 
 pub struct HelpViewComponent {
     pub id: u32,
-    content: HashMap<&'static str, &'static str>,
+    /// Keys of the pane with what they do, the global ones coming last.
+    content: Vec<(Vec<Keys>, &'static str)>,
     title: String,
     scrollstate: ScrollbarState,
     tablestate: TableState,
 }
 
 impl HelpViewComponent {
-    pub fn new(
-        id: u32,
-        title: String,
-        content: Option<HashMap<&'static str, &'static str>>,
-    ) -> HelpViewComponent {
-        if let Some(hc) = content {
-            return HelpViewComponent {
-                id,
-                content: hc.clone(),
-                title,
-                scrollstate: ScrollbarState::new(hc.len()).position(0),
-                tablestate: TableState::default(),
-            };
-        }
+    pub fn new(id: u32, title: String, content: Vec<(Vec<Keys>, &'static str)>) -> Self {
         HelpViewComponent {
             id,
-            content: HashMap::new(),
+            scrollstate: ScrollbarState::new(content.len()).position(0),
+            content,
             title,
-            scrollstate: ScrollbarState::new(0).position(0),
             tablestate: TableState::default(),
         }
     }
@@ -48,7 +31,7 @@ impl HelpViewComponent {
 impl MutableComponent for HelpViewComponent {
     fn event(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
         match input {
-            Keys::Char('j') => {
+            Keys::Char('j') if !self.content.is_empty() => {
                 if let Some(i) = self.tablestate.selected() {
                     let index = if i == self.content.len() - 1 {
                         0
@@ -63,7 +46,7 @@ impl MutableComponent for HelpViewComponent {
                     self.scrollstate = self.scrollstate.position(0);
                 }
             }
-            Keys::Char('k') => {
+            Keys::Char('k') if !self.content.is_empty() => {
                 if let Some(i) = self.tablestate.selected() {
                     let index = if i == 0 {
                         self.content.len() - 1
@@ -81,12 +64,12 @@ impl MutableComponent for HelpViewComponent {
             Keys::Enter => {
                 if let Some(i) = self.tablestate.selected() {
                     store.selected_pane = store.previous_selected_pane;
-                    let key_str = *self.content.keys().collect::<Vec<_>>()[i];
-                    let key = Keys::Char(key_str.chars().nth(0).unwrap());
-                    store
-                        .event_handler
-                        .send_key(key)
-                        .expect("An error occured while sending keys to thread.");
+                    for key in &self.content[i].0 {
+                        store
+                            .event_handler
+                            .send_key(*key)
+                            .expect("An error occured while sending keys to thread.");
+                    }
                 }
             }
             _ => return Ok(EventState::Wasted),
@@ -109,12 +92,24 @@ impl MutableComponent for HelpViewComponent {
                 Style::default().fg(self.selected_color(selected, store.preference.theme_config)),
             );
 
+        let sequences = self
+            .content
+            .iter()
+            .map(|(keys, _)| Keys::format_sequence(keys))
+            .collect::<Vec<_>>();
+        let key_width = sequences
+            .iter()
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or(0);
+
         let table = Table::new(
-            self.content
-                .iter()
-                .map(|(key, value)| {
+            sequences
+                .into_iter()
+                .zip(&self.content)
+                .map(|(sequence, (_, value))| {
                     Row::new(vec![
-                        Cell::from(*key).style(
+                        Cell::from(sequence).style(
                             Style::default()
                                 .fg(self.get_color(store.preference.theme_config.help_key_color)),
                         ),
@@ -126,10 +121,10 @@ impl MutableComponent for HelpViewComponent {
                     ])
                 })
                 .collect::<Vec<Row>>(),
-            [Constraint::Length(4), Constraint::Fill(1)],
+            [Constraint::Length(key_width as u16), Constraint::Fill(1)],
         )
         .block(container)
-        .highlight_style(Style::default().reversed());
+        .highlight_style(self.selection_style(store.preference.theme_config));
 
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("▲"))
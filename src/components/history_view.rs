@@ -0,0 +1,152 @@
+use super::{centered_rect, list_mouse, MutableComponent};
+use crate::{
+    application::Store,
+    components::{widgets::sql_highlight::highlight, LayoutArea},
+    events::{key::Keys, EventState},
+};
+use chrono::Local;
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{prelude::*, widgets::*};
+
+/// A statement sent to a connection.
+struct HistoryEntry {
+    sql: String,
+    connection: String,
+    /// When it was sent, `HH:MM:SS`.
+    at: String,
+}
+
+/// Statements run since dbv was started, the last one first.
+pub struct HistoryViewComponent {
+    entries: Vec<HistoryEntry>,
+    list_state: ListState,
+}
+
+impl Default for HistoryViewComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistoryViewComponent {
+    pub fn new() -> Self {
+        HistoryViewComponent {
+            entries: Vec::new(),
+            list_state: ListState::default(),
+        }
+    }
+
+    pub fn push(&mut self, connection: &str, sql: &str) {
+        self.entries.insert(
+            0,
+            HistoryEntry {
+                sql: sql.to_string(),
+                connection: connection.to_string(),
+                at: Local::now().format("%H:%M:%S").to_string(),
+            },
+        );
+        // stay on the statement which was selected
+        if let Some(selected) = self.list_state.selected() {
+            self.list_state.select(Some(selected + 1));
+        }
+    }
+}
+
+impl MutableComponent for HistoryViewComponent {
+    fn event(&mut self, input: &Keys, _store: &mut Store) -> anyhow::Result<EventState> {
+        let count = self.entries.len();
+        if count == 0 {
+            return Ok(EventState::Wasted);
+        }
+        match input {
+            Keys::Char('j') | Keys::ArrowDown => {
+                let index = self.list_state.selected().map_or(0, |i| (i + 1) % count);
+                self.list_state.select(Some(index));
+            }
+            Keys::Char('k') | Keys::ArrowUp => {
+                let index = self
+                    .list_state
+                    .selected()
+                    .map_or(0, |i| (i + count - 1) % count);
+                self.list_state.select(Some(index));
+            }
+            Keys::Home => self.list_state.select(Some(0)),
+            Keys::End => self.list_state.select(Some(count - 1)),
+            // the statement is put back in the command pane
+            Keys::Enter => {
+                if let Some(index) = self.list_state.selected() {
+                    return Ok(EventState::ConfirmedText(self.entries[index].sql.clone()));
+                }
+            }
+            _ => return Ok(EventState::Wasted),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn mouse(
+        &mut self,
+        mouse: &MouseEvent,
+        area: Rect,
+        _store: &mut Store,
+    ) -> anyhow::Result<EventState> {
+        // entries span several lines, only the wheel moves through them
+        if !matches!(
+            mouse.kind,
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp
+        ) {
+            return Ok(EventState::Wasted);
+        }
+        match list_mouse(mouse, area, &self.list_state, self.entries.len()) {
+            Some(index) => {
+                self.list_state.select(Some(index));
+                Ok(EventState::Consumed)
+            }
+            None => Ok(EventState::Wasted),
+        }
+    }
+
+    fn draw(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        selected: bool,
+        store: &Store,
+        _layout: &LayoutArea,
+    ) -> anyhow::Result<()> {
+        let theme_config = store.preference.theme_config;
+        let mut container = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.selected_color(selected, theme_config)))
+            .border_type(BorderType::Rounded);
+
+        if self.entries.is_empty() {
+            let no_data = Paragraph::new("No history").style(Style::new().italic());
+            frame.render_widget(container, area);
+            frame.render_widget(no_data, centered_rect(area, 11, 20));
+            return Ok(());
+        }
+
+        if selected {
+            container = container.title_bottom("<Enter>: Edit in the command pane");
+        }
+        let help_color = self.get_color(theme_config.help_text_color);
+        let items = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut text = Text::from(
+                    Line::from(format!("{} {}", entry.at, entry.connection))
+                        .style(Style::new().fg(help_color)),
+                );
+                text.extend(highlight(&entry.sql, theme_config.syntax));
+                ListItem::new(text)
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(container)
+            .highlight_symbol("> ")
+            .highlight_spacing(HighlightSpacing::Always);
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+        Ok(())
+    }
+}
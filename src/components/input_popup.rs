@@ -19,6 +19,7 @@ pub struct InputPopupComponent {
     pub content: String,
     pub action: InputAction,
     pub cursor_pos: usize,
+    pub masked: bool,
 }
 
 impl InputPopupComponent {
@@ -29,14 +30,20 @@ impl InputPopupComponent {
             content,
             action,
             cursor_pos,
+            masked: false,
         }
     }
+    pub fn masked(mut self) -> Self {
+        self.masked = true;
+        self
+    }
     pub fn default() -> Self {
         InputPopupComponent {
             title: String::new(),
             content: String::new(),
             action: InputAction::Insert,
             cursor_pos: 0,
+            masked: false,
         }
     }
 }
@@ -114,12 +121,22 @@ impl MutableComponent for InputPopupComponent {
             .padding(Padding::left(1))
             .border_type(BorderType::Rounded);
 
-        let text = Paragraph::new(&*self.content)
+        let content = if self.masked {
+            "*".repeat(self.content.chars().count())
+        } else {
+            self.content.clone()
+        };
+        let text = Paragraph::new(content)
             .block(container)
             .style(Style::new().fg(self.get_color(store.preference.theme_config.unselected_color)));
 
         frame.render_widget(Clear, area);
-        frame.set_cursor(area.x + 2 + self.cursor_pos as u16, area.y + 1);
+        // the cursor is a byte offset, one cell is drawn per character
+        let column = self
+            .content
+            .get(..self.cursor_pos)
+            .map_or(self.cursor_pos, |before| before.chars().count());
+        frame.set_cursor(area.x + 2 + column as u16, area.y + 1);
         frame.render_widget(text, area);
         Ok(())
     }
@@ -1,48 +1,318 @@
-use ratatui::{prelude::*, Frame};
+use ratatui::{layout::Position, prelude::*};
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
+/// Fewest cells a pane keeps when its border is dragged.
+const MIN_PANE_SIZE: u16 = 5;
+
+/// Border between two panes, dragged with the mouse or moved with the keys
+/// to share the space between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Border {
+    /// Between the lists and the panes of the records, on their left or
+    /// above them.
+    Columns,
+    /// After the list of the index, connections or databases.
+    Lists(usize),
+    /// Between the records and the command pane.
+    Records,
+    /// Between the command pane and the log.
+    Log,
+}
+
+/// Fill weights of the panes, with the ones shown. The tabs and the command
+/// pane keep their height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaneSizes {
+    pub columns: [u16; 2],
+    pub lists: [u16; 3],
+    #[serde(rename = "recordsLog")]
+    pub records_log: [u16; 2],
+    #[serde(rename = "showLog")]
+    pub show_log: bool,
+    /// The line of the keys under the panes.
+    #[serde(rename = "showHelp")]
+    pub show_help: bool,
+    /// Width under which the lists go above the records, side by side.
+    #[serde(rename = "verticalUnder")]
+    pub vertical_under: u16,
+}
+
+impl Default for PaneSizes {
+    fn default() -> Self {
+        PaneSizes {
+            columns: [2, 6],
+            lists: [3, 3, 3],
+            records_log: [2, 1],
+            show_log: true,
+            show_help: true,
+            vertical_under: 100,
+        }
+    }
+}
+
+/// Cells given to the first of two panes sharing `total` when the border
+/// between them sits `offset` cells after the start of the first.
+fn share(offset: u16, total: u16) -> [u16; 2] {
+    let first = offset
+        .min(total.saturating_sub(MIN_PANE_SIZE))
+        .max(MIN_PANE_SIZE.min(total));
+    [first, total - first]
+}
+
+impl PaneSizes {
+    /// Move `border` under the mouse, the panes around it sharing their
+    /// space anew.
+    pub fn drag(&mut self, border: Border, layout: &LayoutArea, column: u16, row: u16) {
+        let position = if layout.moves_across(border) {
+            column
+        } else {
+            row
+        };
+        self.move_border(border, layout, position);
+    }
+
+    /// Make `pane` bigger by `delta` cells along `direction`, or smaller when
+    /// it is negative. Nothing is done for a side the pane can't grow on.
+    pub fn resize(
+        &mut self,
+        pane: (u8, u8),
+        layout: &LayoutArea,
+        direction: Direction,
+        delta: i16,
+    ) {
+        let along_lists = (direction == Direction::Vertical) != layout.vertical;
+        let (border, sign) = match (pane, along_lists) {
+            ((0, index @ (0 | 1)), true) => (Border::Lists(index as usize), 1),
+            ((0, 2), true) => (Border::Lists(1), -1),
+            ((0, _), false) => (Border::Columns, 1),
+            // the records and the log share the rows whatever the layout
+            ((1, 1), _) if direction == Direction::Vertical && self.show_log => {
+                (Border::Records, 1)
+            }
+            ((1, 2), _) if direction == Direction::Vertical && self.show_log => (Border::Log, -1),
+            ((1, _), false) => (Border::Columns, -1),
+            _ => return,
+        };
+        let position = layout.border_position(border) as i16 + sign * delta;
+        self.move_border(border, layout, position.max(0) as u16);
+    }
+
+    /// Move `border` to `position`, the column or the row of the last cell
+    /// of the pane before it.
+    fn move_border(&mut self, border: Border, layout: &LayoutArea, position: u16) {
+        // the lists go along the rows, or the columns when they are above
+        // the records
+        let start = |area: Rect| if layout.vertical { area.x } else { area.y };
+        let size = |area: Rect| {
+            if layout.vertical {
+                area.width
+            } else {
+                area.height
+            }
+        };
+        match border {
+            Border::Columns => {
+                let area = layout.main_area[0];
+                self.columns = if layout.vertical {
+                    share(position.saturating_sub(area.y) + 1, area.height)
+                } else {
+                    share(position.saturating_sub(area.x) + 1, area.width)
+                };
+            }
+            Border::Lists(index) => {
+                let [above, below] = [layout.left_area[index], layout.left_area[index + 1]];
+                let shared = share(
+                    position.saturating_sub(start(above)) + 1,
+                    size(above) + size(below),
+                );
+                self.lists = [0, 1, 2].map(|i| size(layout.left_area[i]));
+                self.lists[index] = shared[0];
+                self.lists[index + 1] = shared[1];
+            }
+            Border::Records | Border::Log => {
+                let [records, command, log] = [
+                    layout.right_area[1],
+                    layout.right_area[2],
+                    layout.right_area[3],
+                ];
+                // the command pane moves along, the border of the log
+                // being `command.height` under the one of the records
+                let offset = match border {
+                    Border::Records => position.saturating_sub(records.y) + 1,
+                    _ => position.saturating_sub(records.y + command.height) + 1,
+                };
+                self.records_log = share(offset, records.height + log.height);
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct LayoutArea {
     pub main_area: Rc<[Rect]>,
     pub sub_main_area: Rc<[Rect]>,
     pub left_area: Rc<[Rect]>,
     pub right_area: Rc<[Rect]>,
+    /// The lists are above the records, side by side.
+    pub vertical: bool,
+    /// One pane takes the whole screen, with the command pane under it.
+    pub zoomed: bool,
 }
 
 impl LayoutArea {
-    pub fn new(frame: &Frame) -> Self {
+    /// Panes sized by `sizes` in `area`, the whole screen, or the `zoom` one
+    /// alone.
+    pub fn new(area: Rect, sizes: &PaneSizes, zoom: Option<(u8, u8)>) -> Self {
+        let vertical = area.width < sizes.vertical_under;
+        let (across, along) = if vertical {
+            (Direction::Vertical, Direction::Horizontal)
+        } else {
+            (Direction::Horizontal, Direction::Vertical)
+        };
         let main_area = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Fill(1), Constraint::Length(1)])
-            .split(frame.size());
+            .constraints(vec![
+                Constraint::Fill(1),
+                Constraint::Length(sizes.show_help as u16),
+            ])
+            .split(area);
 
         let sub_main_area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Fill(2), Constraint::Fill(6)])
+            .direction(across)
+            .constraints(sizes.columns.map(Constraint::Fill))
             .split(main_area[0]);
 
         let left_area = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![
-                Constraint::Fill(3),
-                Constraint::Fill(3),
-                Constraint::Fill(3),
-            ])
+            .direction(along)
+            .constraints(sizes.lists.map(Constraint::Fill))
             .split(sub_main_area[0]);
         let right_area = Layout::default()
             .direction(Direction::Vertical)
             .constraints(vec![
                 Constraint::Length(3),
-                Constraint::Fill(2),
+                Constraint::Fill(sizes.records_log[0]),
                 Constraint::Length(3),
-                Constraint::Fill(1),
+                if sizes.show_log {
+                    Constraint::Fill(sizes.records_log[1])
+                } else {
+                    Constraint::Length(0)
+                },
             ])
             .split(sub_main_area[1]);
-        LayoutArea {
+        let layout = LayoutArea {
             main_area,
             sub_main_area,
             left_area,
             right_area,
+            vertical,
+            zoomed: false,
+        };
+        match zoom {
+            Some(pane) => layout.zoom(pane),
+            None => layout,
+        }
+    }
+
+    /// `pane` over the whole screen, the other ones being left empty but the
+    /// command pane, kept under it to type in.
+    fn zoom(self, pane: (u8, u8)) -> Self {
+        let split =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).split(self.main_area[0]);
+        let [zoomed, command] = [split[0], split[1]];
+        let area_of = |other: (u8, u8)| {
+            if other == pane {
+                zoomed
+            } else {
+                Rect::default()
+            }
+        };
+        LayoutArea {
+            left_area: (0..3).map(|i| area_of((0, i))).collect(),
+            right_area: [area_of((1, 0)), area_of((1, 1)), command, area_of((1, 2))]
+                .into_iter()
+                .collect(),
+            zoomed: true,
+            ..self
+        }
+    }
+
+    /// The whole screen the panes are laid out in.
+    pub fn area(&self) -> Rect {
+        self.main_area[0].union(self.main_area[1])
+    }
+
+    /// Whether `border` is moved along the columns, the other ones along the
+    /// rows.
+    fn moves_across(&self, border: Border) -> bool {
+        match border {
+            Border::Columns => !self.vertical,
+            Border::Lists(_) => self.vertical,
+            Border::Records | Border::Log => false,
+        }
+    }
+
+    /// Column or row of the last cell of the pane before `border`.
+    fn border_position(&self, border: Border) -> u16 {
+        let before = match border {
+            Border::Columns => self.sub_main_area[0],
+            Border::Lists(index) => self.left_area[index],
+            Border::Records => self.right_area[1],
+            Border::Log => self.right_area[2],
+        };
+        if self.moves_across(border) {
+            before.right().saturating_sub(1)
+        } else {
+            before.bottom().saturating_sub(1)
+        }
+    }
+
+    /// Pane under the mouse with the area it is drawn in.
+    pub fn pane_at(&self, column: u16, row: u16) -> Option<((u8, u8), Rect)> {
+        let position = Position { x: column, y: row };
+        let panes = [
+            ((0, 0), self.left_area[0]),
+            ((0, 1), self.left_area[1]),
+            ((0, 2), self.left_area[2]),
+            ((1, 0), self.right_area[0]),
+            ((1, 1), self.right_area[1]),
+            ((1, 3), self.right_area[2]),
+            ((1, 2), self.right_area[3]),
+        ];
+        panes.into_iter().find(|(_, area)| area.contains(position))
+    }
+
+    /// Border under the mouse, on the last cell of the pane before it or the
+    /// first one of the pane after.
+    pub fn border_at(&self, column: u16, row: u16) -> Option<Border> {
+        if self.zoomed || !self.main_area[0].contains(Position { x: column, y: row }) {
+            return None;
+        }
+        let on = |border: Border, after: Rect| {
+            let position = if self.moves_across(border) {
+                (column, after.x)
+            } else {
+                (row, after.y)
+            };
+            position.0 == self.border_position(border) || position.0 == position.1
+        };
+        if on(Border::Columns, self.sub_main_area[1]) {
+            return Some(Border::Columns);
+        }
+        if self.sub_main_area[0].contains(Position { x: column, y: row }) {
+            (0..2)
+                .find(|&i| on(Border::Lists(i), self.left_area[i + 1]))
+                .map(Border::Lists)
+        } else if self.right_area[3].is_empty() {
+            // the log is hidden, the records take what is left
+            None
+        } else if on(Border::Records, self.right_area[2]) {
+            Some(Border::Records)
+        } else if on(Border::Log, self.right_area[3]) {
+            Some(Border::Log)
+        } else {
+            None
         }
     }
 }
@@ -1,10 +1,11 @@
-use super::MutableComponent;
+use super::{color, list_mouse, MutableComponent};
 use crate::{
-    application::Store,
+    application::{preferences::ThemeConfig, Store},
     events::{key::Keys, EventState},
     components::LayoutArea
 };
 
+use crossterm::event::MouseEvent;
 use ratatui::{prelude::*, widgets::*};
 
 #[derive(Debug, Clone)]
@@ -15,34 +16,21 @@ pub enum LogContent {
     Success(String),
 }
 
-impl<'a> From<LogContent> for ListItem<'a> {
-    fn from(val: LogContent) -> Self {
-        match val {
-            LogContent::Info(content) => {
-                return ListItem::new(Line::from(vec![
-                    Span::from("[INFO] ").style(Style::new().blue()),
-                    content.into(),
-                ]));
-            }
+impl LogContent {
+    /// Line of the log, its level tag colored by the theme.
+    pub fn to_list_item(self, theme_config: &ThemeConfig) -> ListItem<'static> {
+        let (tag, color, content) = match self {
+            LogContent::Info(content) => ("[INFO] ", theme_config.log_info_color, content),
             LogContent::Success(content) => {
-                return ListItem::new(Line::from(vec![
-                    Span::from("[SUCCESS] ").style(Style::new().green()),
-                    content.into(),
-                ]));
-            }
-            LogContent::Error(content) => {
-                return ListItem::new(Line::from(vec![
-                    Span::from("[ERROR] ").style(Style::new().red()),
-                    content.into(),
-                ]));
+                ("[SUCCESS] ", theme_config.log_success_color, content)
             }
-            LogContent::Debug(content) => {
-                return ListItem::new(Line::from(vec![
-                    Span::from("[DEBUG] ").style(Style::new().gray()),
-                    content.into(),
-                ]));
-            }
-        }
+            LogContent::Error(content) => ("[ERROR] ", theme_config.log_error_color, content),
+            LogContent::Debug(content) => ("[DEBUG] ", theme_config.log_debug_color, content),
+        };
+        ListItem::new(Line::from(vec![
+            Span::from(tag).style(Style::new().fg(color::from_rgb(color))),
+            content.into(),
+        ]))
     }
 }
 
@@ -93,12 +81,38 @@ impl MutableComponent for LogViewComponent {
                     self.position_scroll = store.log_contents().len() - 1;
                 }
             }
+            Keys::Home | Keys::End => {
+                let index = if *input == Keys::Home {
+                    0
+                } else {
+                    store.log_contents().len().saturating_sub(1)
+                };
+                self.list_state.select(Some(index));
+                self.position_scroll = index;
+            }
             _ => return Ok(EventState::Wasted),
         }
 
         Ok(EventState::Consumed)
     }
 
+
+    fn mouse(
+        &mut self,
+        mouse: &MouseEvent,
+        area: Rect,
+        store: &mut Store,
+    ) -> anyhow::Result<EventState> {
+        match list_mouse(mouse, area, &self.list_state, store.log_contents().len()) {
+            Some(index) => {
+                self.list_state.select(Some(index));
+                self.position_scroll = index;
+                Ok(EventState::Consumed)
+            }
+            None => Ok(EventState::Wasted),
+        }
+    }
+
     fn draw(
         &mut self,
         frame: &mut Frame,
@@ -119,10 +133,10 @@ impl MutableComponent for LogViewComponent {
             store
                 .log_contents()
                 .iter()
-                .map(|item| LogContent::from(item.clone())),
+                .map(|item| item.clone().to_list_item(&store.preference.theme_config)),
         )
         .block(container)
-        .highlight_style(Style::default().reversed());
+        .highlight_style(self.selection_style(store.preference.theme_config));
 
         let mut scrollbar_state = ScrollbarState::default()
             .content_length(store.log_contents().len())
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use ratatui::{layout::Rect, prelude::*, Frame};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{layout::Rect, prelude::*, widgets::ListState, Frame};
 
 use crate::{
     application::Store,
@@ -7,15 +8,22 @@ use crate::{
     events::{key::Keys, EventState},
 };
 
+pub mod color;
 pub mod connection_list;
+pub mod data_diff_view;
 pub mod database_list;
 pub mod help_text;
 pub mod help_view;
+pub mod history_view;
 pub mod records_view;
+pub mod schema_diff_view;
 pub mod log_view;
+pub mod parameter_form;
+pub mod query_library;
 pub mod tab;
 pub mod table_list;
 pub mod input_popup;
+pub mod form_popup;
 pub mod layout;
 
 pub mod widgets;
@@ -24,37 +32,32 @@ pub mod command;
 
 pub use command::CommandComponent;
 pub use connection_list::ConnectionListComponent;
+pub use data_diff_view::DataDiffViewComponent;
 pub use database_list::DatabaseListComponent;
 pub use help_text::HelpTextComponent;
 pub use help_view::HelpViewComponent;
+pub use history_view::HistoryViewComponent;
 pub use records_view::RecordsViewComponent;
+pub use schema_diff_view::SchemaDiffViewComponent;
 pub use log_view::LogViewComponent;
 pub use log_view::LogContent;
+pub use parameter_form::ParameterFormComponent;
 pub use tab::TabComponent;
 pub use table_list::TableListComponent;
 pub use input_popup::InputPopupComponent;
 pub use input_popup::InputAction;
+pub use form_popup::{FieldKind, FormField, FormPopupComponent};
 pub use layout::LayoutArea;
 
-pub use help_view::HelpContentText;
-
 fn selected_color(selected: bool, theme_config: ThemeConfig) -> Color {
     if selected {
-        Color::Rgb(
-            theme_config.selected_color[0],
-            theme_config.selected_color[1],
-            theme_config.selected_color[2],
-        )
+        color::from_rgb(theme_config.selected_color)
     } else {
-        Color::Rgb(
-            theme_config.unselected_color[0],
-            theme_config.unselected_color[1],
-            theme_config.unselected_color[2],
-        )
+        color::from_rgb(theme_config.unselected_color)
     }
 }
 fn get_color(rgb: [u8; 3]) -> Color {
-    Color::Rgb(rgb[0], rgb[1], rgb[2])
+    color::from_rgb(rgb)
 }
 
 pub trait Component {
@@ -73,6 +76,9 @@ pub trait Component {
     fn get_color(&self, rgb: [u8; 3]) -> Color {
         get_color(rgb)
     }
+    fn selection_style(&self, theme_config: ThemeConfig) -> Style {
+        color::selection_style(&theme_config)
+    }
 }
 
 pub trait MutableComponent {
@@ -86,12 +92,46 @@ pub trait MutableComponent {
         layout: &LayoutArea
     ) -> Result<()>;
 
+    /// Click or wheel over the pane, drawn in `area`.
+    fn mouse(
+        &mut self,
+        _mouse: &MouseEvent,
+        _area: Rect,
+        _store: &mut Store,
+    ) -> Result<EventState> {
+        Ok(EventState::Wasted)
+    }
+
     fn selected_color(&self, selected: bool, theme_config: ThemeConfig) -> Color {
         selected_color(selected, theme_config)
     }
     fn get_color(&self, rgb: [u8; 3]) -> Color {
         get_color(rgb)
     }
+    fn selection_style(&self, theme_config: ThemeConfig) -> Style {
+        color::selection_style(&theme_config)
+    }
+}
+
+/// Item of a bordered list of one line items the mouse selects, the wheel
+/// moving the selection without wrapping.
+pub fn list_mouse(mouse: &MouseEvent, area: Rect, state: &ListState, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    match mouse.kind {
+        MouseEventKind::ScrollDown => Some(state.selected().map_or(0, |i| (i + 1).min(len - 1))),
+        MouseEventKind::ScrollUp => Some(state.selected().map_or(0, |i| i.saturating_sub(1))),
+        MouseEventKind::Down(MouseButton::Left) => {
+            let top = area.y + 1;
+            if mouse.row < top || mouse.row + 1 >= area.bottom() {
+                return None;
+            }
+            let index = state.offset() + (mouse.row - top) as usize;
+            (index < len).then_some(index)
+        }
+        _ => None,
+    }
 }
 
 // Need to be adapted : percent_y may not be convenient for different screen size
@@ -115,3 +155,17 @@ pub fn centered_rect(r: Rect, percent_x: u16, percent_y: u16) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+pub fn centered_rect_fixed_height(r: Rect, percent_x: u16, height: u16) -> Rect {
+    let height = height.min(r.height);
+    let area = Rect::new(r.x, r.y + (r.height - height) / 2, r.width, height);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(area)[1]
+}
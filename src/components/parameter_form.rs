@@ -0,0 +1,137 @@
+use super::{centered_rect_fixed_height, MutableComponent};
+use crate::{
+    application::{parameters::ParameterHistory, Store},
+    components::{FieldKind, FormField, FormPopupComponent, InputAction, LayoutArea},
+    events::{key::Keys, EventState},
+    sql::{
+        dialect::Dialect,
+        parameters::{self, BoundStatement, ParameterType},
+    },
+};
+use ratatui::prelude::*;
+use std::collections::HashMap;
+
+/// Choices of the field telling whether the value of a parameter is kept.
+const REMEMBER: [&str; 2] = ["yes", "no"];
+
+/// Whether the name of a parameter hints at a value not to be shown nor
+/// written to the history, like `:password`.
+fn secret(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["password", "passwd", "pwd", "secret", "token"]
+        .iter()
+        .any(|hint| name.contains(hint))
+}
+
+/// Asks the values of the parameters of the statements about to be run, a
+/// value, the type to bind it as and whether to remember it for each one.
+pub struct ParameterFormComponent {
+    form: FormPopupComponent,
+    statements: Vec<String>,
+    names: Vec<String>,
+    dialect: Dialect,
+    history: ParameterHistory,
+}
+
+impl ParameterFormComponent {
+    pub fn new(statements: Vec<String>, names: Vec<String>, dialect: Dialect) -> Self {
+        let history = ParameterHistory::load().unwrap_or_default();
+        let types = ParameterType::ALL
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>();
+        let fields = names
+            .iter()
+            .flat_map(|name| {
+                let remembered = history.get(name);
+                let value = remembered.map(|r| r.value.clone()).unwrap_or_default();
+                let kind = remembered.map_or(ParameterType::guess(name), |r| r.kind);
+                let secret = remembered.map_or(secret(name), |r| r.secret);
+                let field = if secret {
+                    FieldKind::Password
+                } else {
+                    FieldKind::Text
+                };
+                [
+                    FormField::new(name, value, field),
+                    FormField::new(
+                        &format!("{} type", name),
+                        kind.to_string(),
+                        FieldKind::Choice(types.clone()),
+                    ),
+                    FormField::new(
+                        &format!("{} remembered", name),
+                        REMEMBER[secret as usize].to_string(),
+                        FieldKind::Choice(REMEMBER.map(String::from).to_vec()),
+                    ),
+                ]
+            })
+            .collect();
+        ParameterFormComponent {
+            form: FormPopupComponent::new(
+                format!("Parameters of {} statement(s)", statements.len()),
+                fields,
+                InputAction::Insert,
+            ),
+            statements,
+            names,
+            dialect,
+            history,
+        }
+    }
+
+    /// Bind the values of the form to the statements, and remember the ones
+    /// not opted out of for the next time.
+    pub fn bind(
+        &mut self,
+        values: Vec<String>,
+        store: &mut Store,
+    ) -> anyhow::Result<Vec<BoundStatement>> {
+        let mut bound = HashMap::new();
+        for (name, fields) in self.names.iter().zip(values.chunks(3)) {
+            let [text, kind, remember] = fields else {
+                anyhow::bail!("Unexpected parameter form.");
+            };
+            let kind = kind.parse::<ParameterType>()?;
+            bound.insert(name.clone(), kind.parse(name, text)?);
+            if remember == REMEMBER[0] {
+                self.history.remember(name.clone(), text.clone(), kind);
+            } else {
+                self.history.forget(name.clone(), kind);
+            }
+        }
+        let statements = self
+            .statements
+            .iter()
+            .map(|statement| parameters::bind(statement, self.dialect, &bound))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if let Err(e) = self.history.save() {
+            store.error(&format!("Unable to remember the parameters : {}", e));
+        }
+        Ok(statements)
+    }
+}
+
+impl MutableComponent for ParameterFormComponent {
+    fn event(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        self.form.event(input, store)
+    }
+
+    fn draw(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        selected: bool,
+        store: &Store,
+        layout: &LayoutArea,
+    ) -> anyhow::Result<()> {
+        let height = self.form.height();
+        self.form.draw(
+            frame,
+            centered_rect_fixed_height(area, 50, height),
+            selected,
+            store,
+            layout,
+        )
+    }
+}
@@ -0,0 +1,293 @@
+use super::{centered_rect_fixed_height, MutableComponent};
+use crate::{
+    application::{
+        library::{QueryLibrary, SavedQuery},
+        Store,
+    },
+    components::{InputAction, InputPopupComponent, LayoutArea},
+    events::{key::Keys, keymap::Scope, EventState},
+    utils,
+};
+use ratatui::{prelude::*, widgets::*};
+
+/// What was picked in the library.
+pub enum LibraryChoice {
+    /// Put the query in the command pane.
+    Load(SavedQuery),
+    /// Run the query as if it had been typed.
+    Run(SavedQuery),
+}
+
+/// Popup listing the saved queries of the current connection and the global
+/// ones, narrowed down by a fuzzy search.
+pub struct QueryLibraryComponent {
+    library: QueryLibrary,
+    /// Id of the current connection.
+    connection: Option<String>,
+    search: String,
+    /// Indices of the queries matching the search, best first.
+    matches: Vec<usize>,
+    list_state: ListState,
+    rename_popup: Option<InputPopupComponent>,
+    pub choice: Option<LibraryChoice>,
+}
+
+impl QueryLibraryComponent {
+    pub fn new(connection: Option<String>) -> anyhow::Result<Self> {
+        let mut component = QueryLibraryComponent {
+            library: QueryLibrary::load()?,
+            connection,
+            search: String::new(),
+            matches: Vec::new(),
+            list_state: ListState::default(),
+            rename_popup: None,
+            choice: None,
+        };
+        component.refresh();
+        Ok(component)
+    }
+
+    /// None while a query is renamed.
+    pub fn keymap_scope(&self) -> Option<Scope> {
+        self.rename_popup.is_none().then_some(Scope::SavedQueries)
+    }
+
+    fn refresh(&mut self) {
+        let visible = self.library.visible(self.connection.as_deref());
+        self.matches = if self.search.trim().is_empty() {
+            visible
+        } else {
+            let mut scored = visible
+                .into_iter()
+                .filter_map(|i| {
+                    utils::fuzzy_score(&self.search, &self.library.queries[i].search_text())
+                        .map(|score| (i, score))
+                })
+                .collect::<Vec<_>>();
+            scored.sort_by_key(|(_, score)| -score);
+            scored.into_iter().map(|(i, _)| i).collect()
+        };
+        let selected = match self.list_state.selected() {
+            _ if self.matches.is_empty() => None,
+            Some(i) => Some(i.min(self.matches.len() - 1)),
+            None => Some(0),
+        };
+        self.list_state.select(selected);
+    }
+
+    /// Index in the library of the query selected in the list.
+    fn selected(&self) -> Option<usize> {
+        self.list_state.selected().map(|i| self.matches[i])
+    }
+
+    fn save(&self, store: &mut Store) {
+        if let Err(e) = self.library.save() {
+            store.error(&format!("Unable to save the queries : {}", e));
+        }
+    }
+
+    fn event_rename(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<()> {
+        let Some(popup) = &mut self.rename_popup else {
+            return Ok(());
+        };
+        match popup.event(input, store)? {
+            EventState::ConfirmedText(name) => {
+                self.rename_popup = None;
+                let Some(index) = self.selected() else {
+                    return Ok(());
+                };
+                let previous = self.library.queries[index].path();
+                match self.library.rename(index, &name) {
+                    Ok(()) => {
+                        self.save(store);
+                        store.success(&format!(
+                            "Saved query {} renamed to {}.",
+                            previous,
+                            self.library.queries[index].path()
+                        ));
+                        self.refresh();
+                    }
+                    Err(e) => store.error(&e.to_string()),
+                }
+            }
+            EventState::Escaped => {
+                self.rename_popup = None;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Where the query is run, `global` or the name of its connection.
+    fn scope(&self, connection: Option<&String>, store: &Store) -> String {
+        match connection {
+            None => String::from("global"),
+            Some(id) => store
+                .user_data
+                .connection_list
+                .list
+                .iter()
+                .find(|c| c.id == *id)
+                .map_or(String::from("unknown connection"), |c| c.name.clone()),
+        }
+    }
+}
+
+impl MutableComponent for QueryLibraryComponent {
+    fn event(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        if self.rename_popup.is_some() {
+            self.event_rename(input, store)?;
+            return Ok(EventState::Consumed);
+        }
+
+        let count = self.matches.len();
+        match input {
+            Keys::Esc => return Ok(EventState::Escaped),
+            Keys::ArrowDown if count > 0 => {
+                let index = self.list_state.selected().map_or(0, |i| (i + 1) % count);
+                self.list_state.select(Some(index));
+            }
+            Keys::ArrowUp if count > 0 => {
+                let index = self
+                    .list_state
+                    .selected()
+                    .map_or(0, |i| (i + count - 1) % count);
+                self.list_state.select(Some(index));
+            }
+            Keys::Enter => {
+                if let Some(index) = self.selected() {
+                    let query = self.library.queries[index].clone();
+                    self.choice = Some(LibraryChoice::Load(query));
+                }
+            }
+            Keys::CtrlChar('r') => {
+                if let Some(index) = self.selected() {
+                    let query = self.library.queries[index].clone();
+                    self.choice = Some(LibraryChoice::Run(query));
+                }
+            }
+            Keys::CtrlChar('e') => {
+                if let Some(index) = self.selected() {
+                    let query = &self.library.queries[index];
+                    self.rename_popup = Some(InputPopupComponent::new(
+                        format!("Rename {}", query.path()),
+                        query.name.clone(),
+                        InputAction::Edit,
+                    ));
+                }
+            }
+            Keys::CtrlChar('d') => {
+                if let Some(index) = self.selected() {
+                    let query = self.library.remove(index);
+                    self.save(store);
+                    store.success(&format!("Saved query {} deleted.", query.path()));
+                    self.refresh();
+                }
+            }
+            Keys::Backspace => {
+                self.search.pop();
+                self.refresh();
+            }
+            Keys::Char(c) => {
+                self.search.push(*c);
+                self.list_state.select(Some(0));
+                self.refresh();
+            }
+            _ => {}
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn draw(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        _selected: bool,
+        store: &Store,
+        layout: &LayoutArea,
+    ) -> anyhow::Result<()> {
+        let theme_config = store.preference.theme_config;
+        let container = Block::default()
+            .title(format!(
+                "Saved queries ({} of {})",
+                self.matches.len(),
+                self.library.visible(self.connection.as_deref()).len()
+            ))
+            .title_bottom("<Enter>: Load, <C-r>: Run, <C-e>: Rename, <C-d>: Delete, <Esc>: Close")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.get_color(theme_config.selected_color)))
+            .padding(Padding::horizontal(1))
+            .border_type(BorderType::Rounded);
+
+        frame.render_widget(Clear, area);
+        let inner = container.inner(area);
+        frame.render_widget(container, area);
+        let [search_area, list_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+
+        let search = Paragraph::new(Line::from(vec![
+            Span::from("Search : ")
+                .style(Style::new().fg(self.get_color(theme_config.selected_color))),
+            Span::from(self.search.as_str()),
+        ]))
+        .block(Block::default().borders(Borders::BOTTOM));
+        frame.render_widget(search, search_area);
+        if self.rename_popup.is_none() {
+            frame.set_cursor(
+                search_area.x + 9 + self.search.chars().count() as u16,
+                search_area.y,
+            );
+        }
+
+        if self.matches.is_empty() {
+            let empty = if self.search.is_empty() {
+                "No query is saved yet, <A-s> in the command pane saves one."
+            } else {
+                "No saved query matches the search."
+            };
+            frame.render_widget(
+                Paragraph::new(empty).style(Style::new().italic()),
+                list_area,
+            );
+        } else {
+            let help_color = self.get_color(theme_config.help_text_color);
+            let items = self
+                .matches
+                .iter()
+                .map(|&i| {
+                    let query = &self.library.queries[i];
+                    let mut spans = vec![Span::from(query.path()).bold()];
+                    for tag in &query.tags {
+                        spans.push(
+                            Span::from(format!(" #{}", tag)).style(Style::new().fg(help_color)),
+                        );
+                    }
+                    spans.push(Span::from(format!(
+                        " ({})",
+                        self.scope(query.connection.as_ref(), store)
+                    )));
+                    let preview = query.sql.split_whitespace().collect::<Vec<_>>().join(" ");
+                    spans.push(
+                        Span::from(format!("  {}", preview)).style(Style::new().fg(help_color)),
+                    );
+                    ListItem::new(Line::from(spans))
+                })
+                .collect::<Vec<_>>();
+            let list = List::new(items)
+                .style(Style::new().fg(self.get_color(theme_config.unselected_color)))
+                .highlight_style(self.selection_style(store.preference.theme_config));
+            frame.render_stateful_widget(list, list_area, &mut self.list_state);
+        }
+
+        if let Some(popup) = &mut self.rename_popup {
+            popup.draw(
+                frame,
+                centered_rect_fixed_height(layout.main_area[0], 50, 3),
+                true,
+                store,
+                layout,
+            )?;
+        }
+        Ok(())
+    }
+}
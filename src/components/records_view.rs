@@ -1,20 +1,30 @@
 use super::{centered_rect, MutableComponent};
 use crate::{
-    application::Store,
+    application::{editor, Store},
     components::{
-        widgets::custom_table::{CustomTable, CustomTableState},
+        widgets::custom_table::{column_widths, CustomTable, CustomTableState},
         LayoutArea,
     },
     events::{key::Keys, EventState},
-    sql::parser::SqlParser,
+    sql::{parser::SqlParser, value::Value},
 };
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{prelude::*, widgets::*};
 use sqlx::{any::AnyRow, Row as SqlRow};
 
+/// Cells the selected column is widened or shrunk by.
+const COLUMN_STEP: i16 = 2;
+
 pub struct RecordsViewComponent {
     header: Vec<String>,
     rows: Vec<Vec<String>>,
+    /// The rows as read, with their types.
+    values: Vec<Vec<Value>>,
     total: Option<i64>,
+    /// Table the records were read from, `None` for the result of a query.
+    table: Option<String>,
+    /// Columns the rows of the table are told apart by, empty without a key.
+    primary_key: Vec<String>,
 
     table_state: CustomTableState,
 
@@ -26,42 +36,136 @@ impl<'a> RecordsViewComponent {
         RecordsViewComponent {
             header: Vec::new(),
             rows: Vec::new(),
+            values: Vec::new(),
             total: None,
+            table: None,
+            primary_key: Vec::new(),
             table_state: CustomTableState::default(),
             scrollbar_state_right: ScrollbarState::default(),
         }
     }
 
     pub fn set_header(&mut self, header: Vec<String>) {
-        self.table_state = self.table_state.header_length(header.len());
+        self.table_state = self.table_state.clone().header_length(header.len());
         self.header = header;
     }
 
     pub fn set_body(&mut self, content: Vec<AnyRow>) {
         let mut rows: Vec<Vec<String>> = Vec::new();
+        let mut values: Vec<Vec<Value>> = Vec::new();
         for r in content.iter() {
-            rows.push(
-                r.columns()
-                    .iter()
-                    .map(|col| {
-                        return SqlParser::convert_from_sqlx_row(&col, &r);
-                    })
-                    .collect::<Vec<String>>(),
-            )
+            let (shown, read) = r
+                .columns()
+                .iter()
+                .enumerate()
+                .map(|(i, col)| match Value::from_row(r, i) {
+                    // the parser reads a NULL as the type of its column
+                    Ok(Value::Null) => ("NULL".to_string(), Value::Null),
+                    Ok(value) => (SqlParser::convert_from_sqlx_row(col, r), value),
+                    // a cell no type fits is kept as the text shown
+                    Err(_) => {
+                        let shown = SqlParser::convert_from_sqlx_row(col, r);
+                        (shown.clone(), Value::Text(shown))
+                    }
+                })
+                .unzip();
+            rows.push(shown);
+            values.push(read);
         }
+        self.values = values;
         let content_length = content.len();
         self.rows = rows;
         self.scrollbar_state_right = self.scrollbar_state_right.content_length(content_length);
-        self.table_state = self.table_state.content_length(content_length);
+        self.table_state = self.table_state.clone().content_length(content_length);
+        // sized anew from the rows once drawn
+        self.table_state.widths = Vec::new();
     }
 
     pub fn set_total(&mut self, total: Option<i64>) {
         self.total = total;
     }
+
+    /// Header, rows read with their types and count of the records of the
+    /// table, of which only the page read is held.
+    pub fn records(&self) -> (&[String], &[Vec<Value>], Option<i64>) {
+        (&self.header, &self.values, self.total)
+    }
+
+    pub fn set_table(&mut self, table: Option<String>, primary_key: Vec<String>) {
+        self.table = table;
+        self.primary_key = primary_key;
+    }
+
+    /// Open the selected value in `$VISUAL` or `$EDITOR`. Once changed in
+    /// the records of a table, an UPDATE of the row matched on its primary
+    /// key is drafted, to be reviewed in the command pane.
+    fn edit_cell(&mut self, store: &mut Store) -> anyhow::Result<EventState> {
+        let Some((x, y)) = self.table_state.selected().filter(|(_, y)| *y > 0) else {
+            return Ok(EventState::Consumed);
+        };
+        let column = self.table_state.offset_x + x;
+        let row = &self.values[self.table_state.offset_y + y - 1];
+        let value = match &row[column] {
+            Value::Null => String::new(),
+            Value::Text(text) => text.clone(),
+            Value::Bytes(_) => {
+                anyhow::bail!(
+                    "The value of {} can't be shown as text.",
+                    self.header[column]
+                )
+            }
+            other => other.to_string(),
+        };
+        let edited = editor::edit(&value, "txt", store)?;
+        if edited == value {
+            store.log("The value is unchanged.");
+            return Ok(EventState::Consumed);
+        }
+
+        let connection_list = &store.user_data.connection_list;
+        let (Some(table), Some(index), Some(database)) = (
+            &self.table,
+            connection_list.current_connection,
+            store.database_list.current(),
+        ) else {
+            anyhow::bail!("Only the records of a table can be updated.");
+        };
+        if self.primary_key.is_empty() {
+            anyhow::bail!(
+                "{} has no primary key, its rows can't be told apart.",
+                table
+            );
+        }
+        let dialect = connection_list.list[index].dialect();
+        let conditions = self
+            .primary_key
+            .iter()
+            .map(|key| {
+                let position = self
+                    .header
+                    .iter()
+                    .position(|h| h == key)
+                    .ok_or(anyhow::anyhow!(
+                        "The column {} of the primary key isn't shown.",
+                        key
+                    ))?;
+                anyhow::Ok(row[position].condition(dialect, key))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let update = format!(
+            "UPDATE {} SET {} = {} WHERE {};",
+            dialect.qualified_name(database, table),
+            dialect.quote_identifier(&self.header[column]),
+            Value::Text(edited).literal(dialect),
+            conditions.join(" AND ")
+        );
+        store.log("Review the UPDATE in the command pane, <Enter> runs it.");
+        Ok(EventState::ConfirmedText(update))
+    }
 }
 
 impl<'a> MutableComponent for RecordsViewComponent {
-    fn event(&mut self, input: &Keys, _store: &mut Store) -> anyhow::Result<EventState> {
+    fn event(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
         let rows_len = self.rows.len();
         if rows_len > 0 {
             match input {
@@ -100,6 +204,20 @@ impl<'a> MutableComponent for RecordsViewComponent {
                     }
                     self.table_state.prev_row();
                 }
+                Keys::Home => {
+                    self.scrollbar_state_right.first();
+                    self.table_state.first_row();
+                }
+                Keys::End => {
+                    self.scrollbar_state_right.last();
+                    self.table_state.last_row();
+                }
+                Keys::Char('>') => self.table_state.resize_col(COLUMN_STEP),
+                Keys::Char('<') => self.table_state.resize_col(-COLUMN_STEP),
+                Keys::Char('e') => match self.edit_cell(store) {
+                    Ok(state) => return Ok(state),
+                    Err(e) => store.error(&e.to_string()),
+                },
                 _ => return Ok(EventState::Wasted),
             }
             return Ok(EventState::Consumed);
@@ -108,6 +226,36 @@ impl<'a> MutableComponent for RecordsViewComponent {
         }
     }
 
+
+    fn mouse(
+        &mut self,
+        mouse: &MouseEvent,
+        area: Rect,
+        store: &mut Store,
+    ) -> anyhow::Result<EventState> {
+        if self.rows.is_empty() {
+            return Ok(EventState::Wasted);
+        }
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.event(&Keys::Char('j'), store),
+            MouseEventKind::ScrollUp => self.event(&Keys::Char('k'), store),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some((x, y)) = self
+                    .table_state
+                    .position_at(area, mouse.column, mouse.row)
+                else {
+                    return Ok(EventState::Wasted);
+                };
+                self.table_state.select(Some((x, y)));
+                self.scrollbar_state_right = self
+                    .scrollbar_state_right
+                    .position((self.table_state.offset_y + y).saturating_sub(1));
+                Ok(EventState::Consumed)
+            }
+            _ => Ok(EventState::Wasted),
+        }
+    }
+
     fn draw(
         &mut self,
         frame: &mut Frame,
@@ -142,18 +290,25 @@ impl<'a> MutableComponent for RecordsViewComponent {
                 .begin_symbol(Some("▲"))
                 .end_symbol(Some("▼"));
 
-            let content_style =
-                Style::default().fg(self.get_color(store.preference.theme_config.unselected_color));
+            if self.table_state.widths.len() != self.header.len() {
+                let max_width = store.preference.max_column_width;
+                self.table_state.widths = column_widths(&self.header, &self.rows, max_width);
+            }
+
+            let theme_config = store.preference.theme_config;
+            let fg = |rgb| Style::default().fg(self.get_color(rgb));
 
             let table = CustomTable::new()
                 .block(container)
                 .header_block_style(
-                    Style::default().fg(self.selected_color(true, store.preference.theme_config)),
+                    Style::default().fg(self.selected_color(true, theme_config)),
                 )
                 .header(self.header.clone())
-                .header_style(content_style)
-                .rows_style(content_style)
-                .highlight_style(content_style.reversed())
+                .header_style(fg(theme_config.header_color))
+                .rows_style(fg(theme_config.string_color))
+                .null_style(fg(theme_config.null_color).italic())
+                .number_style(fg(theme_config.number_color))
+                .highlight_style(self.selection_style(theme_config))
                 .rows(self.rows.clone());
 
             frame.render_stateful_widget(table, area, &mut self.table_state);
@@ -0,0 +1,396 @@
+use super::{centered_rect_fixed_height, MutableComponent};
+use crate::{
+    application::Store,
+    components::{InputAction, InputPopupComponent, LayoutArea},
+    events::{key::Keys, keymap::Scope, EventState},
+    sql::{
+        data_diff::SyncDirection,
+        guard::{self, AccessMode},
+        schema::{ColumnSchema, ForeignKeySchema, IndexSchema},
+        schema_diff::{SchemaChange, SchemaDiff, SchemaSide},
+    },
+};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::BTreeMap;
+
+/// A difference, `<` when only the left side has it, `>` when only the
+/// right one does and `~` when both have it in another way.
+struct Node {
+    marker: char,
+    text: String,
+}
+
+/// A table which differs, with what differs in it.
+struct TableNode {
+    node: Node,
+    children: Vec<Node>,
+    collapsed: bool,
+}
+
+/// What the migration script is asked for.
+enum Prompt {
+    Save(InputPopupComponent),
+    Run(InputPopupComponent),
+}
+
+/// Popup showing how two schemas differ as a tree of tables, from which the
+/// migration script is saved or run.
+pub struct SchemaDiffViewComponent {
+    diff: SchemaDiff,
+    tables: Vec<TableNode>,
+    list_state: ListState,
+    direction: SyncDirection,
+    prompt: Option<Prompt>,
+}
+
+impl SchemaDiffViewComponent {
+    pub fn new(diff: SchemaDiff) -> Self {
+        let tables = Self::tree(&diff);
+        let mut list_state = ListState::default();
+        if !tables.is_empty() {
+            list_state.select(Some(0));
+        }
+        SchemaDiffViewComponent {
+            diff,
+            tables,
+            list_state,
+            direction: SyncDirection::LeftToRight,
+            prompt: None,
+        }
+    }
+
+    /// None while a path or the name of the database is typed.
+    pub fn keymap_scope(&self) -> Option<Scope> {
+        self.prompt.is_none().then_some(Scope::SchemaDiff)
+    }
+
+    /// The changes making the right side like the left one, read as
+    /// differences between both sides.
+    fn tree(diff: &SchemaDiff) -> Vec<TableNode> {
+        let mut tables: BTreeMap<String, TableNode> = BTreeMap::new();
+        for change in diff.changes(SyncDirection::LeftToRight) {
+            let table = change.table().to_string();
+            let entry = tables.entry(table.clone()).or_insert(TableNode {
+                node: Node {
+                    marker: '~',
+                    text: table.clone(),
+                },
+                children: Vec::new(),
+                collapsed: false,
+            });
+            let child = match change {
+                SchemaChange::CreateTable(name) => {
+                    let count = diff.left_schema.tables[&name].columns.len();
+                    entry.node = Node {
+                        marker: '<',
+                        text: format!("{} ({} columns)", name, count),
+                    };
+                    continue;
+                }
+                SchemaChange::DropTable(name) => {
+                    let count = diff.right_schema.tables[&name].columns.len();
+                    entry.node = Node {
+                        marker: '>',
+                        text: format!("{} ({} columns)", name, count),
+                    };
+                    continue;
+                }
+                SchemaChange::AddColumn(_, column) => Node {
+                    marker: '<',
+                    text: format!("column {}", describe_column(&column)),
+                },
+                SchemaChange::DropColumn(_, name) => {
+                    let column = diff.right_schema.tables[&table].column(&name);
+                    Node {
+                        marker: '>',
+                        text: format!("column {}", column.map(describe_column).unwrap_or(name)),
+                    }
+                }
+                SchemaChange::AlterColumn(_, right, left) => Node {
+                    marker: '~',
+                    text: format!(
+                        "column {} -> {}",
+                        describe_column(&left),
+                        describe_column(&right)
+                    ),
+                },
+                SchemaChange::AlterPrimaryKey(_, right, left) => Node {
+                    marker: '~',
+                    text: format!(
+                        "primary key {} -> {}",
+                        describe_key(&left),
+                        describe_key(&right)
+                    ),
+                },
+                SchemaChange::AddIndex(_, index) => Node {
+                    marker: '<',
+                    text: describe_index(&index),
+                },
+                SchemaChange::DropIndex(_, index) => Node {
+                    marker: '>',
+                    text: describe_index(&index),
+                },
+                SchemaChange::AddForeignKey(_, foreign_key) => Node {
+                    marker: '<',
+                    text: describe_foreign_key(&foreign_key),
+                },
+                SchemaChange::DropForeignKey(_, foreign_key) => Node {
+                    marker: '>',
+                    text: describe_foreign_key(&foreign_key),
+                },
+            };
+            entry.children.push(child);
+        }
+        tables.into_values().collect()
+    }
+
+    /// Lines on screen, with the table each one belongs to.
+    fn visible(&self) -> Vec<(usize, &Node, bool)> {
+        let mut lines = Vec::new();
+        for (index, table) in self.tables.iter().enumerate() {
+            lines.push((index, &table.node, false));
+            if !table.collapsed {
+                lines.extend(table.children.iter().map(|child| (index, child, true)));
+            }
+        }
+        lines
+    }
+
+    /// Where the migration runs, followed by the side it copies.
+    fn target(&self) -> (&SchemaSide, &SchemaSide) {
+        match self.direction {
+            SyncDirection::LeftToRight => (&self.diff.right, &self.diff.left),
+            SyncDirection::RightToLeft => (&self.diff.left, &self.diff.right),
+        }
+    }
+
+    fn run(&self, store: &mut Store) {
+        let (target, _) = self.target();
+        if target.mode == AccessMode::Guarded {
+            let destructive = guard::destructive(&self.diff.statements(self.direction));
+            if let Err(e) = guard::audit(&target.label, &target.database, &destructive) {
+                store.error(&format!("Unable to write the audit file : {}", e));
+                return;
+            }
+        }
+        store.log(&format!("Running the migration on {}...", target.name()));
+        self.diff.run(self.direction, store.actions_tx.clone());
+    }
+
+    fn save(&self, path: &str, store: &mut Store) {
+        match std::fs::write(path, self.diff.script(self.direction)) {
+            Ok(()) => store.success(&format!("Migration script written to {}.", path)),
+            Err(e) => store.error(&format!("Unable to write {} : {}", path, e)),
+        }
+    }
+
+    fn event_prompt(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<()> {
+        let (popup, run) = match &mut self.prompt {
+            Some(Prompt::Save(popup)) => (popup, false),
+            Some(Prompt::Run(popup)) => (popup, true),
+            None => return Ok(()),
+        };
+        match popup.event(input, store)? {
+            EventState::ConfirmedText(text) => {
+                self.prompt = None;
+                if !run {
+                    self.save(text.trim(), store);
+                } else if text.trim() == self.target().0.database {
+                    self.run(store);
+                } else {
+                    store.error("The database name doesn't match, nothing was run.");
+                }
+            }
+            EventState::Escaped => {
+                self.prompt = None;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn describe_column(column: &ColumnSchema) -> String {
+    let mut text = format!("{} {}", column.name, column.data_type);
+    if !column.nullable {
+        text.push_str(" not null");
+    }
+    if let Some(default) = &column.default {
+        text.push_str(&format!(" default {}", default));
+    }
+    text
+}
+
+fn describe_key(columns: &[String]) -> String {
+    if columns.is_empty() {
+        "none".to_string()
+    } else {
+        format!("({})", columns.join(", "))
+    }
+}
+
+fn describe_index(index: &IndexSchema) -> String {
+    format!(
+        "{}index {} ({})",
+        if index.unique { "unique " } else { "" },
+        index.name,
+        index.columns.join(", ")
+    )
+}
+
+fn describe_foreign_key(foreign_key: &ForeignKeySchema) -> String {
+    format!(
+        "foreign key ({}) -> {} ({})",
+        foreign_key.columns.join(", "),
+        foreign_key.foreign_table,
+        foreign_key.foreign_columns.join(", ")
+    )
+}
+
+impl MutableComponent for SchemaDiffViewComponent {
+    fn event(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        if self.prompt.is_some() {
+            self.event_prompt(input, store)?;
+            return Ok(EventState::Consumed);
+        }
+
+        let count = self.visible().len();
+        match input {
+            Keys::Char('j') if count > 0 => {
+                let index = self.list_state.selected().map_or(0, |i| (i + 1) % count);
+                self.list_state.select(Some(index));
+            }
+            Keys::Char('k') if count > 0 => {
+                let index = self
+                    .list_state
+                    .selected()
+                    .map_or(0, |i| (i + count - 1) % count);
+                self.list_state.select(Some(index));
+            }
+            Keys::Enter => {
+                let Some(selected) = self.list_state.selected() else {
+                    return Ok(EventState::Consumed);
+                };
+                let table = self.visible()[selected].0;
+                self.tables[table].collapsed = !self.tables[table].collapsed;
+                // stay on the table when its children fold away
+                let row = self.visible().iter().position(|(t, _, _)| *t == table);
+                self.list_state.select(row);
+            }
+            Keys::Tab => {
+                self.direction = match self.direction {
+                    SyncDirection::LeftToRight => SyncDirection::RightToLeft,
+                    SyncDirection::RightToLeft => SyncDirection::LeftToRight,
+                };
+            }
+            Keys::Char('s') => {
+                self.prompt = Some(Prompt::Save(InputPopupComponent::new(
+                    "Save the migration script to".to_string(),
+                    format!("./migrate_{}.sql", self.target().0.database),
+                    InputAction::Insert,
+                )));
+            }
+            Keys::Char('x') => {
+                let (target, _) = self.target();
+                if target.mode == AccessMode::ReadOnly {
+                    store.error(&format!(
+                        "{} is read-only, the migration can't be run on it.",
+                        target.label
+                    ));
+                    return Ok(EventState::Consumed);
+                }
+                self.prompt = Some(Prompt::Run(InputPopupComponent::new(
+                    format!(
+                        "Type {} to run the migration on {}",
+                        target.database, target.label
+                    ),
+                    String::new(),
+                    InputAction::Insert,
+                )));
+            }
+            Keys::Esc => return Ok(EventState::Escaped),
+            _ => return Ok(EventState::Wasted),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn draw(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        _selected: bool,
+        store: &Store,
+        layout: &LayoutArea,
+    ) -> anyhow::Result<()> {
+        let (target, source) = self.target();
+        let container = Block::default()
+            .title(format!(
+                "Schema diff : < {} > {}",
+                self.diff.left.name(),
+                self.diff.right.name()
+            ))
+            .title_bottom(format!(
+                "<Tab>: Migrate {} to {}, <Enter>: Fold, <s>: Save, <x>: Run, <Esc>: Close",
+                target.name(),
+                source.name()
+            ))
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default().fg(self.get_color(store.preference.theme_config.selected_color)),
+            )
+            .padding(Padding::horizontal(1))
+            .border_type(BorderType::Rounded);
+
+        frame.render_widget(Clear, area);
+        if self.tables.is_empty() {
+            let same = Paragraph::new("Both schemas are the same.")
+                .block(container)
+                .style(Style::new().italic());
+            frame.render_widget(same, area);
+        } else {
+            let items = self
+                .visible()
+                .into_iter()
+                .map(|(index, node, child)| {
+                    let theme_config = &store.preference.theme_config;
+                    let color = match node.marker {
+                        '<' => theme_config.log_error_color,
+                        '>' => theme_config.log_success_color,
+                        _ => theme_config.warning_color,
+                    };
+                    let style = Style::new().fg(self.get_color(color));
+                    let table = &self.tables[index];
+                    let fold = match (child, table.collapsed) {
+                        (true, _) => "    ",
+                        (false, _) if table.children.is_empty() => "  ",
+                        (false, true) => "+ ",
+                        (false, false) => "- ",
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::from(fold),
+                        Span::from(format!("{} ", node.marker)).style(style),
+                        Span::from(node.text.clone()),
+                    ]))
+                })
+                .collect::<Vec<_>>();
+            let list = List::new(items)
+                .block(container)
+                .style(
+                    Style::new().fg(self.get_color(store.preference.theme_config.unselected_color)),
+                )
+                .highlight_style(self.selection_style(store.preference.theme_config));
+            frame.render_stateful_widget(list, area, &mut self.list_state);
+        }
+
+        if let Some(Prompt::Save(popup) | Prompt::Run(popup)) = &mut self.prompt {
+            popup.draw(
+                frame,
+                centered_rect_fixed_height(layout.main_area[0], 50, 3),
+                true,
+                store,
+                layout,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     events::{key::Keys, EventState},
 };
 
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{prelude::*, widgets::*};
 
 #[derive(Debug, Clone, Copy)]
@@ -32,6 +33,14 @@ impl TabComponent {
             tabs: vec![Tab::Record, Tab::Structure, Tab::History],
         }
     }
+
+    pub fn select(&mut self, index: usize) {
+        self.selected_tab = index.min(self.tabs.len() - 1);
+    }
+
+    pub fn selected(&self) -> Tab {
+        self.tabs[self.selected_tab]
+    }
 }
 
 impl MutableComponent for TabComponent {
@@ -59,6 +68,29 @@ impl MutableComponent for TabComponent {
         }
     }
 
+
+    fn mouse(
+        &mut self,
+        mouse: &MouseEvent,
+        area: Rect,
+        _store: &mut Store,
+    ) -> anyhow::Result<EventState> {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) || mouse.row != area.y + 1 {
+            return Ok(EventState::Wasted);
+        }
+        // titles have a space on each side and a divider between them
+        let mut start = area.x + 1;
+        for (index, tab) in self.tabs.iter().enumerate() {
+            let end = start + tab.to_string().len() as u16 + 2;
+            if (start..end).contains(&mouse.column) {
+                self.selected_tab = index;
+                return Ok(EventState::Consumed);
+            }
+            start = end + 1;
+        }
+        Ok(EventState::Wasted)
+    }
+
     fn draw(
         &mut self,
         frame: &mut Frame,
@@ -74,9 +106,10 @@ impl MutableComponent for TabComponent {
             )
             .border_type(BorderType::Rounded);
 
+        let active_color = self.get_color(store.preference.theme_config.active_tab_color);
         let tabs = Tabs::new(self.tabs.iter().map(|tab| tab.to_string()))
             .block(container)
-            .highlight_style(Style::default().fg(Color::Blue))
+            .highlight_style(Style::default().fg(active_color))
             .select(self.selected_tab as usize);
 
         frame.render_widget(tabs, area);
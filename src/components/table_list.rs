@@ -1,11 +1,15 @@
-use super::{centered_rect, MutableComponent};
+use super::{centered_rect, centered_rect_fixed_height, list_mouse, MutableComponent};
 use crate::{
     application::{AppAction, Store, UpdateAction},
-    components::LayoutArea,
+    components::{FieldKind, FormField, FormPopupComponent, InputAction, LayoutArea},
     events::{key::Keys, EventState},
-    sql::records::Records,
+    sql::{
+        data_diff::{DataDiff, DiffSide},
+        records::Records,
+    },
 };
 
+use crossterm::event::MouseEvent;
 use ratatui::{prelude::*, widgets::*};
 
 #[derive(Debug, Clone)]
@@ -17,6 +21,9 @@ pub struct TableItem {
 pub struct TableListComponent {
     list_state: ListState,
     selected: isize,
+    diff_popup: Option<FormPopupComponent>,
+    /// Connections offered in the diff form, in the order of its choices.
+    diff_targets: Vec<usize>,
 }
 
 impl TableListComponent {
@@ -24,12 +31,170 @@ impl TableListComponent {
         TableListComponent {
             list_state: ListState::default(),
             selected: -1,
+            diff_popup: None,
+            diff_targets: Vec::new(),
         }
     }
+
+    pub fn current_table<'s>(&self, store: &'s Store) -> Option<&'s String> {
+        usize::try_from(self.selected)
+            .ok()
+            .and_then(|index| store.tables_list.get(index))
+    }
+
+    /// Make the table at `index` the current one and load its records.
+    pub fn select(&mut self, index: usize, store: &mut Store) -> anyhow::Result<()> {
+        store
+            .actions_tx
+            .send(UpdateAction::SendAppAction(AppAction::SendResetRecords))?;
+        self.list_state.select(Some(index));
+        let current_db = Self::current_database(store)?;
+        let current_table = store.tables_list[index].clone();
+        let pool = store.user_data.connection_list.get_pool()?;
+        let dialect = store.user_data.connection_list.get_dialect()?;
+        let actions_tx = store.actions_tx.clone();
+        let limit = store.preference.page_size;
+
+        Records::get_all(pool, actions_tx, dialect, current_db, current_table, limit);
+        self.selected = index as isize;
+        Ok(())
+    }
+
+    fn current_database(store: &Store) -> anyhow::Result<String> {
+        store
+            .database_list
+            .current()
+            .cloned()
+            .ok_or(anyhow::anyhow!("No database is selected."))
+    }
+
+    /// Ask which table the highlighted one is compared to, among the open
+    /// connections.
+    fn open_diff_form(&mut self, store: &mut Store) -> anyhow::Result<()> {
+        let Some(table) = self
+            .list_state
+            .selected()
+            .and_then(|i| store.tables_list.get(i))
+        else {
+            return Ok(());
+        };
+        let database = Self::current_database(store)?;
+        let (connection, targets) = FormField::open_connections("Connection", store)?;
+
+        self.diff_targets = targets;
+        self.diff_popup = Some(FormPopupComponent::new(
+            format!("Compare the rows of {} with", table),
+            vec![
+                connection,
+                FormField::new("Database", database, FieldKind::Text),
+                FormField::new("Table", table.clone(), FieldKind::Text),
+            ],
+            InputAction::Insert,
+        ));
+        store.is_lock = true;
+        Ok(())
+    }
+
+    fn start_diff(&self, values: Vec<String>, store: &mut Store) -> anyhow::Result<()> {
+        let Some(table) = self
+            .list_state
+            .selected()
+            .and_then(|i| store.tables_list.get(i))
+        else {
+            return Ok(());
+        };
+        let connection_list = &store.user_data.connection_list;
+        let side = |index: usize, database: String, table: String| {
+            let connection = &connection_list.list[index];
+            anyhow::Ok(DiffSide {
+                label: connection.name.clone(),
+                pool: connection_list.open_pool(index)?,
+                dialect: connection.dialect(),
+                database,
+                table,
+            })
+        };
+
+        let current = connection_list
+            .current_connection
+            .ok_or(anyhow::anyhow!("No current connection is set."))?;
+        let left = side(current, Self::current_database(store)?, table.clone())?;
+
+        let (database, table) = (values[1].trim(), values[2].trim());
+        if database.is_empty() || table.is_empty() {
+            anyhow::bail!("A database and a table are required to compare with.");
+        }
+        let target = self
+            .diff_popup
+            .as_ref()
+            .and_then(|popup| popup.fields[0].choice_position(&values[0]))
+            .and_then(|i| self.diff_targets.get(i))
+            .ok_or(anyhow::anyhow!("No connection to compare with."))?;
+        let right = side(*target, database.to_string(), table.to_string())?;
+
+        store.log(&format!(
+            "Comparing {} with {}...",
+            left.name(),
+            right.name()
+        ));
+        DataDiff::compare(left, right, store.actions_tx.clone());
+        Ok(())
+    }
+
+    pub fn event_popup(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        let Some(popup) = &mut self.diff_popup else {
+            return Ok(EventState::Wasted);
+        };
+        match popup.event(input, store)? {
+            EventState::ConfirmedForm(values) => {
+                if let Err(e) = self.start_diff(values, store) {
+                    store.error(&e.to_string());
+                    return Ok(EventState::Consumed);
+                }
+                self.diff_popup = None;
+                store.is_lock = false;
+            }
+            EventState::Escaped => {
+                self.diff_popup = None;
+                store.is_lock = false;
+            }
+            _ => {}
+        }
+        Ok(EventState::Consumed)
+    }
+
+    /// Popups are drawn once every pane has been, so they stay on top.
+    pub fn draw_popup(
+        &mut self,
+        frame: &mut Frame,
+        selected: bool,
+        store: &Store,
+        layout: &LayoutArea,
+    ) -> anyhow::Result<()> {
+        if let Some(popup) = &mut self.diff_popup {
+            if selected {
+                let height = popup.height();
+                popup.draw(
+                    frame,
+                    centered_rect_fixed_height(layout.main_area[0], 50, height),
+                    true,
+                    store,
+                    layout,
+                )?;
+            } else {
+                self.diff_popup = None;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl MutableComponent for TableListComponent {
     fn event(&mut self, input: &Keys, store: &mut Store) -> anyhow::Result<EventState> {
+        if let EventState::Consumed = self.event_popup(input, store)? {
+            return Ok(EventState::Consumed);
+        }
+
         if store.tables_list.len() > 0 {
             match input {
                 Keys::Char('j') => {
@@ -57,26 +222,38 @@ impl MutableComponent for TableListComponent {
                         self.list_state.select(Some(store.tables_list.len() - 1));
                     }
                 }
+                Keys::Home => self.list_state.select(Some(0)),
+                Keys::End => self.list_state.select(Some(store.tables_list.len() - 1)),
                 Keys::Enter => {
-                    store
-                        .actions_tx
-                        .send(UpdateAction::SendAppAction(AppAction::SendResetRecords))?;
                     if let Some(index) = self.list_state.selected() {
-                        let current_db = store.database_list.list[store.database_list.current_database.unwrap()].clone();
-                        let current_table = store.tables_list[index].clone();
-                        let pool = store.user_data.connection_list.get_pool().unwrap();
-                        let actions_tx = store.actions_tx.clone();
-
-                        Records::get_all(pool, actions_tx, current_db, current_table);
-                        self.selected = index as isize;
+                        self.select(index, store)?;
                     }
                 }
+                Keys::Char('D') => {
+                    self.open_diff_form(store)?;
+                }
                 _ => return Ok(EventState::Wasted),
             }
         }
         Ok(EventState::Consumed)
     }
 
+    fn mouse(
+        &mut self,
+        mouse: &MouseEvent,
+        area: Rect,
+        store: &mut Store,
+    ) -> anyhow::Result<EventState> {
+        let len = store.tables_list.len();
+        match list_mouse(mouse, area, &self.list_state, len) {
+            Some(index) => {
+                self.list_state.select(Some(index));
+                Ok(EventState::Consumed)
+            }
+            None => Ok(EventState::Wasted),
+        }
+    }
+
     fn draw(
         &mut self,
         frame: &mut ratatui::prelude::Frame,
@@ -114,7 +291,7 @@ impl MutableComponent for TableListComponent {
                 selected_idx,
                 store.tables_list.len()
             )))
-            .highlight_style(Style::default().reversed())
+            .highlight_style(self.selection_style(store.preference.theme_config))
             .repeat_highlight_symbol(true);
 
             frame.render_stateful_widget(list, area, &mut self.list_state);
@@ -1,6 +1,51 @@
 use ratatui::{prelude::*, widgets::*};
+use std::borrow::Cow;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-const MAX_ELEMENT_ROW: usize = 4;
+/// Narrowest a column gets, with the keys or from its content.
+const MIN_COLUMN_WIDTH: u16 = 3;
+/// Rows looked at to size the columns, the first ones.
+const SAMPLED_ROWS: usize = 100;
+/// Cells between two columns.
+const COLUMN_SPACING: u16 = 1;
+
+/// Width of each column, the one of its header or of its widest value among
+/// the sampled rows, up to `max_width`.
+pub fn column_widths(header: &[String], rows: &[Vec<String>], max_width: u16) -> Vec<u16> {
+    header
+        .iter()
+        .enumerate()
+        .map(|(index, title)| {
+            let content = rows
+                .iter()
+                .take(SAMPLED_ROWS)
+                .filter_map(|row| row.get(index))
+                .map(|value| value.width())
+                .max()
+                .unwrap_or(0);
+            let width = title.width().max(content).min(max_width as usize) as u16;
+            width.max(MIN_COLUMN_WIDTH)
+        })
+        .collect()
+}
+
+/// `content` cut to `width` cells, an ellipsis telling it was.
+fn truncate(content: &str, width: u16) -> Cow<'_, str> {
+    let width = width as usize;
+    if content.width() <= width {
+        return Cow::Borrowed(content);
+    }
+    let mut used = 0;
+    let mut truncated = content
+        .chars()
+        .take_while(|c| {
+            used += c.width().unwrap_or(0);
+            used < width
+        })
+        .collect::<String>();
+    truncated.push('…');
+    Cow::Owned(truncated)
+}
 
 #[derive(Clone)]
 pub struct CustomTable<'a> {
@@ -10,10 +55,10 @@ pub struct CustomTable<'a> {
     header_style: Style,
     header_block_style: Style,
     rows_style: Style,
+    null_style: Style,
+    number_style: Style,
     highlight_style: Style,
     style: Style,
-
-    constraints_col: Vec<Constraint>,
 }
 
 impl<'a> CustomTable<'a> {
@@ -25,9 +70,10 @@ impl<'a> CustomTable<'a> {
             header_style: Style::default(),
             header_block_style: Style::default(),
             rows_style: Style::default(),
+            null_style: Style::default(),
+            number_style: Style::default(),
             highlight_style: Style::default(),
             style: Style::default(),
-            constraints_col: Vec::new(),
         }
     }
 
@@ -37,20 +83,6 @@ impl<'a> CustomTable<'a> {
     }
     pub fn header(mut self, header: Vec<String>) -> Self {
         self.header = header;
-
-        self.constraints_col = if self.header.len() > MAX_ELEMENT_ROW {
-            vec![
-                Constraint::Fill(1),
-                Constraint::Fill(1),
-                Constraint::Fill(1),
-                Constraint::Fill(1),
-            ]
-        } else {
-            self.header
-                .iter()
-                .map(|_| Constraint::Fill(1))
-                .collect::<Vec<_>>()
-        };
         self
     }
     pub fn rows(mut self, rows: Vec<Vec<String>>) -> Self {
@@ -69,6 +101,15 @@ impl<'a> CustomTable<'a> {
         self.rows_style = style;
         self
     }
+    /// Style of the NULL values, the other ones being the rows style.
+    pub fn null_style(mut self, style: Style) -> Self {
+        self.null_style = style;
+        self
+    }
+    pub fn number_style(mut self, style: Style) -> Self {
+        self.number_style = style;
+        self
+    }
     pub fn header_block_style(mut self, style: Style) -> Self {
         self.header_block_style = style;
         self
@@ -80,18 +121,9 @@ impl<'a> CustomTable<'a> {
             .borders(Borders::BOTTOM)
             .border_style(self.header_block_style);
 
-        let rects = Layout::horizontal(&self.constraints_col)
-            .flex(layout::Flex::Center)
-            .split(Rect::new(
-                area.x + 1,
-                area.y + 1,
-                area.width - 1,
-                area.height - 1,
-            ));
-
-        for index in 0..self.constraints_col.len() {
-            let header_title = self.header.get(state.offset_x + index).unwrap();
-            let mut line = Line::from(Span::from(header_title).style(self.header_style));
+        for (index, (x, width)) in state.visible_columns(area).into_iter().enumerate() {
+            let header_title = truncate(&self.header[state.offset_x + index], width);
+            let mut line = Line::from(Span::from(header_title.clone()).style(self.header_style));
 
             if let Some((x, y)) = state.position {
                 if x == index && y == 0 {
@@ -99,33 +131,37 @@ impl<'a> CustomTable<'a> {
                     line = Line::from(Span::from(header_title).style(self.highlight_style));
                 }
             }
-            line.render(*rects.get(index).unwrap(), buf);
+            line.render(Rect::new(x, area.y + 1, width, 1), buf);
         }
 
         header_block.render(Rect::new(area.x, area.y, area.width - 2, 3), buf);
     }
 
+    fn content_style(&self, content: &str) -> Style {
+        if content == "NULL" {
+            self.null_style
+        } else if content.parse::<f64>().is_ok() {
+            self.number_style
+        } else {
+            self.rows_style
+        }
+    }
+
     fn render_rows(&self, area: Rect, buf: &mut Buffer, state: &mut CustomTableState) {
         // we need to set max element in col because we don't know
         // the size of the area otherwise
         state.max_element_in_col = (area.height - 4) as usize;
-        // avoid calculating constraint directly here
-        let col_size = ((area.width - 2) as usize / state.max_element_in_row) as u16;
+        let columns = state.visible_columns(area);
 
-        for (row_index, result) in self.rows
-            [state.offset_y..state.offset_y + state.max_element_in_col]
-            .iter()
-            .enumerate()
-        {
-            for col_index in 0..self.constraints_col.len() {
-                let item_rect = Rect::new(
-                    area.x + 1 + (col_index as u16) * col_size,
-                    area.y + 3 + row_index as u16,
-                    col_size,
-                    1,
-                );
+        // results may hold fewer rows than fit in the area
+        let end = (state.offset_y + state.max_element_in_col).min(self.rows.len());
+        for (row_index, result) in self.rows[state.offset_y.min(end)..end].iter().enumerate() {
+            for (col_index, (x, width)) in columns.iter().enumerate() {
+                let item_rect = Rect::new(*x, area.y + 3 + row_index as u16, *width, 1);
                 let content = result.get(state.offset_x + col_index).unwrap();
-                let mut line = Line::from(Span::from(content).style(self.rows_style));
+                let style = self.content_style(content);
+                let content = truncate(content, *width);
+                let mut line = Line::from(Span::from(content.clone()).style(style));
 
                 if let Some((x, y)) = state.position {
                     if x == col_index && y == row_index + 1 {
@@ -144,6 +180,7 @@ impl StatefulWidget for CustomTable<'_> {
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         buf.set_style(area, self.style);
 
+        state.scroll_to_selected(area);
         self.render_header(area, buf, state);
         self.render_rows(area, buf, state);
 
@@ -151,13 +188,15 @@ impl StatefulWidget for CustomTable<'_> {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct CustomTableState {
     pub offset_x: usize,
     pub offset_y: usize,
     pub position: Option<(usize, usize)>,
     pub header_length: usize,
     pub content_length: usize,
+    /// Width of each column, empty until they are sized from the records.
+    pub widths: Vec<u16>,
 
     pub max_element_in_row: usize,
     pub max_element_in_col: usize,
@@ -165,18 +204,14 @@ pub struct CustomTableState {
 
 impl CustomTableState {
     pub fn new(header_length: usize, content_length: usize) -> Self {
-        let max_element_in_row = if header_length > MAX_ELEMENT_ROW {
-            MAX_ELEMENT_ROW
-        } else {
-            header_length
-        };
         CustomTableState {
             offset_x: 0,
             offset_y: 0,
             position: None,
             header_length,
             content_length,
-            max_element_in_row,
+            widths: Vec::new(),
+            max_element_in_row: header_length,
             max_element_in_col: 0,
         }
     }
@@ -186,23 +221,56 @@ impl CustomTableState {
     }
     pub fn header_length(mut self, header_length: usize) -> Self {
         self.header_length = header_length;
-        let max_element_row = if header_length > MAX_ELEMENT_ROW {
-            MAX_ELEMENT_ROW
-        } else {
-            header_length
-        };
-        self.max_element_in_row = max_element_row;
+        self.max_element_in_row = header_length;
+        self.widths = Vec::new();
         self
     }
 
+    /// Column and width of the columns shown from `offset_x`, as many as fit
+    /// in `area` but one at least.
+    fn visible_columns(&self, area: Rect) -> Vec<(u16, u16)> {
+        let right = area.right().saturating_sub(1);
+        let mut x = area.x + 1;
+        let mut columns = Vec::new();
+        for index in self.offset_x..self.header_length {
+            let width = self.widths.get(index).copied().unwrap_or(MIN_COLUMN_WIDTH);
+            if x + width > right && !columns.is_empty() {
+                break;
+            }
+            columns.push((x, width.min(right.saturating_sub(x))));
+            x += width + COLUMN_SPACING;
+        }
+        columns
+    }
+
+    /// Scroll the columns for the selected one to be shown.
+    fn scroll_to_selected(&mut self, area: Rect) {
+        if let Some((x, y)) = self.position {
+            let column = self.offset_x + x;
+            while column >= self.offset_x + self.visible_columns(area).len().max(1) {
+                self.offset_x += 1;
+            }
+            self.position = Some((column - self.offset_x, y));
+        }
+        self.max_element_in_row = self.visible_columns(area).len();
+    }
+
+    /// Make the selected column wider by `delta` cells, or narrower when it
+    /// is negative.
+    pub fn resize_col(&mut self, delta: i16) {
+        let Some((x, _)) = self.position else {
+            return;
+        };
+        if let Some(width) = self.widths.get_mut(self.offset_x + x) {
+            *width = width.saturating_add_signed(delta).max(MIN_COLUMN_WIDTH);
+        }
+    }
+
     pub fn next_col(&mut self) {
         if let Some((pos, y)) = self.position {
-            if pos == self.max_element_in_row - 1 {
-                if self.offset_x + pos < self.header_length - 1 {
-                    self.offset_x = self.offset_x.saturating_add(1);
-                }
-            } else {
-                self.position = Some((pos.saturating_add(1), y));
+            // the columns scroll once drawn if it goes past the last shown
+            if self.offset_x + pos + 1 < self.header_length {
+                self.position = Some((pos + 1, y));
             }
         } else {
             self.position = Some((0, 0));
@@ -240,6 +308,39 @@ impl CustomTableState {
             self.position = Some((0, 0));
         }
     }
+    pub fn first_row(&mut self) {
+        let x = self.position.map_or(0, |(x, _)| x);
+        self.offset_y = 0;
+        self.position = Some((x, 1.min(self.content_length)));
+    }
+    pub fn last_row(&mut self) {
+        let x = self.position.map_or(0, |(x, _)| x);
+        let shown = self.max_element_in_col.max(1);
+        self.offset_y = self.content_length.saturating_sub(shown);
+        self.position = Some((x, self.content_length.min(shown)));
+    }
+    /// Position of the cell drawn at the mouse in `area`, the header being
+    /// row 0.
+    pub fn position_at(&self, area: Rect, column: u16, row: u16) -> Option<(usize, usize)> {
+        if column <= area.x || row <= area.y {
+            return None;
+        }
+        let x = self
+            .visible_columns(area)
+            .iter()
+            .position(|(x, width)| column >= *x && column < x + width + COLUMN_SPACING)?;
+        let y = match row - area.y {
+            1 => 0,
+            // under the line closing the header
+            r if r >= 3 => (r - 2) as usize,
+            _ => return None,
+        };
+        let shown = self
+            .content_length
+            .saturating_sub(self.offset_y)
+            .min(self.max_element_in_col);
+        (x < self.max_element_in_row && y <= shown).then_some((x, y))
+    }
 
     pub fn selected(&self) -> Option<(usize, usize)> {
         self.position
@@ -248,3 +349,42 @@ impl CustomTableState {
         self.position = new_pos;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn columns_fit_their_widest_value() {
+        let header = strings(&["id", "name", "note"]);
+        let rows = vec![
+            strings(&["1", "Zoë", "a long note going on"]),
+            strings(&["22", "東京", ""]),
+        ];
+        assert_eq!(column_widths(&header, &rows, 10), [3, 4, 10]);
+    }
+
+    #[test]
+    fn only_the_first_rows_are_sampled() {
+        let header = strings(&["a"]);
+        let mut rows = vec![strings(&["x"]); SAMPLED_ROWS];
+        rows.push(strings(&["much wider"]));
+        assert_eq!(column_widths(&header, &rows, 40), [MIN_COLUMN_WIDTH]);
+        // rows shorter than the header
+        assert_eq!(column_widths(&strings(&["a", "bcdef"]), &rows, 40), [3, 5]);
+    }
+
+    #[test]
+    fn truncate_by_cells() {
+        assert_eq!(truncate("short", 5), "short");
+        assert!(matches!(truncate("short", 5), Cow::Borrowed(_)));
+        assert_eq!(truncate("shorter", 5), "shor…");
+        // wide characters take two cells
+        assert_eq!(truncate("東京都庁", 5), "東京…");
+        assert_eq!(truncate("abc", 0), "…");
+    }
+}
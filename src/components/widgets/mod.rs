@@ -1,2 +1,3 @@
 pub mod custom_table;
 pub mod fps_counter;
+pub mod sql_highlight;
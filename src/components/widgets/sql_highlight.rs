@@ -0,0 +1,45 @@
+use crate::{
+    application::preferences::SyntaxTheme,
+    components::color,
+    sql::syntax::{self, TokenKind},
+};
+use ratatui::prelude::*;
+
+/// Lines of `sql`, each piece colored by what it is.
+pub fn highlight(sql: &str, theme: SyntaxTheme) -> Text<'static> {
+    let mut lines = vec![Line::default()];
+    for (range, kind) in syntax::lex(sql) {
+        let style = style(kind, theme);
+        // tokens spanning several lines, like comments, are cut on each one
+        for (index, part) in sql[range].split('\n').enumerate() {
+            if index > 0 {
+                lines.push(Line::default());
+            }
+            if !part.is_empty() {
+                let line = lines.last_mut().unwrap();
+                line.spans.push(Span::styled(part.to_string(), style));
+            }
+        }
+    }
+    Text::from(lines)
+}
+
+fn style(kind: TokenKind, theme: SyntaxTheme) -> Style {
+    let rgb = match kind {
+        TokenKind::Keyword => theme.keyword_color,
+        TokenKind::Identifier | TokenKind::Whitespace => theme.identifier_color,
+        TokenKind::String => theme.string_color,
+        TokenKind::Number => theme.number_color,
+        TokenKind::Comment => theme.comment_color,
+        TokenKind::Operator => theme.operator_color,
+        TokenKind::Parameter => theme.parameter_color,
+        TokenKind::Error => theme.error_color,
+    };
+    let style = Style::new().fg(color::from_rgb(rgb));
+    match kind {
+        TokenKind::Keyword => style.bold(),
+        TokenKind::Comment => style.italic(),
+        TokenKind::Error => style.underlined(),
+        _ => style,
+    }
+}
@@ -1,58 +1,101 @@
 use crate::events::key::Keys;
-use crossterm::event::{self, Event};
-use std::sync::mpsc::{channel, Receiver, Sender};
-use std::thread;
+use crossterm::event::{Event, EventStream, KeyEventKind, MouseEvent, MouseEventKind};
+use futures::{future::poll_fn, StreamExt};
+use std::{
+    sync::{Arc, Mutex},
+    task::Poll,
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
+/// Handle the app keeps on the events, to send keys as if they were typed
+/// and to give the terminal to another program.
 pub struct EventsHandling {
-    tx: Sender<EventThread>,
-    rx: Receiver<EventThread>,
+    tx: UnboundedSender<Keys>,
+    /// Reads the terminal, none while it is handed over to another program
+    /// which reads the keys itself.
+    stream: Arc<Mutex<Option<EventStream>>>,
 }
 
-pub enum EventThread {
-    Tick, // nothing happened
-    Event(Keys),
+/// What the main loop waits on: the terminal and the keys sent by the app.
+pub struct EventsReceiver {
+    stream: Arc<Mutex<Option<EventStream>>>,
+    rx: UnboundedReceiver<Keys>,
+}
+
+pub enum TerminalEvent {
+    Key(Keys),
+    Mouse(MouseEvent),
+    Resize,
 }
 
 impl EventsHandling {
-    pub fn new() -> Self {
-        let (tx, rx) = channel::<EventThread>();
-        Self { tx, rx }
+    pub fn new() -> (Self, EventsReceiver) {
+        let (tx, rx) = unbounded_channel::<Keys>();
+        let stream = Arc::new(Mutex::new(Some(EventStream::new())));
+        let receiver = EventsReceiver {
+            stream: stream.clone(),
+            rx,
+        };
+        (Self { tx, stream }, receiver)
     }
 
-    pub fn start(self) -> Self {
-        let etx = self.tx.clone();
-        thread::spawn(move || loop {
-            if event::poll(std::time::Duration::from_millis(16))
-                .expect("An error occured while polling event.")
-            {
-                match event::read().expect("An error occured while reading event.") {
-                    Event::Key(key) => {
-                        let k: Keys = key.into();
-                        etx.send(EventThread::Event(k))
-                            .expect("An error occured while sending event.");
-                    }
-                    Event::Mouse(_) => {
-                        // todo one day ?
-                    }
-                    _ => {
-                        etx.send(EventThread::Tick)
-                            .expect("An error occured while sending tick.");
-                    }
-                }
-            }
-            etx.send(EventThread::Tick)
-                .expect("An error occured while sending tick.");
-        });
-        self
+    /// Stop reading the terminal until `resume`. The stream is dropped, as
+    /// its reader thread would otherwise keep the keys typed meanwhile.
+    pub fn pause(&self) {
+        *self
+            .stream
+            .lock()
+            .expect("An error occured while pausing events.") = None;
     }
 
-    pub fn next(&self) -> Result<EventThread, std::sync::mpsc::RecvError> {
-        self.rx.recv()
+    pub fn resume(&self) {
+        *self
+            .stream
+            .lock()
+            .expect("An error occured while resuming events.") = Some(EventStream::new());
     }
 
     pub fn send_key(&self, key: Keys) -> anyhow::Result<()> {
-        self.tx.send(EventThread::Event(key))?;
+        self.tx.send(key)?;
         Ok(())
+    }
+}
 
+impl EventsReceiver {
+    /// Wait for a key, a click or a resize. Mouse moves and key releases are
+    /// skipped, they would only wake the loop up for nothing.
+    pub async fn next(&mut self) -> anyhow::Result<TerminalEvent> {
+        let stream = self.stream.clone();
+        // never ready while paused, the loop comes back here once resumed
+        let terminal = || {
+            poll_fn(|cx| {
+                match stream
+                    .lock()
+                    .expect("An error occured while polling events.")
+                    .as_mut()
+                {
+                    Some(stream) => stream.poll_next_unpin(cx),
+                    None => Poll::Pending,
+                }
+            })
+        };
+        loop {
+            tokio::select! {
+                Some(key) = self.rx.recv() => return Ok(TerminalEvent::Key(key)),
+                event = terminal() => match event {
+                    Some(Ok(Event::Key(key))) if key.kind != KeyEventKind::Release => {
+                        return Ok(TerminalEvent::Key(key.into()));
+                    }
+                    Some(Ok(Event::Mouse(mouse))) if mouse.kind != MouseEventKind::Moved => {
+                        return Ok(TerminalEvent::Mouse(mouse));
+                    }
+                    Some(Ok(Event::Resize(_, _))) => return Ok(TerminalEvent::Resize),
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                    None => anyhow::bail!("The terminal is closed."),
+                },
+                else => anyhow::bail!("No more events can come."),
+            }
+        }
     }
 }
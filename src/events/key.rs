@@ -9,6 +9,10 @@ pub enum Keys {
     Enter,
     Backspace,
     Esc,
+    Tab,
+    BackTab,
+    Home,
+    End,
 
     AltChar(char),
     CtrlChar(char),
@@ -60,8 +64,93 @@ impl From<KeyEvent> for Keys {
                 code: KeyCode::Esc,
                 ..
             } => Keys::Esc,
+            KeyEvent {
+                code: KeyCode::Tab,
+                ..
+            } => Keys::Tab,
+            KeyEvent {
+                code: KeyCode::BackTab,
+                ..
+            } => Keys::BackTab,
+            KeyEvent {
+                code: KeyCode::Home,
+                ..
+            } => Keys::Home,
+            KeyEvent {
+                code: KeyCode::End,
+                ..
+            } => Keys::End,
 
             _ => Keys::Unknown,
         }
     }
 }
+
+/// Names of the keys written between `<` and `>`, as in vim.
+const NAMED_KEYS: [(&str, Keys); 13] = [
+    ("Left", Keys::ArrowLeft),
+    ("Right", Keys::ArrowRight),
+    ("Up", Keys::ArrowUp),
+    ("Down", Keys::ArrowDown),
+    ("Enter", Keys::Enter),
+    ("BS", Keys::Backspace),
+    ("Esc", Keys::Esc),
+    ("Tab", Keys::Tab),
+    ("S-Tab", Keys::BackTab),
+    ("Home", Keys::Home),
+    ("End", Keys::End),
+    ("Space", Keys::Char(' ')),
+    ("lt", Keys::Char('<')),
+];
+
+impl Keys {
+    /// Keys written in vim notation, `gg`, `<C-h>`, `<A-o>` or `<Enter>`.
+    pub fn parse_sequence(sequence: &str) -> anyhow::Result<Vec<Keys>> {
+        let mut keys = Vec::new();
+        let mut chars = sequence.chars();
+        while let Some(c) = chars.next() {
+            if c != '<' {
+                keys.push(Keys::Char(c));
+                continue;
+            }
+            let name = chars.by_ref().take_while(|c| *c != '>').collect::<String>();
+            let mut name_chars = name.chars();
+            let key = match (name_chars.next(), name_chars.next(), name_chars.next()) {
+                (Some('C' | 'c'), Some('-'), Some(c)) if name.chars().count() == 3 => {
+                    Keys::CtrlChar(c)
+                }
+                (Some('A' | 'a' | 'M' | 'm'), Some('-'), Some(c)) if name.chars().count() == 3 => {
+                    Keys::AltChar(c)
+                }
+                _ => NAMED_KEYS
+                    .iter()
+                    .find(|(n, _)| n.eq_ignore_ascii_case(&name))
+                    .map(|(_, key)| *key)
+                    .ok_or(anyhow::anyhow!("Unknown key : <{}>", name))?,
+            };
+            keys.push(key);
+        }
+        if keys.is_empty() {
+            anyhow::bail!("No key was given.");
+        }
+        Ok(keys)
+    }
+
+    pub fn format_sequence(keys: &[Keys]) -> String {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+}
+
+impl std::fmt::Display for Keys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((name, _)) = NAMED_KEYS.iter().find(|(_, key)| key == self) {
+            return write!(f, "<{}>", name);
+        }
+        match self {
+            Keys::CtrlChar(c) => write!(f, "<C-{}>", c),
+            Keys::AltChar(c) => write!(f, "<A-{}>", c),
+            Keys::Char(c) => write!(f, "{}", c),
+            _ => write!(f, "<Unknown>"),
+        }
+    }
+}
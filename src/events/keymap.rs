@@ -0,0 +1,791 @@
+use super::key::Keys;
+use std::{collections::BTreeMap, str::FromStr};
+
+/// Panes bindings apply in. The global ones apply in every pane but the
+/// command one, the saved queries and the comparisons, the popups taking
+/// keys as they are typed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Global,
+    Connections,
+    Databases,
+    Tables,
+    Tabs,
+    Records,
+    History,
+    Log,
+    Help,
+    Command,
+    SavedQueries,
+    DataDiff,
+    SchemaDiff,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 13] = [
+        Scope::Global,
+        Scope::Connections,
+        Scope::Databases,
+        Scope::Tables,
+        Scope::Tabs,
+        Scope::Records,
+        Scope::History,
+        Scope::Log,
+        Scope::Help,
+        Scope::Command,
+        Scope::SavedQueries,
+        Scope::DataDiff,
+        Scope::SchemaDiff,
+    ];
+
+    fn value(&self) -> &'static str {
+        match self {
+            Scope::Global => "global",
+            Scope::Connections => "connections",
+            Scope::Databases => "databases",
+            Scope::Tables => "tables",
+            Scope::Tabs => "tabs",
+            Scope::Records => "records",
+            Scope::History => "history",
+            Scope::Log => "log",
+            Scope::Help => "help",
+            Scope::Command => "command",
+            Scope::SavedQueries => "saved-queries",
+            Scope::DataDiff => "data-diff",
+            Scope::SchemaDiff => "schema-diff",
+        }
+    }
+
+    /// Shown on top of the help.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Scope::Global => "Everywhere",
+            Scope::Connections => "Connections list",
+            Scope::Databases => "Databases list",
+            Scope::Tables => "Tables list",
+            Scope::Tabs => "Tabs",
+            Scope::Records => "Records",
+            Scope::History => "History",
+            Scope::Log => "Log",
+            Scope::Help => "Help",
+            Scope::Command => "Command",
+            Scope::SavedQueries => "Saved queries",
+            Scope::DataDiff => "Rows comparison",
+            Scope::SchemaDiff => "Schema comparison",
+        }
+    }
+
+    /// Whether the global bindings apply in the panes of the scope too.
+    fn inherits_global(&self) -> bool {
+        !matches!(
+            self,
+            Scope::Global
+                | Scope::Command
+                | Scope::SavedQueries
+                | Scope::DataDiff
+                | Scope::SchemaDiff
+        )
+    }
+
+    /// Whether the letters are typed in the panes of the scope, so they can
+    /// only be bound to single keys that aren't.
+    fn takes_text(&self) -> bool {
+        matches!(self, Scope::Command | Scope::SavedQueries)
+    }
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scope::ALL
+            .into_iter()
+            .find(|scope| scope.value() == s)
+            .ok_or(anyhow::anyhow!("Unknown keymap section : {}", s))
+    }
+}
+
+/// What a key does, whichever key it is bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    PaneLeft,
+    PaneDown,
+    PaneUp,
+    PaneRight,
+    NextWorkspace,
+    PreviousWorkspace,
+    CloseWorkspace,
+    ToggleTransaction,
+    Commit,
+    Rollback,
+    SavedQueries,
+    Zoom,
+    Wider,
+    Narrower,
+    Taller,
+    Shorter,
+    Command,
+    Help,
+    Quit,
+
+    Down,
+    Up,
+    Left,
+    Right,
+    First,
+    Last,
+    Select,
+    Close,
+
+    NewConnection,
+    EditConnection,
+    DeleteConnection,
+    TestConnection,
+    Reload,
+    Compare,
+    EditCell,
+    WidenColumn,
+    ShrinkColumn,
+
+    Complete,
+    SaveQuery,
+    EditCommand,
+    RunQuery,
+    RenameQuery,
+    DeleteQuery,
+    SyncLeft,
+    SyncRight,
+    SwapSides,
+    SaveScript,
+    RunScript,
+}
+
+impl Action {
+    pub const ALL: [Action; 47] = [
+        Action::PaneLeft,
+        Action::PaneDown,
+        Action::PaneUp,
+        Action::PaneRight,
+        Action::NextWorkspace,
+        Action::PreviousWorkspace,
+        Action::CloseWorkspace,
+        Action::ToggleTransaction,
+        Action::Commit,
+        Action::Rollback,
+        Action::SavedQueries,
+        Action::Zoom,
+        Action::Wider,
+        Action::Narrower,
+        Action::Taller,
+        Action::Shorter,
+        Action::Command,
+        Action::Help,
+        Action::Quit,
+        Action::Down,
+        Action::Up,
+        Action::Left,
+        Action::Right,
+        Action::First,
+        Action::Last,
+        Action::Select,
+        Action::Close,
+        Action::NewConnection,
+        Action::EditConnection,
+        Action::DeleteConnection,
+        Action::TestConnection,
+        Action::Reload,
+        Action::Compare,
+        Action::EditCell,
+        Action::WidenColumn,
+        Action::ShrinkColumn,
+        Action::Complete,
+        Action::SaveQuery,
+        Action::EditCommand,
+        Action::RunQuery,
+        Action::RenameQuery,
+        Action::DeleteQuery,
+        Action::SyncLeft,
+        Action::SyncRight,
+        Action::SwapSides,
+        Action::SaveScript,
+        Action::RunScript,
+    ];
+
+    fn value(&self) -> &'static str {
+        match self {
+            Action::PaneLeft => "pane-left",
+            Action::PaneDown => "pane-down",
+            Action::PaneUp => "pane-up",
+            Action::PaneRight => "pane-right",
+            Action::NextWorkspace => "next-workspace",
+            Action::PreviousWorkspace => "previous-workspace",
+            Action::CloseWorkspace => "close-workspace",
+            Action::ToggleTransaction => "toggle-transaction",
+            Action::Commit => "commit",
+            Action::Rollback => "rollback",
+            Action::SavedQueries => "saved-queries",
+            Action::Zoom => "zoom",
+            Action::Wider => "wider",
+            Action::Narrower => "narrower",
+            Action::Taller => "taller",
+            Action::Shorter => "shorter",
+            Action::Command => "command",
+            Action::Help => "help",
+            Action::Quit => "quit",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Select => "select",
+            Action::Close => "close",
+            Action::NewConnection => "new-connection",
+            Action::EditConnection => "edit-connection",
+            Action::DeleteConnection => "delete-connection",
+            Action::TestConnection => "test-connection",
+            Action::Reload => "reload",
+            Action::Compare => "compare",
+            Action::EditCell => "edit-cell",
+            Action::WidenColumn => "widen-column",
+            Action::ShrinkColumn => "shrink-column",
+            Action::Complete => "complete",
+            Action::SaveQuery => "save-query",
+            Action::EditCommand => "edit-command",
+            Action::RunQuery => "run-query",
+            Action::RenameQuery => "rename-query",
+            Action::DeleteQuery => "delete-query",
+            Action::SyncLeft => "sync-left",
+            Action::SyncRight => "sync-right",
+            Action::SwapSides => "swap-sides",
+            Action::SaveScript => "save-script",
+            Action::RunScript => "run-script",
+        }
+    }
+
+    /// Key the panes of `scope` handle the action on, whatever it is bound
+    /// to.
+    pub fn key(&self, scope: Scope) -> Keys {
+        match self {
+            // j and k are typed there
+            Action::Down if scope.takes_text() => Keys::ArrowDown,
+            Action::Up if scope.takes_text() => Keys::ArrowUp,
+            Action::PaneLeft => Keys::CtrlChar('h'),
+            Action::PaneDown => Keys::CtrlChar('j'),
+            Action::PaneUp => Keys::CtrlChar('k'),
+            Action::PaneRight => Keys::CtrlChar('l'),
+            Action::NextWorkspace => Keys::AltChar('l'),
+            Action::PreviousWorkspace => Keys::AltChar('h'),
+            Action::CloseWorkspace => Keys::AltChar('w'),
+            Action::ToggleTransaction => Keys::AltChar('t'),
+            Action::Commit => Keys::AltChar('c'),
+            Action::Rollback => Keys::AltChar('r'),
+            Action::SavedQueries => Keys::AltChar('o'),
+            Action::Zoom => Keys::AltChar('z'),
+            Action::Wider => Keys::AltChar('.'),
+            Action::Narrower => Keys::AltChar(','),
+            Action::Taller => Keys::AltChar('='),
+            Action::Shorter => Keys::AltChar('-'),
+            Action::Command => Keys::Char(':'),
+            Action::Help => Keys::Char('?'),
+            Action::Quit => Keys::Char('q'),
+            Action::Down => Keys::Char('j'),
+            Action::Up => Keys::Char('k'),
+            Action::Left => Keys::Char('h'),
+            Action::Right => Keys::Char('l'),
+            Action::First => Keys::Home,
+            Action::Last => Keys::End,
+            Action::Select => Keys::Enter,
+            Action::Close => Keys::Esc,
+            Action::NewConnection => Keys::Char('i'),
+            Action::EditConnection => Keys::Char('e'),
+            Action::DeleteConnection => Keys::Char('d'),
+            Action::TestConnection => Keys::Char('t'),
+            Action::Reload => Keys::Char('r'),
+            Action::Compare => Keys::Char('D'),
+            Action::EditCell => Keys::Char('e'),
+            Action::WidenColumn => Keys::Char('>'),
+            Action::ShrinkColumn => Keys::Char('<'),
+            Action::Complete => Keys::Tab,
+            Action::SaveQuery => Keys::AltChar('s'),
+            Action::EditCommand => Keys::AltChar('e'),
+            Action::RunQuery => Keys::CtrlChar('r'),
+            Action::RenameQuery => Keys::CtrlChar('e'),
+            Action::DeleteQuery => Keys::CtrlChar('d'),
+            Action::SyncLeft => Keys::Char('<'),
+            Action::SyncRight => Keys::Char('>'),
+            Action::SwapSides => Keys::Tab,
+            Action::SaveScript => Keys::Char('s'),
+            Action::RunScript => Keys::Char('x'),
+        }
+    }
+
+    /// What the action does in the panes of `scope`, for the help.
+    pub fn description(&self, scope: Scope) -> &'static str {
+        match (self, scope) {
+            (Action::PaneLeft, _) => "Move to the pane on the left",
+            (Action::PaneDown, _) => "Move to the pane below",
+            (Action::PaneUp, _) => "Move to the pane above",
+            (Action::PaneRight, _) => "Move to the pane on the right",
+            (Action::NextWorkspace, _) => "Switch to the next workspace",
+            (Action::PreviousWorkspace, _) => "Switch to the previous workspace",
+            (Action::CloseWorkspace, _) => "Close the workspace",
+            (Action::ToggleTransaction, _) => "Toggle the manual-commit mode",
+            (Action::Commit, _) => "Commit the transaction",
+            (Action::Rollback, _) => "Roll back the transaction",
+            (Action::SavedQueries, _) => "Open the saved queries",
+            (Action::Zoom, _) => "Show the pane alone, or all of them back",
+            (Action::Wider, _) => "Make the pane wider",
+            (Action::Narrower, _) => "Make the pane narrower",
+            (Action::Taller, _) => "Make the pane taller",
+            (Action::Shorter, _) => "Make the pane shorter",
+            (Action::Command, _) => "Type a : command",
+            (Action::Help, _) => "Show the keys of the pane",
+            (Action::Quit, _) => "Quit",
+            (Action::Down, Scope::Records) => "Next row",
+            (Action::Up, Scope::Records) => "Previous row",
+            (Action::Left, Scope::Records) => "Previous column",
+            (Action::Right, Scope::Records) => "Next column",
+            (Action::First, Scope::Records) => "First row",
+            (Action::Last, Scope::Records) => "Last row",
+            (Action::Down, Scope::Command) => "Next completion",
+            (Action::Up, Scope::Command) => "Previous completion",
+            (Action::Down, Scope::SavedQueries) => "Next query",
+            (Action::Up, Scope::SavedQueries) => "Previous query",
+            (Action::Down, Scope::DataDiff) => "Scroll down",
+            (Action::Up, Scope::DataDiff) => "Scroll up",
+            (Action::Left, _) => "Previous tab",
+            (Action::Right, _) => "Next tab",
+            (Action::Down, _) => "Next item",
+            (Action::Up, _) => "Previous item",
+            (Action::First, _) => "First item",
+            (Action::Last, _) => "Last item",
+            (Action::Select, Scope::Connections) => "Connect to the database",
+            (Action::Select, Scope::Databases) => "Use the database",
+            (Action::Select, Scope::Tables) => "Show the records of the table",
+            (Action::Select, Scope::History) => "Edit the statement in the command pane",
+            (Action::Select, Scope::Command) => "Run the SQL or the : command",
+            (Action::Select, Scope::SavedQueries) => "Load the query in the command pane",
+            (Action::Select, Scope::SchemaDiff) => "Fold or unfold the table",
+            (Action::Select, _) => "Press the key",
+            (Action::Close, Scope::Command) => "Leave the command pane",
+            (Action::Close, _) => "Close",
+            (Action::NewConnection, _) => "Insert a new database connection",
+            (Action::EditConnection, _) => "Edit the database connection",
+            (Action::DeleteConnection, _) => "Delete the database connection",
+            (Action::TestConnection, _) => "Test the database connection",
+            (Action::Reload, _) => "Reload the database's list",
+            (Action::Compare, Scope::Databases) => "Compare the schema with another database",
+            (Action::Compare, _) => "Compare the rows with another table",
+            (Action::EditCell, _) => "Edit the value in $EDITOR",
+            (Action::WidenColumn, _) => "Widen the column",
+            (Action::ShrinkColumn, _) => "Shrink the column",
+            (Action::Complete, _) => "Complete the word before the cursor",
+            (Action::SaveQuery, _) => "Save the query",
+            (Action::EditCommand, _) => "Edit the command in $EDITOR",
+            (Action::RunQuery, _) => "Run the query",
+            (Action::RenameQuery, _) => "Rename the query",
+            (Action::DeleteQuery, _) => "Delete the query",
+            (Action::SyncLeft, _) => "Save the script syncing the left side",
+            (Action::SyncRight, _) => "Save the script syncing the right side",
+            (Action::SwapSides, _) => "Swap the side the migration is for",
+            (Action::SaveScript, _) => "Save the migration script",
+            (Action::RunScript, _) => "Run the migration",
+        }
+    }
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+impl FromStr for Action {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.value() == s)
+            .ok_or(anyhow::anyhow!("Unknown action : {}", s))
+    }
+}
+
+/// Actions of the panes of `scope`, with the keys they are bound to when
+/// dbv.json doesn't tell.
+fn defaults(scope: Scope) -> Vec<(Action, Vec<&'static str>)> {
+    let list = vec![
+        (Action::Down, vec!["j", "<Down>"]),
+        (Action::Up, vec!["k", "<Up>"]),
+        (Action::First, vec!["gg", "<Home>"]),
+        (Action::Last, vec!["G", "<End>"]),
+    ];
+    let mut actions = match scope {
+        Scope::Global => vec![
+            (Action::PaneLeft, vec!["<C-h>"]),
+            (Action::PaneDown, vec!["<C-j>"]),
+            (Action::PaneUp, vec!["<C-k>"]),
+            (Action::PaneRight, vec!["<C-l>"]),
+            (Action::NextWorkspace, vec!["<A-l>"]),
+            (Action::PreviousWorkspace, vec!["<A-h>"]),
+            (Action::CloseWorkspace, vec!["<A-w>"]),
+            (Action::ToggleTransaction, vec!["<A-t>"]),
+            (Action::Commit, vec!["<A-c>"]),
+            (Action::Rollback, vec!["<A-r>"]),
+            (Action::SavedQueries, vec!["<A-o>"]),
+            (Action::Zoom, vec!["<A-z>"]),
+            (Action::Wider, vec!["<A-.>"]),
+            (Action::Narrower, vec!["<A-,>"]),
+            (Action::Taller, vec!["<A-=>"]),
+            (Action::Shorter, vec!["<A-->"]),
+            (Action::Command, vec![":"]),
+            (Action::Help, vec!["?"]),
+            (Action::Quit, vec!["q"]),
+        ],
+        Scope::Connections => vec![
+            (Action::Select, vec!["<Enter>"]),
+            (Action::NewConnection, vec!["i"]),
+            (Action::EditConnection, vec!["e"]),
+            (Action::DeleteConnection, vec!["d"]),
+            (Action::TestConnection, vec!["t"]),
+        ],
+        Scope::Databases => vec![
+            (Action::Select, vec!["<Enter>"]),
+            (Action::Reload, vec!["r"]),
+            (Action::Compare, vec!["D"]),
+        ],
+        Scope::Tables => vec![
+            (Action::Select, vec!["<Enter>"]),
+            (Action::Compare, vec!["D"]),
+        ],
+        Scope::Tabs => vec![
+            (Action::Left, vec!["h", "<Left>"]),
+            (Action::Right, vec!["l", "<Right>"]),
+        ],
+        Scope::Records => vec![
+            (Action::Left, vec!["h", "<Left>"]),
+            (Action::Right, vec!["l", "<Right>"]),
+            (Action::EditCell, vec!["e"]),
+            (Action::WidenColumn, vec![">"]),
+            (Action::ShrinkColumn, vec!["<lt>"]),
+        ],
+        Scope::History => vec![(Action::Select, vec!["<Enter>"])],
+        Scope::Log => Vec::new(),
+        Scope::Help => vec![
+            (Action::Down, vec!["j", "<Down>"]),
+            (Action::Up, vec!["k", "<Up>"]),
+            (Action::Select, vec!["<Enter>"]),
+            (Action::Close, vec!["<Esc>", "q"]),
+        ],
+        Scope::Command => vec![
+            (Action::Select, vec!["<Enter>"]),
+            (Action::Complete, vec!["<Tab>"]),
+            (Action::Down, vec!["<Down>", "<C-n>"]),
+            (Action::Up, vec!["<Up>", "<C-p>"]),
+            (Action::SaveQuery, vec!["<A-s>"]),
+            (Action::SavedQueries, vec!["<A-o>"]),
+            (Action::EditCommand, vec!["<A-e>"]),
+            (Action::Close, vec!["<Esc>"]),
+        ],
+        Scope::SavedQueries => vec![
+            (Action::Down, vec!["<Down>", "<Tab>", "<C-j>"]),
+            (Action::Up, vec!["<Up>", "<S-Tab>", "<C-k>"]),
+            (Action::Select, vec!["<Enter>"]),
+            (Action::RunQuery, vec!["<C-r>"]),
+            (Action::RenameQuery, vec!["<C-e>"]),
+            (Action::DeleteQuery, vec!["<C-d>"]),
+            (Action::Close, vec!["<Esc>"]),
+        ],
+        Scope::DataDiff => vec![
+            (Action::Down, vec!["j", "<Down>"]),
+            (Action::Up, vec!["k", "<Up>"]),
+            (Action::SyncLeft, vec!["<lt>"]),
+            (Action::SyncRight, vec![">"]),
+            (Action::Close, vec!["<Esc>", "q"]),
+        ],
+        Scope::SchemaDiff => vec![
+            (Action::Down, vec!["j", "<Down>"]),
+            (Action::Up, vec!["k", "<Up>"]),
+            (Action::Select, vec!["<Enter>", "<Space>"]),
+            (Action::SwapSides, vec!["<Tab>"]),
+            (Action::SaveScript, vec!["s"]),
+            (Action::RunScript, vec!["x"]),
+            (Action::Close, vec!["<Esc>", "q"]),
+        ],
+    };
+    if scope.inherits_global() && !matches!(scope, Scope::Tabs | Scope::Help) {
+        actions.splice(0..0, list);
+    }
+    actions
+}
+
+pub enum Resolution {
+    Action(Action),
+    /// The keys start a longer sequence.
+    Pending,
+    Unbound,
+}
+
+struct Binding {
+    scope: Scope,
+    keys: Vec<Keys>,
+    action: Action,
+}
+
+/// Keys bound to the actions of each pane.
+pub struct Keymap {
+    bindings: Vec<Binding>,
+}
+
+impl Keymap {
+    /// The default bindings, replaced for the actions given keys in the
+    /// `keymap` section of dbv.json. What is wrong in it is given back to be
+    /// shown, the bindings at fault being left out.
+    pub fn new(config: &BTreeMap<String, BTreeMap<String, Vec<String>>>) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+        for (section, actions) in config {
+            let scope = match section.parse::<Scope>() {
+                Ok(scope) => scope,
+                Err(e) => {
+                    problems.push(format!("Keymap : {}", e));
+                    continue;
+                }
+            };
+            for action in actions.keys() {
+                match action.parse::<Action>() {
+                    Ok(action) if defaults(scope).iter().any(|(a, _)| *a == action) => {}
+                    Ok(action) => {
+                        problems.push(format!("Keymap : {} can't be bound in {}.", action, scope))
+                    }
+                    Err(e) => problems.push(format!("Keymap : {}", e)),
+                }
+            }
+        }
+
+        let mut bindings: Vec<Binding> = Vec::new();
+        for scope in Scope::ALL {
+            let configured = config.get(scope.value());
+            for (action, default_keys) in defaults(scope) {
+                let sequences = match configured.and_then(|c| c.get(action.value())) {
+                    Some(sequences) => sequences.iter().map(String::as_str).collect(),
+                    None => default_keys,
+                };
+                for sequence in sequences {
+                    let keys = match Keys::parse_sequence(sequence) {
+                        Ok(keys) => keys,
+                        Err(e) => {
+                            problems.push(format!("Keymap {} {} : {}", scope, action, e));
+                            continue;
+                        }
+                    };
+                    if scope.takes_text() && (keys.len() > 1 || matches!(keys[0], Keys::Char(_))) {
+                        problems.push(format!(
+                            "Keymap : {} would be typed in {}, {} is left out.",
+                            sequence, scope, action
+                        ));
+                        continue;
+                    }
+                    if let Some(bound) =
+                        bindings.iter().find(|b| b.scope == scope && b.keys == keys)
+                    {
+                        problems.push(format!(
+                            "Keymap : {} is bound to both {} and {} in {}, {} is left out.",
+                            sequence, bound.action, action, scope, action
+                        ));
+                        continue;
+                    }
+                    bindings.push(Binding {
+                        scope,
+                        keys,
+                        action,
+                    });
+                }
+            }
+        }
+
+        let keymap = Keymap { bindings };
+        // a sequence starting with another one is never reached
+        for scope in Scope::ALL {
+            let available = keymap.available(scope).collect::<Vec<_>>();
+            for short in &available {
+                for long in &available {
+                    if long.keys.len() > short.keys.len() && long.keys.starts_with(&short.keys) {
+                        problems.push(format!(
+                            "Keymap : {} of {} hides {} of {} in {}.",
+                            Keys::format_sequence(&short.keys),
+                            short.action,
+                            Keys::format_sequence(&long.keys),
+                            long.action,
+                            scope
+                        ));
+                    }
+                }
+            }
+        }
+        (keymap, problems)
+    }
+
+    /// Bindings of the scope, then the global ones it doesn't override.
+    fn available(&self, scope: Scope) -> impl Iterator<Item = &Binding> {
+        let own = self.bindings.iter().filter(move |b| b.scope == scope);
+        let global = self.bindings.iter().filter(move |b| {
+            scope.inherits_global()
+                && b.scope == Scope::Global
+                && !self
+                    .bindings
+                    .iter()
+                    .any(|o| o.scope == scope && o.keys == b.keys)
+        });
+        own.chain(global)
+    }
+
+    /// What the keys typed in a pane of `scope` do.
+    pub fn resolve(&self, scope: Scope, keys: &[Keys]) -> Resolution {
+        if let Some(binding) = self.available(scope).find(|b| b.keys == keys) {
+            return Resolution::Action(binding.action);
+        }
+        if self
+            .available(scope)
+            .any(|b| b.keys.len() > keys.len() && b.keys.starts_with(keys))
+        {
+            return Resolution::Pending;
+        }
+        Resolution::Unbound
+    }
+
+    /// Whether a pane of `scope` handles the key for one of its actions, so
+    /// it is dropped when not bound to it.
+    pub fn handles(&self, scope: Scope, key: Keys) -> bool {
+        let global = scope.inherits_global().then_some(Scope::Global);
+        [Some(scope), global]
+            .into_iter()
+            .flatten()
+            .flat_map(defaults)
+            .any(|(action, _)| action.key(scope) == key)
+    }
+
+    /// Keys of the pane then the global ones, with what they do.
+    pub fn help(&self, scope: Scope) -> Vec<(Vec<Keys>, &'static str)> {
+        self.available(scope)
+            .map(|b| (b.keys.clone(), b.action.description(scope)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(
+        entries: &[(&str, &str, &[&str])],
+    ) -> BTreeMap<String, BTreeMap<String, Vec<String>>> {
+        let mut config: BTreeMap<String, BTreeMap<String, Vec<String>>> = BTreeMap::new();
+        for (section, action, keys) in entries {
+            config.entry(section.to_string()).or_default().insert(
+                action.to_string(),
+                keys.iter().map(|k| k.to_string()).collect(),
+            );
+        }
+        config
+    }
+
+    fn action(keymap: &Keymap, scope: Scope, keys: &str) -> Option<Action> {
+        match keymap.resolve(scope, &Keys::parse_sequence(keys).unwrap()) {
+            Resolution::Action(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        let (keymap, problems) = Keymap::new(&BTreeMap::new());
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(action(&keymap, Scope::Records, "e"), Some(Action::EditCell));
+        assert_eq!(
+            action(&keymap, Scope::Records, "<C-l>"),
+            Some(Action::PaneRight)
+        );
+        assert_eq!(action(&keymap, Scope::Log, "q"), Some(Action::Quit));
+        // the help closes on q rather than quitting
+        assert_eq!(action(&keymap, Scope::Help, "q"), Some(Action::Close));
+    }
+
+    #[test]
+    fn sequences_wait_for_their_next_key() {
+        let (keymap, _) = Keymap::new(&BTreeMap::new());
+        let g = Keys::parse_sequence("g").unwrap();
+        assert!(matches!(
+            keymap.resolve(Scope::Tables, &g),
+            Resolution::Pending
+        ));
+        assert_eq!(action(&keymap, Scope::Tables, "gg"), Some(Action::First));
+        assert!(matches!(
+            keymap.resolve(Scope::Tabs, &g),
+            Resolution::Unbound
+        ));
+    }
+
+    #[test]
+    fn configured_keys_replace_the_defaults() {
+        let (keymap, problems) = Keymap::new(&config(&[("records", "edit-cell", &["E", "<C-e>"])]));
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(action(&keymap, Scope::Records, "E"), Some(Action::EditCell));
+        assert_eq!(
+            action(&keymap, Scope::Records, "<C-e>"),
+            Some(Action::EditCell)
+        );
+        assert_eq!(action(&keymap, Scope::Records, "e"), None);
+    }
+
+    #[test]
+    fn conflicts_are_reported_and_left_out() {
+        let (keymap, problems) = Keymap::new(&config(&[
+            ("records", "widen-column", &["e"]),
+            ("tables", "compare", &["g"]),
+            ("logs", "down", &["j"]),
+            ("log", "edit-cell", &["e"]),
+            ("help", "close", &["<X-q>"]),
+        ]));
+        assert_eq!(
+            problems,
+            [
+                "Keymap : edit-cell can't be bound in log.",
+                "Keymap : Unknown keymap section : logs",
+                "Keymap : e is bound to both edit-cell and widen-column in records, widen-column is left out.",
+                "Keymap help close : Unknown key : <X-q>",
+                "Keymap : g of compare hides gg of first in tables.",
+            ]
+        );
+        assert_eq!(action(&keymap, Scope::Records, "e"), Some(Action::EditCell));
+        assert_eq!(action(&keymap, Scope::Tables, "g"), Some(Action::Compare));
+    }
+
+    #[test]
+    fn letters_are_typed_in_the_command_pane() {
+        let (keymap, problems) = Keymap::new(&config(&[
+            ("command", "edit-command", &["<C-x>"]),
+            ("saved-queries", "run-query", &["r"]),
+        ]));
+        assert_eq!(
+            problems,
+            ["Keymap : r would be typed in saved-queries, run-query is left out."]
+        );
+        assert_eq!(
+            action(&keymap, Scope::Command, "<C-x>"),
+            Some(Action::EditCommand)
+        );
+        // the global bindings don't apply there
+        assert_eq!(action(&keymap, Scope::Command, "q"), None);
+        assert!(!keymap.handles(Scope::Command, Keys::Char(':')));
+        assert!(keymap.handles(Scope::Command, Keys::AltChar('e')));
+        assert_eq!(Action::Down.key(Scope::SavedQueries), Keys::ArrowDown);
+        assert_eq!(action(&keymap, Scope::DataDiff, "q"), Some(Action::Close));
+    }
+}
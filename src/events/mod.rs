@@ -1,9 +1,11 @@
 pub mod key;
 pub mod events;
+pub mod keymap;
 
 pub enum EventState{
     Consumed,
     ConfirmedText(String),
+    ConfirmedForm(Vec<String>),
     Escaped,
     Wasted
 }
@@ -1,9 +1,14 @@
 use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use ratatui::prelude::{CrosstermBackend, Terminal};
-use std::io::{stdout, Result};
+use std::{
+    io::{stdout, Result},
+    time::Duration,
+};
+use tokio::time::{interval, MissedTickBehavior};
 
 use dbv::{application::App, events::events::*, log::set_logger};
 
@@ -14,7 +19,7 @@ async fn main() -> anyhow::Result<()> {
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     terminal.clear()?;
 
-    let events_handling = EventsHandling::new().start();
+    let (events_handling, mut events) = EventsHandling::new();
     let mut app = App::new(&events_handling);
 
     app.store
@@ -27,40 +32,80 @@ async fn main() -> anyhow::Result<()> {
 
     sqlx::any::install_default_drivers();
 
+    if app.store.preference.mouse {
+        stdout().execute(EnableMouseCapture)?;
+    }
+
     set_logger(&app.store.preference)?;
 
+    if let Err(e) = app.restore_session() {
+        app.store.error(&format!(
+            "An error occured while restoring the session : {}",
+            e
+        ));
+    }
+
+    // frames are drawn at most this often, the keys typed in between being
+    // handled first
+    let mut render = interval(Duration::from_millis(16));
+    render.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    // workspaces in the background and the time a transaction is open for
+    let mut background = interval(Duration::from_millis(250));
+    background.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut dirty = true;
+
     loop {
-        // draw
-        terminal
-            .draw(|frame| {
-                if let Err(e) = app.draw(frame) {
-                    eprintln!("An error occured : {}", e.to_string());
-                    std::process::exit(1);
+        tokio::select! {
+            event = events.next() => {
+                let handled = match event {
+                    Ok(TerminalEvent::Key(key)) => app.event_handling(key),
+                    Ok(TerminalEvent::Mouse(mouse)) => app.mouse_handling(mouse),
+                    Ok(TerminalEvent::Resize) => Ok(terminal.autoresize()?),
+                    Err(err) => {
+                        eprintln!("An error occured : {}", err.to_string());
+                        std::process::exit(1);
+                    }
                 };
-            })
-            .expect("An error occured while rendering terminal.");
-
-        // event
-        match events_handling.next() {
-            Ok(event) => {
-                if let EventThread::Event(key) = event {
-                    app.event_handling(key).unwrap();
+                if let Err(e) = handled {
+                    app.store.error(&e.to_string());
                 }
+                dirty = true;
             }
-            Err(err) => {
-                eprintln!("An error occured : {}", err.to_string());
-                std::process::exit(1);
+            Some(action) = app.store.actions_rx.recv() => {
+                app.dispatch(action);
+                dirty = true;
             }
-        };
+            _ = background.tick() => {
+                dirty |= app.is_ticking();
+            }
+            _ = render.tick(), if dirty => {
+                if app.store.redraw {
+                    terminal.clear()?;
+                    app.store.redraw = false;
+                }
+                terminal
+                    .draw(|frame| {
+                        if let Err(e) = app.draw(frame) {
+                            eprintln!("An error occured : {}", e.to_string());
+                            std::process::exit(1);
+                        };
+                    })
+                    .expect("An error occured while rendering terminal.");
+                dirty = false;
+            }
+        }
 
-        // update
-        app.update();
+        dirty |= app.update();
 
         if app.store.exit {
             break;
         }
     }
 
+    if let Err(e) = app.save_session() {
+        log::error!("An error occured while saving the session : {}", e);
+    }
+
     close_terminal()?;
     Ok(())
 }
@@ -73,6 +118,7 @@ fn setup_terminal() -> Result<()> {
 }
 
 fn close_terminal() -> Result<()> {
+    stdout().execute(DisableMouseCapture)?;
     stdout().execute(LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
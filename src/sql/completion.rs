@@ -0,0 +1,273 @@
+use super::{dialect::Dialect, schema::Schema, statement, syntax::KEYWORDS};
+use crate::application::{StoreAction, UpdateAction};
+use sqlx::{Any, Pool};
+use std::collections::BTreeMap;
+use tokio::{spawn, sync::mpsc::UnboundedSender};
+
+/// Tables of the current database with their columns, read once when the
+/// database is selected so completing doesn't query the server.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    pub database: String,
+    pub tables: BTreeMap<String, Vec<String>>,
+}
+
+impl Catalog {
+    pub fn load(
+        pool: Pool<Any>,
+        sender: UnboundedSender<UpdateAction>,
+        dialect: Dialect,
+        database: String,
+    ) {
+        spawn(async move {
+            let action = match Schema::load(&pool, dialect, &database).await {
+                Ok(schema) => StoreAction::SendCatalog(Box::new(Catalog {
+                    tables: schema
+                        .tables
+                        .into_iter()
+                        .map(|(name, table)| {
+                            (name, table.columns.into_iter().map(|c| c.name).collect())
+                        })
+                        .collect(),
+                    database,
+                })),
+                // completion goes on with the keywords alone
+                Err(e) => StoreAction::SendQueryError(format!(
+                    "Unable to read the columns for completion : {}",
+                    e
+                )),
+            };
+            let _ = sender.send(UpdateAction::SendStoreAction(action));
+        });
+    }
+
+    /// Table named `name`, case aside.
+    fn table(&self, name: &str) -> Option<(&String, &Vec<String>)> {
+        self.tables
+            .iter()
+            .find(|(table, _)| table.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Keyword,
+    Table,
+    Column,
+    /// What follows `:` in the command pane, and its arguments.
+    Command,
+    Connection,
+    Database,
+    Option,
+    Theme,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub text: String,
+    pub kind: CompletionKind,
+}
+
+/// What may be typed at `cursor`, a byte in `sql`, with the byte the word
+/// being typed starts at. The word is replaced by the completion chosen.
+///
+/// After `alias.` only the columns of the table behind the alias are
+/// offered. After `from` or `join` the tables come first, elsewhere the
+/// keywords do, then the tables and the columns of the tables the statement
+/// reads from.
+pub fn complete(sql: &str, cursor: usize, catalog: Option<&Catalog>) -> (usize, Vec<Completion>) {
+    let before = &sql[..cursor];
+    let start = before
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+        .last()
+        .map_or(cursor, |(i, _)| i);
+    let prefix = &sql[start..cursor];
+    let (statement_start, statement) = statement::statement_at(sql, cursor);
+    let references = statement::references(statement);
+
+    let lowercase = !prefix.chars().any(|c| c.is_uppercase());
+    let matches = |candidate: &str| {
+        candidate.len() > prefix.len()
+            && candidate
+                .get(..prefix.len())
+                .is_some_and(|c| c.eq_ignore_ascii_case(prefix))
+    };
+    let keywords = || {
+        KEYWORDS.iter().filter(|k| matches(k)).map(|k| Completion {
+            text: if lowercase {
+                k.to_string()
+            } else {
+                k.to_uppercase()
+            },
+            kind: CompletionKind::Keyword,
+        })
+    };
+    let tables = || {
+        catalog
+            .into_iter()
+            .flat_map(|c| c.tables.keys())
+            .filter(|t| matches(t))
+            .map(|t| Completion {
+                text: t.clone(),
+                kind: CompletionKind::Table,
+            })
+    };
+    let columns_of = |table: &str| {
+        catalog
+            .and_then(|c| c.table(table))
+            .map(|(_, columns)| columns.clone())
+            .unwrap_or_default()
+    };
+
+    // `alias.` or `table.` right before the word
+    if let Some(qualifier) = before[..start]
+        .strip_suffix('.')
+        .map(|rest| {
+            let from = rest
+                .char_indices()
+                .rev()
+                .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+                .last()
+                .map_or(rest.len(), |(i, _)| i);
+            &rest[from..]
+        })
+        .filter(|qualifier| !qualifier.is_empty())
+    {
+        let table = references
+            .iter()
+            .find(|(_, alias)| {
+                alias
+                    .as_deref()
+                    .is_some_and(|a| a.eq_ignore_ascii_case(qualifier))
+            })
+            .map_or(qualifier, |(table, _)| table.as_str());
+        let completions = columns_of(table)
+            .into_iter()
+            .filter(|c| matches(c))
+            .map(|text| Completion {
+                text,
+                kind: CompletionKind::Column,
+            })
+            .collect();
+        return (start, completions);
+    }
+
+    let mut columns = Vec::new();
+    for (table, _) in &references {
+        for column in columns_of(table) {
+            if matches(&column) && !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+    }
+    let columns = columns.into_iter().map(|text| Completion {
+        text,
+        kind: CompletionKind::Column,
+    });
+
+    let offset = start.saturating_sub(statement_start).min(statement.len());
+    let completions = if statement::expects_table(&statement[..offset]) {
+        tables().chain(keywords()).collect()
+    } else {
+        keywords().chain(tables()).chain(columns).collect()
+    };
+    (start, completions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> Catalog {
+        Catalog {
+            database: "app".to_string(),
+            tables: [
+                (
+                    "users".to_string(),
+                    vec!["id".to_string(), "name".to_string()],
+                ),
+                (
+                    "orders".to_string(),
+                    vec!["id".to_string(), "user_id".to_string()],
+                ),
+                ("uploads".to_string(), vec!["path".to_string()]),
+            ]
+            .into(),
+        }
+    }
+
+    fn texts(sql: &str, catalog: Option<&Catalog>) -> (usize, Vec<String>) {
+        let (start, completions) = complete(sql, sql.len(), catalog);
+        (start, completions.into_iter().map(|c| c.text).collect())
+    }
+
+    #[test]
+    fn keywords_follow_the_case_typed() {
+        let (start, completions) = texts("sel", None);
+        assert_eq!(start, 0);
+        assert!(completions.contains(&"select".to_string()));
+        let (_, completions) = texts("SEL", None);
+        assert!(completions.contains(&"SELECT".to_string()));
+        // a word already typed in full isn't offered again
+        let (start, completions) = texts("select * fr", None);
+        assert_eq!(start, 9);
+        assert!(!completions.contains(&"fr".to_string()));
+    }
+
+    #[test]
+    fn tables_come_first_after_from() {
+        let catalog = catalog();
+        let (start, completions) = complete("select * from u", 15, Some(&catalog));
+        assert_eq!(start, 14);
+        assert_eq!(completions[0].kind, CompletionKind::Table);
+        assert_eq!(
+            completions
+                .iter()
+                .filter(|c| c.kind == CompletionKind::Table)
+                .map(|c| c.text.as_str())
+                .collect::<Vec<_>>(),
+            ["uploads", "users"]
+        );
+    }
+
+    #[test]
+    fn columns_of_the_tables_read() {
+        let catalog = catalog();
+        let (_, completions) = complete("select u from orders", 8, Some(&catalog));
+        assert!(completions.contains(&Completion {
+            text: "user_id".to_string(),
+            kind: CompletionKind::Column,
+        }));
+        assert!(completions.iter().all(|c| c.text != "path"));
+    }
+
+    #[test]
+    fn columns_after_an_alias() {
+        let catalog = catalog();
+        let sql = "select o. from orders o join users u on u.id = o.user_id";
+        let (start, completions) = complete(sql, 9, Some(&catalog));
+        assert_eq!(start, 9);
+        assert_eq!(
+            completions
+                .iter()
+                .map(|c| c.text.as_str())
+                .collect::<Vec<_>>(),
+            ["id", "user_id"]
+        );
+        // only the statement the cursor is in is read
+        let sql = "select u.i from orders u; select u.n from users u";
+        let (start, completions) = complete(sql, 36, Some(&catalog));
+        assert_eq!(&sql[start..36], "n");
+        assert_eq!(completions[0].text, "name");
+        let (_, completions) = complete(sql, 10, Some(&catalog));
+        assert_eq!(completions[0].text, "id");
+    }
+
+    #[test]
+    fn nothing_without_a_catalog() {
+        let (_, completions) = texts("select x.", None);
+        assert!(completions.is_empty());
+    }
+}
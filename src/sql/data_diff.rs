@@ -0,0 +1,481 @@
+use super::{dialect::Dialect, schema, value::Value};
+use crate::application::{AppAction, StoreAction, UpdateAction};
+use sqlx::{Any, Pool, Row};
+use std::collections::{HashMap, HashSet};
+use tokio::{spawn, sync::mpsc::UnboundedSender};
+
+/// Rows a side may hold to be compared, as both are loaded in memory.
+pub const MAX_ROWS: i64 = 100_000;
+
+/// One of the two tables being compared.
+#[derive(Debug, Clone)]
+pub struct DiffSide {
+    /// Name of the connection, to tell the sides apart.
+    pub label: String,
+    pub pool: Pool<Any>,
+    pub dialect: Dialect,
+    pub database: String,
+    pub table: String,
+}
+
+impl DiffSide {
+    pub fn name(&self) -> String {
+        format!("{}.{}.{}", self.label, self.database, self.table)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RowDiff {
+    /// Values of the row, in the order of the left columns.
+    OnlyLeft(Vec<Value>),
+    /// Values of the row, in the order of the right columns.
+    OnlyRight(Vec<Value>),
+    Changed {
+        key: Vec<Value>,
+        /// Column, left value and right value of each cell that differs.
+        cells: Vec<(String, Value, Value)>,
+    },
+}
+
+/// Which side a sync script writes to, the other one being copied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// Rows that differ between two tables, matched on their primary key.
+#[derive(Debug, Clone)]
+pub struct DataDiff {
+    pub left: String,
+    pub right: String,
+    left_dialect: Dialect,
+    right_dialect: Dialect,
+    left_table: (String, String),
+    right_table: (String, String),
+    pub key: Vec<String>,
+    pub left_columns: Vec<String>,
+    pub right_columns: Vec<String>,
+    pub rows: Vec<RowDiff>,
+    /// Rows found on each side.
+    pub counts: (usize, usize),
+}
+
+impl DataDiff {
+    /// Compare the two tables and send the result as `AppAction::SendDataDiff`.
+    pub fn compare(left: DiffSide, right: DiffSide, sender: UnboundedSender<UpdateAction>) {
+        spawn(async move {
+            let action = match DataDiff::run(left, right).await {
+                Ok(diff) => UpdateAction::SendAppAction(AppAction::SendDataDiff(Box::new(diff))),
+                Err(e) => UpdateAction::SendStoreAction(StoreAction::SendQueryError(e.to_string())),
+            };
+            let _ = sender.send(action);
+        });
+    }
+
+    async fn run(left: DiffSide, right: DiffSide) -> anyhow::Result<Self> {
+        let left_columns = columns(&left).await?;
+        let right_columns = columns(&right).await?;
+        let left_key = primary_key(&left).await?;
+        let right_key = primary_key(&right).await?;
+        if left_key.len() != right_key.len()
+            || left_key
+                .iter()
+                .zip(&right_key)
+                .any(|(l, r)| !l.eq_ignore_ascii_case(r))
+        {
+            anyhow::bail!(
+                "The primary keys don't match : ({}) on {}, ({}) on {}.",
+                left_key.join(", "),
+                left.name(),
+                right_key.join(", "),
+                right.name()
+            );
+        }
+
+        for side in [&left, &right] {
+            let count = count_rows(side).await?;
+            if count > MAX_ROWS {
+                anyhow::bail!(
+                    "{} holds {} rows, only tables of up to {} rows can be compared.",
+                    side.name(),
+                    count,
+                    MAX_ROWS
+                );
+            }
+        }
+
+        let left_rows = fetch_rows(&left, &left_columns, &left_key).await?;
+        let right_rows = fetch_rows(&right, &right_columns, &right_key).await?;
+        let left_key_index = column_indexes(&left_columns, &left_key);
+        let right_key_index = column_indexes(&right_columns, &right_key);
+
+        // columns found on both sides, as (left index, right index)
+        let common = left_columns
+            .iter()
+            .enumerate()
+            .filter_map(|(l, name)| {
+                right_columns
+                    .iter()
+                    .position(|c| c.eq_ignore_ascii_case(name))
+                    .map(|r| (l, r))
+            })
+            .collect::<Vec<_>>();
+
+        let right_by_key = right_rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| (key_of(row, &right_key_index), index))
+            .collect::<HashMap<_, _>>();
+        let mut matched = HashSet::new();
+        let mut rows = Vec::new();
+        for row in &left_rows {
+            let key = key_of(row, &left_key_index);
+            match right_by_key.get(&key) {
+                Some(&index) => {
+                    matched.insert(index);
+                    let other = &right_rows[index];
+                    let cells = common
+                        .iter()
+                        .filter(|(l, r)| !row[*l].same(&other[*r]))
+                        .map(|(l, r)| {
+                            (left_columns[*l].clone(), row[*l].clone(), other[*r].clone())
+                        })
+                        .collect::<Vec<_>>();
+                    if !cells.is_empty() {
+                        rows.push(RowDiff::Changed {
+                            key: left_key_index.iter().map(|&i| row[i].clone()).collect(),
+                            cells,
+                        });
+                    }
+                }
+                None => rows.push(RowDiff::OnlyLeft(row.clone())),
+            }
+        }
+        rows.extend(
+            right_rows
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !matched.contains(index))
+                .map(|(_, row)| RowDiff::OnlyRight(row.clone())),
+        );
+
+        Ok(DataDiff {
+            left: left.name(),
+            right: right.name(),
+            left_dialect: left.dialect,
+            right_dialect: right.dialect,
+            left_table: (left.database, left.table),
+            right_table: (right.database, right.table),
+            key: left_key,
+            left_columns,
+            right_columns,
+            rows,
+            counts: (left_rows.len(), right_rows.len()),
+        })
+    }
+
+    /// Name of the left table, without its database.
+    pub fn table(&self) -> &str {
+        &self.left_table.1
+    }
+
+    pub fn only_left(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|r| matches!(r, RowDiff::OnlyLeft(_)))
+            .count()
+    }
+
+    pub fn only_right(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|r| matches!(r, RowDiff::OnlyRight(_)))
+            .count()
+    }
+
+    pub fn changed(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|r| matches!(r, RowDiff::Changed { .. }))
+            .count()
+    }
+
+    /// Statements turning the target side of `direction` into a copy of the
+    /// other one, in the dialect of the target. Columns missing from the
+    /// target are left out.
+    pub fn sync_script(&self, direction: SyncDirection) -> String {
+        let (source, target, dialect, (database, table), source_columns, target_columns) =
+            match direction {
+                SyncDirection::LeftToRight => (
+                    &self.left,
+                    &self.right,
+                    self.right_dialect,
+                    &self.right_table,
+                    &self.left_columns,
+                    &self.right_columns,
+                ),
+                SyncDirection::RightToLeft => (
+                    &self.right,
+                    &self.left,
+                    self.left_dialect,
+                    &self.left_table,
+                    &self.right_columns,
+                    &self.left_columns,
+                ),
+            };
+        let table = dialect.qualified_name(database, table);
+        let in_target = |column: &String| {
+            target_columns
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(column))
+        };
+        let where_key = |values: &[&Value]| {
+            self.key
+                .iter()
+                .zip(values)
+                .map(|(column, value)| value.condition(dialect, column))
+                .collect::<Vec<_>>()
+                .join(" AND ")
+        };
+
+        let mut script = vec![
+            format!("-- Make {} a copy of {}", target, source),
+            "BEGIN;".to_string(),
+        ];
+        for row in &self.rows {
+            let (inserted, deleted) = match (direction, row) {
+                (SyncDirection::LeftToRight, RowDiff::OnlyLeft(values))
+                | (SyncDirection::RightToLeft, RowDiff::OnlyRight(values)) => (Some(values), None),
+                (SyncDirection::LeftToRight, RowDiff::OnlyRight(values))
+                | (SyncDirection::RightToLeft, RowDiff::OnlyLeft(values)) => (None, Some(values)),
+                (_, RowDiff::Changed { key, cells }) => {
+                    let assignments = cells
+                        .iter()
+                        .filter(|(column, _, _)| in_target(column))
+                        .map(|(column, left, right)| {
+                            let value = match direction {
+                                SyncDirection::LeftToRight => left,
+                                SyncDirection::RightToLeft => right,
+                            };
+                            format!(
+                                "{} = {}",
+                                dialect.quote_identifier(column),
+                                value.literal(dialect)
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    if !assignments.is_empty() {
+                        script.push(format!(
+                            "UPDATE {} SET {} WHERE {};",
+                            table,
+                            assignments.join(", "),
+                            where_key(&key.iter().collect::<Vec<_>>())
+                        ));
+                    }
+                    continue;
+                }
+            };
+            if let Some(values) = inserted {
+                let (columns, literals): (Vec<_>, Vec<_>) = source_columns
+                    .iter()
+                    .zip(values)
+                    .filter(|(column, _)| in_target(column))
+                    .map(|(column, value)| {
+                        (dialect.quote_identifier(column), value.literal(dialect))
+                    })
+                    .unzip();
+                script.push(format!(
+                    "INSERT INTO {} ({}) VALUES ({});",
+                    table,
+                    columns.join(", "),
+                    literals.join(", ")
+                ));
+            }
+            if let Some(values) = deleted {
+                let key = column_indexes(target_columns, &self.key)
+                    .into_iter()
+                    .map(|i| &values[i])
+                    .collect::<Vec<_>>();
+                script.push(format!("DELETE FROM {} WHERE {};", table, where_key(&key)));
+            }
+        }
+        script.push("COMMIT;".to_string());
+        script.join("\n") + "\n"
+    }
+}
+
+/// Index of each of the `names` among the columns, compared without case.
+fn column_indexes(columns: &[String], names: &[String]) -> Vec<usize> {
+    names
+        .iter()
+        .filter_map(|name| columns.iter().position(|c| c.eq_ignore_ascii_case(name)))
+        .collect()
+}
+
+/// The key of a row as text, so rows coming from different engines can be
+/// matched.
+fn key_of(row: &[Value], key: &[usize]) -> Vec<String> {
+    key.iter()
+        .map(|&i| row[i].literal(Dialect::Sqlite))
+        .collect()
+}
+
+async fn primary_key(side: &DiffSide) -> anyhow::Result<Vec<String>> {
+    let key = schema::primary_key(&side.pool, side.dialect, &side.database, &side.table).await?;
+    if key.is_empty() {
+        anyhow::bail!("{} has no primary key to match rows on.", side.name());
+    }
+    Ok(key)
+}
+
+/// Columns of the table, in the order they were declared.
+async fn columns(side: &DiffSide) -> anyhow::Result<Vec<String>> {
+    let query = match side.dialect {
+        Dialect::MySql => {
+            "select column_name as name from information_schema.columns \
+             where table_schema = ? and table_name = ? order by ordinal_position"
+        }
+        Dialect::Postgres => {
+            "select column_name::text as name from information_schema.columns \
+             where table_schema = $1 and table_name = $2 order by ordinal_position"
+        }
+        Dialect::Sqlite => "select name from pragma_table_info(?2, ?1) order by cid",
+    };
+    let rows = sqlx::query(query)
+        .bind(&side.database)
+        .bind(&side.table)
+        .fetch_all(&side.pool)
+        .await?;
+    let columns = rows
+        .iter()
+        .map(|row| row.try_get::<String, _>("name"))
+        .collect::<Result<Vec<_>, _>>()?;
+    if columns.is_empty() {
+        anyhow::bail!("{} doesn't exist.", side.name());
+    }
+    Ok(columns)
+}
+
+async fn count_rows(side: &DiffSide) -> anyhow::Result<i64> {
+    let query = format!(
+        "select count(*) from {}",
+        side.dialect.qualified_name(&side.database, &side.table)
+    );
+    Ok(sqlx::query(&query)
+        .fetch_one(&side.pool)
+        .await?
+        .try_get::<i64, _>(0)?)
+}
+
+async fn fetch_rows(
+    side: &DiffSide,
+    columns: &[String],
+    key: &[String],
+) -> anyhow::Result<Vec<Vec<Value>>> {
+    let list = |names: &[String]| {
+        names
+            .iter()
+            .map(|column| side.dialect.quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let query = format!(
+        "select {} from {} order by {}",
+        list(columns),
+        side.dialect.qualified_name(&side.database, &side.table),
+        list(key)
+    );
+    let rows = sqlx::query(&query).fetch_all(&side.pool).await?;
+    let values = rows
+        .iter()
+        .map(|row| {
+            (0..columns.len())
+                .map(|i| Value::from_row(row, i))
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `t` on sqlite on the left and on postgres on the right, where the
+    /// `extra` column is missing.
+    fn diff() -> DataDiff {
+        let text = |s: &str| Value::Text(s.to_string());
+        DataDiff {
+            left: "a.main.t".to_string(),
+            right: "b.public.t".to_string(),
+            left_dialect: Dialect::Sqlite,
+            right_dialect: Dialect::Postgres,
+            left_table: ("main".to_string(), "t".to_string()),
+            right_table: ("public".to_string(), "t".to_string()),
+            key: vec!["id".to_string()],
+            left_columns: vec!["id".to_string(), "name".to_string(), "extra".to_string()],
+            right_columns: vec!["ID".to_string(), "name".to_string()],
+            rows: vec![
+                RowDiff::OnlyLeft(vec![Value::Int(1), text("it's"), Value::Int(0)]),
+                RowDiff::OnlyRight(vec![Value::Null, text("b")]),
+                RowDiff::Changed {
+                    key: vec![Value::Int(3)],
+                    cells: vec![
+                        ("name".to_string(), text("new"), Value::Null),
+                        ("extra".to_string(), Value::Int(1), Value::Int(2)),
+                    ],
+                },
+            ],
+            counts: (2, 2),
+        }
+    }
+
+    #[test]
+    fn sync_script_leaves_out_missing_columns() {
+        assert_eq!(
+            diff().sync_script(SyncDirection::LeftToRight),
+            "-- Make b.public.t a copy of a.main.t\n\
+             BEGIN;\n\
+             INSERT INTO \"public\".\"t\" (\"id\", \"name\") VALUES (1, 'it''s');\n\
+             DELETE FROM \"public\".\"t\" WHERE \"id\" IS NULL;\n\
+             UPDATE \"public\".\"t\" SET \"name\" = 'new' WHERE \"id\" = 3;\n\
+             COMMIT;\n"
+        );
+    }
+
+    #[test]
+    fn sync_script_the_other_way() {
+        assert_eq!(
+            diff().sync_script(SyncDirection::RightToLeft),
+            "-- Make a.main.t a copy of b.public.t\n\
+             BEGIN;\n\
+             DELETE FROM \"main\".\"t\" WHERE \"id\" = 1;\n\
+             INSERT INTO \"main\".\"t\" (\"ID\", \"name\") VALUES (NULL, 'b');\n\
+             UPDATE \"main\".\"t\" SET \"name\" = NULL, \"extra\" = 2 WHERE \"id\" = 3;\n\
+             COMMIT;\n"
+        );
+    }
+
+    #[test]
+    fn counts_rows_by_difference() {
+        let diff = diff();
+        assert_eq!(
+            (diff.only_left(), diff.only_right(), diff.changed()),
+            (1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn keys_match_across_engines() {
+        let row = [Value::Text("a".into()), Value::Int(1), Value::Float(1.0)];
+        assert_eq!(key_of(&row, &[1, 0]), ["1", "'a'"]);
+        assert_eq!(
+            column_indexes(
+                &["ID".to_string(), "Name".to_string()],
+                &["name".to_string()]
+            ),
+            [1]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::application::{UpdateAction, StoreAction};
+use crate::sql::dialect::Dialect;
 use sqlx::{Any, Pool, Row};
 use tokio::{spawn, sync::mpsc::UnboundedSender};
 
@@ -14,14 +15,26 @@ impl DatabaseList {
             current_database: None,
         }
     }
+
+    pub fn current(&self) -> Option<&String> {
+        self.current_database.and_then(|i| self.list.get(i))
+    }
 }
 
 pub struct Database;
 
 impl Database {
-    pub fn get_databases(pool: Pool<Any>, sender: UnboundedSender<UpdateAction>) {
+    pub fn get_databases(pool: Pool<Any>, sender: UnboundedSender<UpdateAction>, dialect: Dialect) {
         spawn(async move {
-            let query = sqlx::query("SHOW databases").fetch_all(&pool).await;
+            let query = match dialect {
+                Dialect::MySql => "SHOW databases",
+                // see `Dialect::databases_title`
+                Dialect::Postgres => {
+                    "select schema_name as \"Database\" from information_schema.schemata"
+                }
+                Dialect::Sqlite => "select name as \"Database\" from pragma_database_list",
+            };
+            let query = sqlx::query(query).fetch_all(&pool).await;
 
             match query {
                 Ok(rows) => {
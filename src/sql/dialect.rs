@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+    Backtick,
+    DoubleQuote,
+    Bracket,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    MySql,
+    Postgres,
+    Sqlite,
+}
+
+impl Dialect {
+    pub fn quote_style(&self) -> QuoteStyle {
+        match self {
+            Dialect::MySql => QuoteStyle::Backtick,
+            Dialect::Postgres | Dialect::Sqlite => QuoteStyle::DoubleQuote,
+        }
+    }
+
+    pub fn quote_identifier(&self, ident: &str) -> String {
+        quote_identifier(ident, self.quote_style())
+    }
+
+    /// What the databases pane lists: a postgres pool is bound to one
+    /// database, so its schemas are browsed instead.
+    pub fn databases_title(&self) -> &'static str {
+        match self {
+            Dialect::Postgres => "Schemas",
+            Dialect::MySql | Dialect::Sqlite => "Databases",
+        }
+    }
+
+    /// `schema.name`, both parts quoted.
+    pub fn qualified_name(&self, schema: &str, name: &str) -> String {
+        format!(
+            "{}.{}",
+            self.quote_identifier(schema),
+            self.quote_identifier(name)
+        )
+    }
+
+    /// Bind placeholder for the `index`-th parameter (starting at 1).
+    pub fn placeholder(&self, index: usize) -> String {
+        match self {
+            Dialect::Postgres => format!("${}", index),
+            Dialect::MySql | Dialect::Sqlite => "?".to_string(),
+        }
+    }
+
+    /// Statement limiting how long the next statements of the session may
+    /// run, `None` when the engine can't.
+    pub fn statement_timeout(&self, timeout: Duration) -> Option<String> {
+        match self {
+            Dialect::MySql => Some(format!(
+                "SET SESSION max_execution_time = {}",
+                timeout.as_millis()
+            )),
+            Dialect::Postgres => Some(format!("SET statement_timeout = {}", timeout.as_millis())),
+            Dialect::Sqlite => None,
+        }
+    }
+}
+
+/// Quote an identifier so it can be safely interpolated into a statement.
+/// The closing quote character is escaped by doubling it, which is the rule
+/// shared by mysql, postgres, sqlite and sql server.
+pub fn quote_identifier(ident: &str, style: QuoteStyle) -> String {
+    let (open, close) = match style {
+        QuoteStyle::Backtick => ('`', '`'),
+        QuoteStyle::DoubleQuote => ('"', '"'),
+        QuoteStyle::Bracket => ('[', ']'),
+    };
+
+    let mut quoted = String::with_capacity(ident.len() + 2);
+    quoted.push(open);
+    for c in ident.chars() {
+        if c == close {
+            quoted.push(close);
+        }
+        quoted.push(c);
+    }
+    quoted.push(close);
+    quoted
+}
@@ -0,0 +1,124 @@
+use super::statement::{self, StatementKind};
+use crate::utils;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::{fs::OpenOptions, io::Write, str::FromStr};
+
+const AUDIT_FILENAME: &str = "audit.log";
+
+/// What dbv lets through to the server on a connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccessMode {
+    #[default]
+    ReadWrite,
+    /// Only statements reading data or changing the session are sent.
+    ReadOnly,
+    /// Destructive statements have to be confirmed by typing the name of the
+    /// database, and are written to the audit file.
+    Guarded,
+}
+
+/// Whether statements may be sent to the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Allowed,
+    /// The first statement the connection doesn't accept.
+    Rejected(String),
+    /// The destructive statements to confirm first.
+    Confirm(Vec<String>),
+}
+
+impl AccessMode {
+    pub const ALL: [AccessMode; 3] = [
+        AccessMode::ReadWrite,
+        AccessMode::ReadOnly,
+        AccessMode::Guarded,
+    ];
+
+    pub fn is_default(&self) -> bool {
+        *self == AccessMode::default()
+    }
+
+    fn value(&self) -> &'static str {
+        match self {
+            AccessMode::ReadWrite => "read-write",
+            AccessMode::ReadOnly => "read-only",
+            AccessMode::Guarded => "guarded",
+        }
+    }
+
+    /// Shown in front of the connection in the list.
+    pub fn marker(&self) -> &'static str {
+        match self {
+            AccessMode::ReadWrite => "",
+            AccessMode::ReadOnly => "🔒 ",
+            AccessMode::Guarded => "⚠ ",
+        }
+    }
+
+    pub fn check(&self, statements: &[String]) -> Verdict {
+        match self {
+            AccessMode::ReadWrite => Verdict::Allowed,
+            AccessMode::ReadOnly => statements
+                .iter()
+                .find(|s| {
+                    statement::kind(s) != StatementKind::Read && !statement::changes_session(s)
+                })
+                .map_or(Verdict::Allowed, |s| Verdict::Rejected(s.clone())),
+            AccessMode::Guarded => {
+                let destructive = destructive(statements);
+                if destructive.is_empty() {
+                    Verdict::Allowed
+                } else {
+                    Verdict::Confirm(destructive)
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for AccessMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+impl FromStr for AccessMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AccessMode::ALL
+            .into_iter()
+            .find(|mode| mode.value() == s)
+            .ok_or(anyhow::anyhow!("Unknown access mode : {}", s))
+    }
+}
+
+pub fn destructive(statements: &[String]) -> Vec<String> {
+    statements
+        .iter()
+        .filter(|s| statement::kind(s) == StatementKind::Destructive)
+        .cloned()
+        .collect()
+}
+
+/// Append the statements run on a guarded connection to
+/// `$HOME/.config/dbv/audit.log`, one line each.
+pub fn audit(connection: &str, database: &str, statements: &[String]) -> anyhow::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(utils::get_path_app_file(AUDIT_FILENAME))?;
+    let now = Local::now().format("%Y-%m-%d %H:%M:%S");
+    for statement in statements {
+        writeln!(
+            file,
+            "{} {}.{} : {}",
+            now,
+            connection,
+            database,
+            statement.split_whitespace().collect::<Vec<_>>().join(" ")
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,355 @@
+use super::{
+    connection::{Connection, Driver},
+    tls::Tls,
+    tunnel::{free_local_port, SshConfig, Tunnel},
+};
+use crate::application::{StoreAction, UpdateAction};
+use sqlx::{Any, AnyConnection, Connection as _, Executor, Pool};
+use std::{
+    net::SocketAddr,
+    path::Path,
+    time::{Duration, Instant},
+};
+use tokio::{
+    net::{lookup_host, TcpStream},
+    spawn,
+    sync::mpsc::UnboundedSender,
+    time::{sleep, timeout},
+};
+use tokio_util::sync::CancellationToken;
+
+const PING_QUERY: &str = "select 1";
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStep {
+    File,
+    Ssh,
+    Dns,
+    Tcp,
+    Auth,
+    Tls,
+    Init,
+}
+
+impl std::fmt::Display for CheckStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let step = match self {
+            CheckStep::File => "File",
+            CheckStep::Ssh => "SSH tunnel",
+            CheckStep::Dns => "DNS",
+            CheckStep::Tcp => "TCP",
+            CheckStep::Auth => "Authentication",
+            CheckStep::Tls => "TLS",
+            CheckStep::Init => "Init SQL",
+        };
+        write!(f, "{}", step)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckOutcome {
+    Passed(String),
+    Failed(String),
+    Skipped(String),
+}
+
+/// State of an open connection, refreshed by a periodic ping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    Up(Duration),
+    Reconnecting { attempt: u32, retry_in: Duration },
+}
+
+impl std::fmt::Display for ConnectionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionStatus::Up(latency) => write!(f, "up {} ms", latency.as_millis()),
+            ConnectionStatus::Reconnecting { attempt, .. } => {
+                write!(f, "reconnecting #{}", attempt)
+            }
+        }
+    }
+}
+
+/// What the connection test needs, taken from a connection whose password has
+/// been resolved so the test can run apart from it.
+pub struct CheckTarget {
+    name: String,
+    driver: Driver,
+    host: String,
+    port: u16,
+    file_path: String,
+    ssh: Option<(SshConfig, u16)>,
+    connection_string: String,
+    timeout: Duration,
+    session_statements: Vec<String>,
+}
+
+impl CheckTarget {
+    pub fn new(connection: &Connection) -> anyhow::Result<Self> {
+        let port = connection.remote_port().unwrap_or_default();
+        let (ssh, connection_string) = match &connection.ssh {
+            Some(ssh) if connection.driver != Driver::Sqlite => {
+                let local_port = free_local_port()?;
+                (
+                    Some((ssh.clone(), local_port)),
+                    connection.connection_string_to("127.0.0.1", Some(local_port))?,
+                )
+            }
+            _ => (None, connection.connection_string()?),
+        };
+
+        Ok(CheckTarget {
+            name: connection.name.clone(),
+            driver: connection.driver,
+            host: connection.host.clone(),
+            port,
+            file_path: connection.file_path.clone(),
+            ssh,
+            connection_string,
+            timeout: connection.pool_settings.connect_timeout(),
+            session_statements: connection
+                .pool_settings
+                .session_statements(connection.dialect()),
+        })
+    }
+}
+
+pub struct Health;
+
+impl Health {
+    /// Go through every step of a connection one after the other and report
+    /// each of them, stopping at the first one which fails.
+    pub fn test_connection(target: CheckTarget, sender: UnboundedSender<UpdateAction>) {
+        spawn(async move {
+            let report = |step: CheckStep, outcome: CheckOutcome| {
+                sender
+                    .send(UpdateAction::SendStoreAction(StoreAction::SendCheckResult(
+                        target.name.clone(),
+                        step,
+                        outcome,
+                    )))
+                    .unwrap();
+            };
+
+            if target.driver == Driver::Sqlite {
+                if target.file_path != ":memory:" && !Path::new(&target.file_path).exists() {
+                    report(
+                        CheckStep::File,
+                        CheckOutcome::Failed(format!("{} doesn't exist.", target.file_path)),
+                    );
+                    return;
+                }
+                report(
+                    CheckStep::File,
+                    CheckOutcome::Passed(target.file_path.clone()),
+                );
+            }
+
+            // kept alive until the end of the test
+            let mut _tunnel = None;
+            let address = match &target.ssh {
+                Some((ssh, local_port)) => {
+                    let start = Instant::now();
+                    let opened =
+                        Tunnel::open(ssh, *local_port, &target.host, target.port, target.timeout)
+                            .await;
+                    match opened {
+                        Ok(tunnel) => _tunnel = Some(tunnel),
+                        Err(e) => {
+                            report(CheckStep::Ssh, CheckOutcome::Failed(e.to_string()));
+                            return;
+                        }
+                    }
+                    report(
+                        CheckStep::Ssh,
+                        CheckOutcome::Passed(format!("open in {} ms", start.elapsed().as_millis())),
+                    );
+                    report(
+                        CheckStep::Dns,
+                        CheckOutcome::Skipped("resolved by the jump host".to_string()),
+                    );
+                    Some(SocketAddr::from(([127, 0, 0, 1], *local_port)))
+                }
+                None if target.driver == Driver::Sqlite => None,
+                None => match Health::resolve(&target.host, target.port, target.timeout).await {
+                    Ok(addresses) => {
+                        report(
+                            CheckStep::Dns,
+                            CheckOutcome::Passed(
+                                addresses
+                                    .iter()
+                                    .map(|a| a.ip().to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(", "),
+                            ),
+                        );
+                        addresses.first().copied()
+                    }
+                    Err(e) => {
+                        report(CheckStep::Dns, CheckOutcome::Failed(e.to_string()));
+                        return;
+                    }
+                },
+            };
+
+            if let Some(address) = address {
+                let start = Instant::now();
+                match timeout(target.timeout, TcpStream::connect(address)).await {
+                    Ok(Ok(_)) => report(
+                        CheckStep::Tcp,
+                        CheckOutcome::Passed(format!(
+                            "{} reached in {} ms",
+                            address,
+                            start.elapsed().as_millis()
+                        )),
+                    ),
+                    Ok(Err(e)) => {
+                        report(CheckStep::Tcp, CheckOutcome::Failed(e.to_string()));
+                        return;
+                    }
+                    Err(_) => {
+                        report(
+                            CheckStep::Tcp,
+                            CheckOutcome::Failed(format!("No answer after {:?}.", target.timeout)),
+                        );
+                        return;
+                    }
+                }
+            }
+
+            let mut connection = match timeout(
+                target.timeout,
+                AnyConnection::connect(&target.connection_string),
+            )
+            .await
+            {
+                Ok(Ok(connection)) => connection,
+                // the handshake happens before the credentials are sent
+                Ok(Err(sqlx::Error::Tls(e))) => {
+                    report(CheckStep::Tls, CheckOutcome::Failed(e.to_string()));
+                    return;
+                }
+                Ok(Err(e)) => {
+                    report(CheckStep::Auth, CheckOutcome::Failed(e.to_string()));
+                    return;
+                }
+                Err(_) => {
+                    report(
+                        CheckStep::Auth,
+                        CheckOutcome::Failed(format!("No answer after {:?}.", target.timeout)),
+                    );
+                    return;
+                }
+            };
+            report(
+                CheckStep::Auth,
+                CheckOutcome::Passed("logged in".to_string()),
+            );
+
+            let outcome = match Tls::is_encrypted(&mut connection, target.driver.dialect()).await {
+                Some(Ok(true)) => CheckOutcome::Passed("the session is encrypted".to_string()),
+                Some(Ok(false)) => {
+                    CheckOutcome::Skipped("the session is not encrypted".to_string())
+                }
+                Some(Err(e)) => CheckOutcome::Skipped(format!("unable to tell : {}", e)),
+                None => CheckOutcome::Skipped("local file".to_string()),
+            };
+            report(CheckStep::Tls, outcome);
+
+            for statement in &target.session_statements {
+                if let Err(e) = connection.execute(statement.as_str()).await {
+                    report(CheckStep::Init, CheckOutcome::Failed(e.to_string()));
+                    return;
+                }
+            }
+            if !target.session_statements.is_empty() {
+                report(
+                    CheckStep::Init,
+                    CheckOutcome::Passed("session set up".to_string()),
+                );
+            }
+            let _ = connection.close().await;
+        });
+    }
+
+    async fn resolve(host: &str, port: u16, wait: Duration) -> anyhow::Result<Vec<SocketAddr>> {
+        let addresses = timeout(wait, lookup_host((host, port)))
+            .await
+            .map_err(|_| anyhow::anyhow!("No answer after {:?}.", wait))??
+            .collect::<Vec<_>>();
+        if addresses.is_empty() {
+            anyhow::bail!("{} has no address.", host);
+        }
+        Ok(addresses)
+    }
+
+    /// Ping the server every few seconds and report the latency. After a
+    /// failed ping the pool drops its broken connections, the next ping opens
+    /// a new one; the delay between attempts doubles up to a minute. The ssh
+    /// tunnel, if any, is started again first when its process has exited.
+    pub fn monitor(
+        pool: Pool<Any>,
+        tunnel: Option<Tunnel>,
+        sender: UnboundedSender<UpdateAction>,
+        id: String,
+    ) -> CancellationToken {
+        let token = CancellationToken::new();
+        let cancelled = token.clone();
+        spawn(async move {
+            let mut attempt = 0;
+            loop {
+                let reopened = match &tunnel {
+                    Some(tunnel) => tunnel.reopen_if_exited().await,
+                    None => Ok(false),
+                };
+                let start = Instant::now();
+                let ping = async {
+                    reopened?;
+                    timeout(PING_TIMEOUT, sqlx::query(PING_QUERY).execute(&pool)).await??;
+                    anyhow::Ok(())
+                };
+                let status = match ping.await {
+                    Ok(()) => {
+                        attempt = 0;
+                        ConnectionStatus::Up(start.elapsed())
+                    }
+                    _ => {
+                        attempt += 1;
+                        ConnectionStatus::Reconnecting {
+                            attempt,
+                            retry_in: backoff(attempt),
+                        }
+                    }
+                };
+
+                if cancelled.is_cancelled()
+                    || sender
+                        .send(UpdateAction::SendStoreAction(
+                            StoreAction::SendConnectionStatus(id.clone(), status),
+                        ))
+                        .is_err()
+                {
+                    break;
+                }
+
+                let wait = match status {
+                    ConnectionStatus::Up(_) => PING_INTERVAL,
+                    ConnectionStatus::Reconnecting { retry_in, .. } => retry_in,
+                };
+                tokio::select! {
+                    _ = cancelled.cancelled() => break,
+                    _ = sleep(wait) => {}
+                }
+            }
+        });
+        token
+    }
+}
+
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.saturating_sub(1).min(6)).min(MAX_BACKOFF)
+}
@@ -1,5 +1,21 @@
+pub mod completion;
 pub mod connection;
+pub mod data_diff;
 pub mod database;
+pub mod dialect;
+pub mod guard;
+pub mod health;
 pub mod tables;
 pub mod records;
+pub mod schema;
+pub mod schema_diff;
+pub mod statement;
+pub mod syntax;
+pub mod parameters;
 pub mod parser;
+pub mod pool;
+pub mod query;
+pub mod tls;
+pub mod transaction;
+pub mod tunnel;
+pub mod value;
@@ -0,0 +1,253 @@
+use super::{dialect::Dialect, statement, value::Value};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, str::FromStr};
+
+/// Type a parameter value is bound as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterType {
+    #[default]
+    Text,
+    Integer,
+    Real,
+    Boolean,
+    Null,
+}
+
+impl ParameterType {
+    pub const ALL: [ParameterType; 5] = [
+        ParameterType::Text,
+        ParameterType::Integer,
+        ParameterType::Real,
+        ParameterType::Boolean,
+        ParameterType::Null,
+    ];
+
+    fn value(&self) -> &'static str {
+        match self {
+            ParameterType::Text => "text",
+            ParameterType::Integer => "integer",
+            ParameterType::Real => "real",
+            ParameterType::Boolean => "boolean",
+            ParameterType::Null => "null",
+        }
+    }
+
+    /// Type hinted by the name of a parameter never given a value before.
+    pub fn guess(name: &str) -> Self {
+        let name = name.trim_start_matches(':').to_lowercase();
+        if name == "id" || name.ends_with("_id") || name == "limit" || name == "offset" {
+            ParameterType::Integer
+        } else if name.starts_with("is_") || name.starts_with("has_") {
+            ParameterType::Boolean
+        } else {
+            ParameterType::Text
+        }
+    }
+
+    /// Read the value typed in the form.
+    pub fn parse(&self, name: &str, text: &str) -> anyhow::Result<Value> {
+        let invalid = || anyhow::anyhow!("{} : {} is not a valid {}.", name, text, self);
+        Ok(match self {
+            ParameterType::Text => Value::Text(text.to_string()),
+            ParameterType::Integer => Value::Int(text.trim().parse().map_err(|_| invalid())?),
+            ParameterType::Real => Value::Float(text.trim().parse().map_err(|_| invalid())?),
+            ParameterType::Boolean => match text.trim().to_lowercase().as_str() {
+                "true" | "t" | "yes" | "y" | "1" => Value::Bool(true),
+                "false" | "f" | "no" | "n" | "0" => Value::Bool(false),
+                _ => return Err(invalid()),
+            },
+            ParameterType::Null => Value::Null,
+        })
+    }
+}
+
+impl std::fmt::Display for ParameterType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+impl FromStr for ParameterType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ParameterType::ALL
+            .into_iter()
+            .find(|t| t.value() == s)
+            .ok_or(anyhow::anyhow!("Unknown parameter type : {}", s))
+    }
+}
+
+/// A statement with the values of its parameters, sent to the server apart
+/// from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundStatement {
+    pub sql: String,
+    pub values: Vec<Value>,
+}
+
+impl From<String> for BoundStatement {
+    fn from(sql: String) -> Self {
+        BoundStatement {
+            sql,
+            values: Vec::new(),
+        }
+    }
+}
+
+/// Placeholders of a statement with the name their value is asked under :
+/// `:id` is `:id`, `$1` stays `$1` and the n-th `?` is `?n`. `?` is an
+/// operator on postgres, not a placeholder.
+fn placeholders(statement: &str, dialect: Dialect) -> Vec<(usize, usize, String)> {
+    let mut position = 0;
+    statement::parameters(statement)
+        .into_iter()
+        .filter_map(|(start, parameter)| {
+            let end = start + parameter.len();
+            if parameter != "?" {
+                return Some((start, end, parameter));
+            }
+            if dialect == Dialect::Postgres {
+                return None;
+            }
+            position += 1;
+            Some((start, end, format!("?{}", position)))
+        })
+        .collect()
+}
+
+/// Names of the parameters of the statements, each one once, in the order
+/// they first appear.
+pub fn names(statements: &[String], dialect: Dialect) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for statement in statements {
+        for (_, _, name) in placeholders(statement, dialect) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Replace the placeholders of the statement by the ones of the engine, the
+/// values being bound in their order. Postgres reuses the placeholder of a
+/// name used twice, the other engines get its value once per placeholder.
+pub fn bind(
+    statement: &str,
+    dialect: Dialect,
+    values: &HashMap<String, Value>,
+) -> anyhow::Result<BoundStatement> {
+    let mut sql = String::new();
+    let mut order: Vec<String> = Vec::new();
+    let mut bound = Vec::new();
+    let mut last = 0;
+    for (start, end, name) in placeholders(statement, dialect) {
+        let value = values
+            .get(&name)
+            .ok_or(anyhow::anyhow!("No value was given for {}.", name))?;
+        let index = match (dialect, order.iter().position(|n| *n == name)) {
+            (Dialect::Postgres, Some(index)) => index + 1,
+            _ => {
+                order.push(name);
+                bound.push(value.clone());
+                order.len()
+            }
+        };
+        sql.push_str(&statement[last..start]);
+        sql.push_str(&dialect.placeholder(index));
+        last = end;
+    }
+    sql.push_str(&statement[last..]);
+    Ok(BoundStatement { sql, values: bound })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn placeholders_skip_casts() {
+        assert_eq!(
+            placeholders("select :a::int, b::text, ? from t", Dialect::Sqlite),
+            [(7, 9, ":a".to_string()), (25, 26, "?1".to_string())]
+        );
+    }
+
+    #[test]
+    fn question_marks_are_numbered_except_on_postgres() {
+        let sql = "select * from t where a = ? and b ? 'k' and c = $1";
+        assert_eq!(
+            names(&[sql.to_string()], Dialect::MySql),
+            ["?1", "?2", "$1"]
+        );
+        assert_eq!(names(&[sql.to_string()], Dialect::Postgres), ["$1"]);
+    }
+
+    #[test]
+    fn names_are_listed_once() {
+        let statements = ["select :a, :b, :a".to_string(), "select :b, :c".to_string()];
+        assert_eq!(names(&statements, Dialect::Sqlite), [":a", ":b", ":c"]);
+    }
+
+    #[test]
+    fn postgres_reuses_placeholders() {
+        let values = values(&[(":a", Value::Int(1)), (":b", Value::Text("x".into()))]);
+        let bound = bind("select :b, :a::int, :b", Dialect::Postgres, &values).unwrap();
+        assert_eq!(bound.sql, "select $1, $2::int, $1");
+        assert_eq!(bound.values, [Value::Text("x".into()), Value::Int(1)]);
+    }
+
+    #[test]
+    fn positional_placeholders_repeat_values() {
+        let values = values(&[(":a", Value::Int(1)), ("?1", Value::Null)]);
+        let bound = bind("select :a, ?, :a", Dialect::MySql, &values).unwrap();
+        assert_eq!(bound.sql, "select ?, ?, ?");
+        assert_eq!(bound.values, [Value::Int(1), Value::Null, Value::Int(1)]);
+    }
+
+    #[test]
+    fn postgres_renumbers_dollar_parameters() {
+        let values = values(&[("$2", Value::Int(2)), ("$1", Value::Int(1))]);
+        let bound = bind("select $2, $1, $2", Dialect::Postgres, &values).unwrap();
+        assert_eq!(bound.sql, "select $1, $2, $1");
+        assert_eq!(bound.values, [Value::Int(2), Value::Int(1)]);
+    }
+
+    #[test]
+    fn question_marks_are_left_alone_on_postgres() {
+        let values = values(&[(":k", Value::Text("a".into()))]);
+        let bound = bind("select doc ? :k from t", Dialect::Postgres, &values).unwrap();
+        assert_eq!(bound.sql, "select doc ? $1 from t");
+    }
+
+    #[test]
+    fn missing_values_are_refused() {
+        assert!(bind("select :a", Dialect::Sqlite, &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn parses_typed_values() {
+        assert_eq!(ParameterType::guess(":user_id"), ParameterType::Integer);
+        assert_eq!(ParameterType::guess(":is_active"), ParameterType::Boolean);
+        assert_eq!(ParameterType::guess(":name"), ParameterType::Text);
+        assert_eq!(
+            ParameterType::Integer.parse(":a", " 42 ").unwrap(),
+            Value::Int(42)
+        );
+        assert_eq!(
+            ParameterType::Boolean.parse(":a", "Yes").unwrap(),
+            Value::Bool(true)
+        );
+        assert!(ParameterType::Real.parse(":a", "x").is_err());
+        assert_eq!(ParameterType::Null.parse(":a", "x").unwrap(), Value::Null);
+    }
+}
@@ -0,0 +1,96 @@
+use super::dialect::Dialect;
+use serde::{Deserialize, Serialize};
+use sqlx::{any::AnyPoolOptions, Executor};
+use std::time::Duration;
+
+const DEFAULT_MAX_CONNECTIONS: u32 = 5;
+const DEFAULT_CONNECT_TIMEOUT: u64 = 10;
+const DEFAULT_ACQUIRE_TIMEOUT: u64 = 5;
+
+fn default_max_connections() -> u32 {
+    DEFAULT_MAX_CONNECTIONS
+}
+fn default_connect_timeout() -> u64 {
+    DEFAULT_CONNECT_TIMEOUT
+}
+fn default_acquire_timeout() -> u64 {
+    DEFAULT_ACQUIRE_TIMEOUT
+}
+
+/// How the pool of a connection is sized and how long it waits, timeouts
+/// being in seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolSettings {
+    #[serde(default = "default_max_connections", rename = "maxConnections")]
+    pub max_connections: u32,
+    /// Time to open the ssh tunnel, then the connection to the server.
+    #[serde(default = "default_connect_timeout", rename = "connectTimeout")]
+    pub connect_timeout: u64,
+    /// Time a statement waits for a connection of the pool, opening a new one
+    /// included when none is free.
+    #[serde(default = "default_acquire_timeout", rename = "acquireTimeout")]
+    pub acquire_timeout: u64,
+    /// Longest a statement may run before the server cancels it. Sqlite has
+    /// no such limit.
+    #[serde(
+        default,
+        rename = "statementTimeout",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub statement_timeout: Option<u64>,
+    /// Statements run on every new connection, separated by `;`.
+    #[serde(default, rename = "initSql", skip_serializing_if = "String::is_empty")]
+    pub init_sql: String,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        PoolSettings {
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            acquire_timeout: DEFAULT_ACQUIRE_TIMEOUT,
+            statement_timeout: None,
+            init_sql: String::new(),
+        }
+    }
+}
+
+impl PoolSettings {
+    pub fn is_default(&self) -> bool {
+        *self == PoolSettings::default()
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout)
+    }
+
+    /// What has to be run on a new connection to set up its session.
+    pub fn session_statements(&self, dialect: Dialect) -> Vec<String> {
+        let mut statements = Vec::new();
+        if let Some(timeout) = self.statement_timeout {
+            if let Some(statement) = dialect.statement_timeout(Duration::from_secs(timeout)) {
+                statements.push(statement);
+            }
+        }
+        if !self.init_sql.trim().is_empty() {
+            statements.push(self.init_sql.clone());
+        }
+        statements
+    }
+
+    pub fn pool_options(&self, dialect: Dialect) -> AnyPoolOptions {
+        let statements = self.session_statements(dialect);
+        AnyPoolOptions::new()
+            .max_connections(self.max_connections)
+            .acquire_timeout(Duration::from_secs(self.acquire_timeout))
+            .after_connect(move |connection, _| {
+                let statements = statements.clone();
+                Box::pin(async move {
+                    for statement in statements {
+                        connection.execute(statement.as_str()).await?;
+                    }
+                    Ok(())
+                })
+            })
+    }
+}
@@ -0,0 +1,86 @@
+use crate::application::{AppAction, StoreAction, UpdateAction};
+use crate::sql::{
+    parameters::BoundStatement,
+    statement::{self, StatementKind},
+    value::Value,
+};
+use sqlx::{any::AnyArguments, query::Query as SqlxQuery, Any, AnyConnection, Executor, Pool};
+use tokio::{spawn, sync::mpsc::UnboundedSender};
+
+pub struct Query;
+
+impl Query {
+    /// Run the statements one after the other on the same connection of the
+    /// pool, stopping at the first failing one. The rows of reads are shown
+    /// in the records view.
+    pub fn run(
+        pool: Pool<Any>,
+        sender: UnboundedSender<UpdateAction>,
+        statements: Vec<BoundStatement>,
+    ) {
+        spawn(async move {
+            let result = async {
+                let mut connection = pool.acquire().await?;
+                for statement in &statements {
+                    Query::execute(&mut connection, statement, &sender).await?;
+                }
+                anyhow::Ok(())
+            }
+            .await;
+            if let Err(e) = result {
+                let _ = sender.send(UpdateAction::SendStoreAction(StoreAction::SendQueryError(
+                    e.to_string(),
+                )));
+            }
+        });
+    }
+
+    /// Run a single statement and report what it did. Statements without
+    /// parameters are run unprepared, as they were typed.
+    pub async fn execute(
+        connection: &mut AnyConnection,
+        statement: &BoundStatement,
+        sender: &UnboundedSender<UpdateAction>,
+    ) -> anyhow::Result<()> {
+        let failed = |e| anyhow::anyhow!("{} failed : {}", statement.sql, e);
+        let action = if statement::kind(&statement.sql) == StatementKind::Read {
+            let rows = if statement.values.is_empty() {
+                connection.fetch_all(statement.sql.as_str()).await
+            } else {
+                Query::bound(statement).fetch_all(&mut *connection).await
+            }
+            .map_err(failed)?;
+            let _ = sender.send(UpdateAction::SendStoreAction(StoreAction::SendSuccess(
+                format!("{} rows returned.", rows.len()),
+            )));
+            UpdateAction::SendAppAction(AppAction::SendRecords((rows, None, Vec::new())))
+        } else {
+            let result = if statement.values.is_empty() {
+                connection.execute(statement.sql.as_str()).await
+            } else {
+                Query::bound(statement).execute(&mut *connection).await
+            }
+            .map_err(failed)?;
+            UpdateAction::SendStoreAction(StoreAction::SendSuccess(format!(
+                "{} rows affected.",
+                result.rows_affected()
+            )))
+        };
+        let _ = sender.send(action);
+        Ok(())
+    }
+
+    fn bound(statement: &BoundStatement) -> SqlxQuery<'_, Any, AnyArguments<'_>> {
+        statement
+            .values
+            .iter()
+            .fold(sqlx::query(&statement.sql), |query, value| match value {
+                Value::Null => query.bind(None::<String>),
+                Value::Bool(value) => query.bind(*value),
+                Value::Int(value) => query.bind(*value),
+                Value::Float(value) => query.bind(*value),
+                Value::Text(value) => query.bind(value.as_str()),
+                Value::Bytes(value) => query.bind(value.as_slice()),
+            })
+    }
+}
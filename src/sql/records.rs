@@ -1,4 +1,5 @@
 use crate::application::{AppAction, StoreAction, UpdateAction};
+use crate::sql::{dialect::Dialect, schema};
 use sqlx::{Any, Pool, Row};
 use tokio::{spawn, sync::mpsc::UnboundedSender};
 
@@ -8,20 +9,25 @@ impl Records {
     pub fn get_all(
         pool: Pool<Any>,
         sender: UnboundedSender<UpdateAction>,
+        dialect: Dialect,
         db_name: String,
         table_name: String,
+        limit: u32,
     ) {
         spawn(async move {
-            let query = sqlx::query(&format!(
-                "select * from {}.{} LIMIT 100",
-                db_name, table_name
-            ))
-            .fetch_all(&pool)
-            .await;
+            let table = dialect.qualified_name(&db_name, &table_name);
+            let query = sqlx::query(&format!("select * from {} LIMIT {}", table, limit))
+                .fetch_all(&pool)
+                .await;
 
-            let count = sqlx::query(&format!("select COUNT(*) from {}.{}", db_name, table_name))
+            let count = sqlx::query(&format!("select COUNT(*) from {}", table))
                 .fetch_one(&pool)
                 .await;
+            // without it the rows can't be updated one by one, they are
+            // shown all the same
+            let primary_key = schema::primary_key(&pool, dialect, &db_name, &table_name)
+                .await
+                .unwrap_or_default();
             match query {
                 Ok(rows) => {
                     if let Ok(c) = count {
@@ -29,12 +35,15 @@ impl Records {
                             .send(UpdateAction::SendAppAction(AppAction::SendRecords((
                                 rows,
                                 Some(c.get::<i64, _>(0)),
+                                primary_key,
                             ))))
                             .unwrap();
                     } else {
                         sender
                             .send(UpdateAction::SendAppAction(AppAction::SendRecords((
-                                rows, None,
+                                rows,
+                                None,
+                                primary_key,
                             ))))
                             .unwrap();
                     }
@@ -0,0 +1,312 @@
+use super::{dialect::Dialect, value::Value};
+use sqlx::{any::AnyRow, Any, Column, Pool, Row};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSchema {
+    pub name: String,
+    /// Type as the engine reports it, e.g. `varchar(20)`.
+    pub data_type: String,
+    pub nullable: bool,
+    /// Default expression, as written in the catalog.
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexSchema {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignKeySchema {
+    /// Name of the constraint, sqlite doesn't keep one.
+    pub name: String,
+    pub columns: Vec<String>,
+    pub foreign_table: String,
+    pub foreign_columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableSchema {
+    pub columns: Vec<ColumnSchema>,
+    pub primary_key: Vec<String>,
+    /// Name of the primary key constraint, postgres drops it by name.
+    pub primary_key_name: Option<String>,
+    pub indexes: Vec<IndexSchema>,
+    pub foreign_keys: Vec<ForeignKeySchema>,
+}
+
+impl TableSchema {
+    pub fn column(&self, name: &str) -> Option<&ColumnSchema> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+}
+
+/// Tables of a database, as read from the catalog of the engine.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    pub tables: BTreeMap<String, TableSchema>,
+}
+
+impl Schema {
+    pub async fn load(pool: &Pool<Any>, dialect: Dialect, database: &str) -> anyhow::Result<Self> {
+        let queries = match dialect {
+            Dialect::MySql => MYSQL_QUERIES,
+            Dialect::Postgres => POSTGRES_QUERIES,
+            Dialect::Sqlite => SQLITE_QUERIES,
+        };
+        let fetch = |query: &'static str| async move {
+            sqlx::query(query)
+                .bind(database)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Unable to read the schema of {} : {}", database, e))
+        };
+
+        let mut schema = Schema::default();
+        for row in fetch(queries.columns).await? {
+            let table = text(&row, "table_name")?;
+            schema
+                .tables
+                .entry(table)
+                .or_default()
+                .columns
+                .push(ColumnSchema {
+                    name: text(&row, "column_name")?,
+                    data_type: text(&row, "data_type")?,
+                    nullable: flag(&row, "nullable")?,
+                    default: optional_text(&row, "column_default")?,
+                });
+        }
+
+        for row in fetch(queries.primary_keys).await? {
+            if let Some(table) = schema.tables.get_mut(&text(&row, "table_name")?) {
+                table.primary_key.push(text(&row, "column_name")?);
+                table.primary_key_name = optional_text(&row, "constraint_name")?;
+            }
+        }
+
+        for row in fetch(queries.indexes).await? {
+            let Some(table) = schema.tables.get_mut(&text(&row, "table_name")?) else {
+                continue;
+            };
+            let name = text(&row, "index_name")?;
+            let column = text(&row, "column_name")?;
+            match table.indexes.iter_mut().find(|i| i.name == name) {
+                Some(index) => index.columns.push(column),
+                None => table.indexes.push(IndexSchema {
+                    name,
+                    columns: vec![column],
+                    unique: flag(&row, "is_unique")?,
+                }),
+            }
+        }
+
+        for row in fetch(queries.foreign_keys).await? {
+            let Some(table) = schema.tables.get_mut(&text(&row, "table_name")?) else {
+                continue;
+            };
+            let name = text(&row, "constraint_name")?;
+            let column = text(&row, "column_name")?;
+            let foreign_column = text(&row, "foreign_column")?;
+            match table.foreign_keys.iter_mut().find(|f| f.name == name) {
+                Some(foreign_key) => {
+                    foreign_key.columns.push(column);
+                    foreign_key.foreign_columns.push(foreign_column);
+                }
+                None => table.foreign_keys.push(ForeignKeySchema {
+                    name,
+                    columns: vec![column],
+                    foreign_table: text(&row, "foreign_table")?,
+                    foreign_columns: vec![foreign_column],
+                }),
+            }
+        }
+
+        if dialect == Dialect::Sqlite {
+            // sqlite doesn't name foreign keys, they are told apart by what
+            // they point to
+            for table in schema.tables.values_mut() {
+                for foreign_key in &mut table.foreign_keys {
+                    foreign_key.name = format!(
+                        "{}({})",
+                        foreign_key.foreign_table,
+                        foreign_key.columns.join(",")
+                    );
+                }
+            }
+        }
+        Ok(schema)
+    }
+}
+
+/// Columns of the primary key of the table, in their order in the key. Empty
+/// when the table has none.
+pub async fn primary_key(
+    pool: &Pool<Any>,
+    dialect: Dialect,
+    database: &str,
+    table: &str,
+) -> anyhow::Result<Vec<String>> {
+    let query = match dialect {
+        Dialect::MySql => {
+            "select column_name as name from information_schema.key_column_usage \
+             where table_schema = ? and table_name = ? and constraint_name = 'PRIMARY' \
+             order by ordinal_position"
+        }
+        Dialect::Postgres => {
+            "select k.column_name::text as name \
+             from information_schema.table_constraints c \
+             join information_schema.key_column_usage k \
+             on k.constraint_name = c.constraint_name and k.table_schema = c.table_schema \
+             and k.table_name = c.table_name \
+             where c.table_schema = $1 and c.table_name = $2 and c.constraint_type = 'PRIMARY KEY' \
+             order by k.ordinal_position"
+        }
+        Dialect::Sqlite => "select name from pragma_table_info(?2, ?1) where pk > 0 order by pk",
+    };
+    let rows = sqlx::query(query)
+        .bind(database)
+        .bind(table)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| row.try_get::<String, _>("name"))
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Catalog queries of an engine, each taking the database as only parameter
+/// and sorted so the columns of an index or a key come in order.
+struct CatalogQueries {
+    columns: &'static str,
+    primary_keys: &'static str,
+    indexes: &'static str,
+    foreign_keys: &'static str,
+}
+
+const MYSQL_QUERIES: CatalogQueries = CatalogQueries {
+    columns: "select c.table_name as table_name, c.column_name as column_name, \
+        c.column_type as data_type, c.is_nullable = 'YES' as nullable, \
+        c.column_default as column_default \
+        from information_schema.columns c join information_schema.tables t \
+        on t.table_schema = c.table_schema and t.table_name = c.table_name \
+        where c.table_schema = ? and t.table_type = 'BASE TABLE' \
+        order by c.table_name, c.ordinal_position",
+    primary_keys: "select table_name as table_name, column_name as column_name, \
+        constraint_name as constraint_name \
+        from information_schema.key_column_usage \
+        where table_schema = ? and constraint_name = 'PRIMARY' \
+        order by table_name, ordinal_position",
+    indexes: "select table_name as table_name, index_name as index_name, \
+        column_name as column_name, non_unique = 0 as is_unique \
+        from information_schema.statistics \
+        where table_schema = ? and index_name <> 'PRIMARY' \
+        order by table_name, index_name, seq_in_index",
+    foreign_keys: "select table_name as table_name, constraint_name as constraint_name, \
+        column_name as column_name, referenced_table_name as foreign_table, \
+        referenced_column_name as foreign_column \
+        from information_schema.key_column_usage \
+        where table_schema = ? and referenced_table_name is not null \
+        order by table_name, constraint_name, ordinal_position",
+};
+
+const POSTGRES_QUERIES: CatalogQueries = CatalogQueries {
+    columns: "select c.relname::text as table_name, a.attname::text as column_name, \
+        format_type(a.atttypid, a.atttypmod) as data_type, not a.attnotnull as nullable, \
+        pg_get_expr(d.adbin, d.adrelid) as column_default \
+        from pg_attribute a \
+        join pg_class c on c.oid = a.attrelid \
+        join pg_namespace n on n.oid = c.relnamespace \
+        left join pg_attrdef d on d.adrelid = a.attrelid and d.adnum = a.attnum \
+        where n.nspname = $1 and c.relkind in ('r', 'p') and a.attnum > 0 \
+        and not a.attisdropped \
+        order by c.relname, a.attnum",
+    primary_keys: "select c.relname::text as table_name, a.attname::text as column_name, \
+        co.conname::text as constraint_name \
+        from pg_constraint co \
+        join pg_class c on c.oid = co.conrelid \
+        join pg_namespace n on n.oid = c.relnamespace \
+        cross join lateral unnest(co.conkey) with ordinality k(attnum, position) \
+        join pg_attribute a on a.attrelid = co.conrelid and a.attnum = k.attnum \
+        where n.nspname = $1 and co.contype = 'p' \
+        order by c.relname, k.position",
+    indexes: "select t.relname::text as table_name, i.relname::text as index_name, \
+        a.attname::text as column_name, ix.indisunique as is_unique \
+        from pg_index ix \
+        join pg_class i on i.oid = ix.indexrelid \
+        join pg_class t on t.oid = ix.indrelid \
+        join pg_namespace n on n.oid = t.relnamespace \
+        cross join lateral unnest(ix.indkey) with ordinality k(attnum, position) \
+        join pg_attribute a on a.attrelid = t.oid and a.attnum = k.attnum \
+        where n.nspname = $1 and not ix.indisprimary \
+        and not exists (select 1 from pg_constraint co where co.conindid = ix.indexrelid) \
+        order by t.relname, i.relname, k.position",
+    foreign_keys: "select c.relname::text as table_name, co.conname::text as constraint_name, \
+        a.attname::text as column_name, fc.relname::text as foreign_table, \
+        fa.attname::text as foreign_column \
+        from pg_constraint co \
+        join pg_class c on c.oid = co.conrelid \
+        join pg_namespace n on n.oid = c.relnamespace \
+        join pg_class fc on fc.oid = co.confrelid \
+        cross join lateral unnest(co.conkey, co.confkey) with ordinality k(attnum, foreign_attnum, position) \
+        join pg_attribute a on a.attrelid = co.conrelid and a.attnum = k.attnum \
+        join pg_attribute fa on fa.attrelid = co.confrelid and fa.attnum = k.foreign_attnum \
+        where n.nspname = $1 and co.contype = 'f' \
+        order by c.relname, co.conname, k.position",
+};
+
+const SQLITE_QUERIES: CatalogQueries = CatalogQueries {
+    columns: "select m.name as table_name, p.name as column_name, p.type as data_type, \
+        not p.\"notnull\" as nullable, p.dflt_value as column_default \
+        from pragma_table_list m join pragma_table_info(m.name, ?1) p \
+        where m.schema = ?1 and m.type = 'table' and m.name not like 'sqlite_%' \
+        order by m.name, p.cid",
+    primary_keys: "select m.name as table_name, p.name as column_name, \
+        null as constraint_name \
+        from pragma_table_list m join pragma_table_info(m.name, ?1) p \
+        where m.schema = ?1 and m.type = 'table' and p.pk > 0 \
+        order by m.name, p.pk",
+    // indexes sqlite creates itself for unique constraints can't be dropped
+    // or created apart from their table
+    indexes: "select m.name as table_name, l.name as index_name, \
+        i.name as column_name, l.\"unique\" as is_unique \
+        from pragma_table_list m \
+        join pragma_index_list(m.name, ?1) l \
+        join pragma_index_info(l.name, ?1) i \
+        where m.schema = ?1 and m.type = 'table' and l.origin = 'c' \
+        order by m.name, l.name, i.seqno",
+    foreign_keys: "select m.name as table_name, cast(f.id as text) as constraint_name, \
+        f.\"from\" as column_name, f.\"table\" as foreign_table, f.\"to\" as foreign_column \
+        from pragma_table_list m join pragma_foreign_key_list(m.name, ?1) f \
+        where m.schema = ?1 and m.type = 'table' \
+        order by m.name, f.id, f.seq",
+};
+
+fn value(row: &AnyRow, column: &str) -> anyhow::Result<Value> {
+    Value::from_row(row, row.try_column(column)?.ordinal())
+}
+
+/// Catalogs of mysql may hand out text as binary strings.
+fn optional_text(row: &AnyRow, column: &str) -> anyhow::Result<Option<String>> {
+    Ok(match value(row, column)? {
+        Value::Null => None,
+        Value::Text(text) => Some(text),
+        Value::Bytes(bytes) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+        other => Some(other.to_string()),
+    })
+}
+
+fn text(row: &AnyRow, column: &str) -> anyhow::Result<String> {
+    optional_text(row, column)?.ok_or(anyhow::anyhow!("{} is NULL in the catalog.", column))
+}
+
+fn flag(row: &AnyRow, column: &str) -> anyhow::Result<bool> {
+    Ok(match value(row, column)? {
+        Value::Bool(b) => b,
+        Value::Int(i) => i != 0,
+        other => anyhow::bail!("{} is not a boolean : {}", column, other),
+    })
+}
@@ -0,0 +1,844 @@
+use super::{
+    data_diff::SyncDirection,
+    dialect::Dialect,
+    guard::AccessMode,
+    schema::{ColumnSchema, ForeignKeySchema, IndexSchema, Schema, TableSchema},
+};
+use crate::application::{AppAction, StoreAction, UpdateAction};
+use sqlx::{Any, Executor, Pool};
+use std::collections::BTreeSet;
+use tokio::{spawn, sync::mpsc::UnboundedSender};
+
+/// One of the two databases being compared.
+#[derive(Debug, Clone)]
+pub struct SchemaSide {
+    /// Name of the connection, to tell the sides apart.
+    pub label: String,
+    pub pool: Pool<Any>,
+    pub dialect: Dialect,
+    pub database: String,
+    pub mode: AccessMode,
+}
+
+impl SchemaSide {
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.label, self.database)
+    }
+}
+
+/// What has to change in the target database for its table to look like the
+/// one of the source database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChange {
+    CreateTable(String),
+    DropTable(String),
+    AddColumn(String, ColumnSchema),
+    DropColumn(String, String),
+    /// The column as it is in the target, then as it should be.
+    AlterColumn(String, ColumnSchema, ColumnSchema),
+    /// The primary key as it is in the target, then as it should be, each
+    /// empty when the table has none.
+    AlterPrimaryKey(String, Vec<String>, Vec<String>),
+    AddIndex(String, IndexSchema),
+    DropIndex(String, IndexSchema),
+    AddForeignKey(String, ForeignKeySchema),
+    DropForeignKey(String, ForeignKeySchema),
+}
+
+impl SchemaChange {
+    pub fn table(&self) -> &str {
+        match self {
+            SchemaChange::CreateTable(table)
+            | SchemaChange::DropTable(table)
+            | SchemaChange::AddColumn(table, _)
+            | SchemaChange::DropColumn(table, _)
+            | SchemaChange::AlterColumn(table, _, _)
+            | SchemaChange::AlterPrimaryKey(table, _, _)
+            | SchemaChange::AddIndex(table, _)
+            | SchemaChange::DropIndex(table, _)
+            | SchemaChange::AddForeignKey(table, _)
+            | SchemaChange::DropForeignKey(table, _) => table,
+        }
+    }
+}
+
+/// Schemas of two databases of the same engine.
+#[derive(Debug, Clone)]
+pub struct SchemaDiff {
+    pub left: SchemaSide,
+    pub right: SchemaSide,
+    pub left_schema: Schema,
+    pub right_schema: Schema,
+}
+
+impl SchemaDiff {
+    /// Read both schemas and send the result as `AppAction::SendSchemaDiff`.
+    pub fn compare(left: SchemaSide, right: SchemaSide, sender: UnboundedSender<UpdateAction>) {
+        spawn(async move {
+            let action = match SchemaDiff::load(left, right).await {
+                Ok(diff) => UpdateAction::SendAppAction(AppAction::SendSchemaDiff(Box::new(diff))),
+                Err(e) => UpdateAction::SendStoreAction(StoreAction::SendQueryError(e.to_string())),
+            };
+            let _ = sender.send(action);
+        });
+    }
+
+    async fn load(left: SchemaSide, right: SchemaSide) -> anyhow::Result<Self> {
+        if left.dialect != right.dialect {
+            anyhow::bail!(
+                "{} and {} don't run on the same engine.",
+                left.name(),
+                right.name()
+            );
+        }
+        let left_schema = Schema::load(&left.pool, left.dialect, &left.database).await?;
+        let right_schema = Schema::load(&right.pool, right.dialect, &right.database).await?;
+        Ok(SchemaDiff {
+            left,
+            right,
+            left_schema,
+            right_schema,
+        })
+    }
+
+    fn sides(&self, direction: SyncDirection) -> (&SchemaSide, &Schema, &SchemaSide, &Schema) {
+        match direction {
+            SyncDirection::LeftToRight => (
+                &self.left,
+                &self.left_schema,
+                &self.right,
+                &self.right_schema,
+            ),
+            SyncDirection::RightToLeft => (
+                &self.right,
+                &self.right_schema,
+                &self.left,
+                &self.left_schema,
+            ),
+        }
+    }
+
+    /// What turns the target of `direction` into the other side.
+    pub fn changes(&self, direction: SyncDirection) -> Vec<SchemaChange> {
+        let (_, source, _, target) = self.sides(direction);
+        let mut changes = Vec::new();
+        let names = source
+            .tables
+            .keys()
+            .chain(target.tables.keys())
+            .collect::<BTreeSet<_>>();
+
+        for name in names {
+            let (from, to) = match (target.tables.get(name), source.tables.get(name)) {
+                (None, Some(_)) => {
+                    changes.push(SchemaChange::CreateTable(name.clone()));
+                    continue;
+                }
+                (Some(_), None) => {
+                    changes.push(SchemaChange::DropTable(name.clone()));
+                    continue;
+                }
+                (Some(from), Some(to)) => (from, to),
+                (None, None) => continue,
+            };
+
+            for column in &to.columns {
+                match from.column(&column.name) {
+                    None => changes.push(SchemaChange::AddColumn(name.clone(), column.clone())),
+                    Some(current) if current != column => changes.push(SchemaChange::AlterColumn(
+                        name.clone(),
+                        current.clone(),
+                        column.clone(),
+                    )),
+                    Some(_) => {}
+                }
+            }
+            for column in &from.columns {
+                if to.column(&column.name).is_none() {
+                    changes.push(SchemaChange::DropColumn(name.clone(), column.name.clone()));
+                }
+            }
+
+            if from.primary_key != to.primary_key {
+                changes.push(SchemaChange::AlterPrimaryKey(
+                    name.clone(),
+                    from.primary_key.clone(),
+                    to.primary_key.clone(),
+                ));
+            }
+
+            for index in &from.indexes {
+                if !to.indexes.contains(index) {
+                    changes.push(SchemaChange::DropIndex(name.clone(), index.clone()));
+                }
+            }
+            for index in &to.indexes {
+                if !from.indexes.contains(index) {
+                    changes.push(SchemaChange::AddIndex(name.clone(), index.clone()));
+                }
+            }
+            for foreign_key in &from.foreign_keys {
+                if !to.foreign_keys.contains(foreign_key) {
+                    changes.push(SchemaChange::DropForeignKey(
+                        name.clone(),
+                        foreign_key.clone(),
+                    ));
+                }
+            }
+            for foreign_key in &to.foreign_keys {
+                if !from.foreign_keys.contains(foreign_key) {
+                    changes.push(SchemaChange::AddForeignKey(
+                        name.clone(),
+                        foreign_key.clone(),
+                    ));
+                }
+            }
+        }
+        changes
+    }
+
+    /// Statements of the migration, in the order they have to run.
+    pub fn statements(&self, direction: SyncDirection) -> Vec<String> {
+        let (_, source, target_side, target) = self.sides(direction);
+        let migration = Migration {
+            dialect: target_side.dialect,
+            database: &target_side.database,
+        };
+        let changes = self.changes(direction);
+
+        // sqlite can't alter a column, a primary key nor a foreign key, the
+        // table is built again instead
+        let rebuilt = if migration.dialect == Dialect::Sqlite {
+            changes
+                .iter()
+                .filter(|c| {
+                    matches!(
+                        c,
+                        SchemaChange::AlterColumn(..)
+                            | SchemaChange::AlterPrimaryKey(..)
+                            | SchemaChange::AddForeignKey(..)
+                            | SchemaChange::DropForeignKey(..)
+                    )
+                })
+                .map(|c| c.table().to_string())
+                .collect::<BTreeSet<_>>()
+        } else {
+            BTreeSet::new()
+        };
+        let kept = |change: &&SchemaChange| !rebuilt.contains(change.table());
+
+        let mut statements = Vec::new();
+        for change in changes.iter().filter(kept) {
+            match change {
+                SchemaChange::DropForeignKey(table, foreign_key) => {
+                    statements.push(migration.drop_foreign_key(table, foreign_key))
+                }
+                SchemaChange::DropIndex(table, index) => {
+                    statements.push(migration.drop_index(table, index))
+                }
+                SchemaChange::AlterPrimaryKey(table, from, _) if !from.is_empty() => {
+                    statements.push(migration.drop_primary_key(table, &target.tables[table]))
+                }
+                _ => {}
+            }
+        }
+        for change in changes.iter().filter(kept) {
+            match change {
+                SchemaChange::CreateTable(table) => {
+                    let schema = &source.tables[table];
+                    statements.extend(migration.create_sequences(&schema.columns));
+                    statements.push(migration.create_table(table, schema))
+                }
+                SchemaChange::AddColumn(table, column) => {
+                    statements.extend(migration.create_sequences(std::slice::from_ref(column)));
+                    statements.push(migration.add_column(table, column))
+                }
+                SchemaChange::AlterColumn(table, from, to) => {
+                    if from.default != to.default {
+                        statements.extend(migration.create_sequences(std::slice::from_ref(to)));
+                    }
+                    statements.extend(migration.alter_column(table, from, to))
+                }
+                SchemaChange::DropColumn(table, column) => {
+                    statements.push(migration.drop_column(table, column))
+                }
+                _ => {}
+            }
+        }
+        for table in &rebuilt {
+            statements.extend(migration.rebuild_table(
+                table,
+                &target.tables[table],
+                &source.tables[table],
+            ));
+        }
+        for change in changes.iter().filter(kept) {
+            if let SchemaChange::DropTable(table) = change {
+                statements.push(migration.drop_table(table));
+            }
+        }
+        for change in changes.iter().filter(kept) {
+            match change {
+                SchemaChange::CreateTable(table) => {
+                    let schema = &source.tables[table];
+                    for index in &schema.indexes {
+                        statements.push(migration.create_index(table, index));
+                    }
+                    if migration.dialect != Dialect::Sqlite {
+                        for foreign_key in &schema.foreign_keys {
+                            statements.push(migration.add_foreign_key(table, foreign_key));
+                        }
+                    }
+                }
+                SchemaChange::AddIndex(table, index) => {
+                    statements.push(migration.create_index(table, index))
+                }
+                SchemaChange::AddForeignKey(table, foreign_key) => {
+                    statements.push(migration.add_foreign_key(table, foreign_key))
+                }
+                SchemaChange::AlterPrimaryKey(table, _, to) if !to.is_empty() => {
+                    statements.push(migration.add_primary_key(table, to))
+                }
+                _ => {}
+            }
+        }
+        statements
+    }
+
+    /// The migration as a file, run in a single transaction where the engine
+    /// allows it.
+    pub fn script(&self, direction: SyncDirection) -> String {
+        let (source, _, target, _) = self.sides(direction);
+        let mut script = vec![format!(
+            "-- Make the schema of {} like the one of {}",
+            target.name(),
+            source.name()
+        )];
+        let statements = self.statements(direction);
+        // mysql commits on its own after each ddl statement
+        let transaction = target.dialect != Dialect::MySql;
+        if transaction {
+            script.push("BEGIN;".to_string());
+        }
+        script.extend(statements.into_iter().map(|s| s + ";"));
+        if transaction {
+            script.push("COMMIT;".to_string());
+        }
+        script.join("\n") + "\n"
+    }
+
+    /// Run the migration on the target side, rolled back if a statement fails
+    /// except on mysql, where the statements run before it stay applied.
+    pub fn run(&self, direction: SyncDirection, sender: UnboundedSender<UpdateAction>) {
+        let (_, _, target, _) = self.sides(direction);
+        let name = target.name();
+        let pool = target.pool.clone();
+        let dialect = target.dialect;
+        let statements = self.statements(direction);
+        spawn(async move {
+            let result = match dialect {
+                // mysql commits on its own after each ddl statement, a
+                // transaction wouldn't undo anything
+                Dialect::MySql => run_each(&pool, &statements).await.map_err(|(done, e)| {
+                    format!(
+                        "Migration of {} stopped, {} of {} statements applied : {}",
+                        name,
+                        done,
+                        statements.len(),
+                        e
+                    )
+                }),
+                Dialect::Postgres | Dialect::Sqlite => run_in_transaction(&pool, &statements)
+                    .await
+                    .map_err(|e| format!("Migration of {} rolled back : {}", name, e)),
+            };
+            let action = match result {
+                Ok(()) => StoreAction::SendSuccess(format!(
+                    "Migration of {} done, {} statements run.",
+                    name,
+                    statements.len()
+                )),
+                Err(e) => StoreAction::SendQueryError(e),
+            };
+            let _ = sender.send(UpdateAction::SendStoreAction(action));
+        });
+    }
+}
+
+async fn run_in_transaction(pool: &Pool<Any>, statements: &[String]) -> anyhow::Result<()> {
+    let mut transaction = pool.begin().await?;
+    for statement in statements {
+        if let Err(e) = transaction.execute(statement.as_str()).await {
+            anyhow::bail!("{} failed : {}", statement, e);
+        }
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Run the statements one after the other, telling how many ran before the
+/// one that failed.
+async fn run_each(pool: &Pool<Any>, statements: &[String]) -> Result<(), (usize, String)> {
+    let mut connection = pool.acquire().await.map_err(|e| (0, e.to_string()))?;
+    for (done, statement) in statements.iter().enumerate() {
+        if let Err(e) = connection.execute(statement.as_str()).await {
+            return Err((done, format!("{} failed : {}", statement, e)));
+        }
+    }
+    Ok(())
+}
+
+/// Writes the statements of a migration for the engine of the target.
+struct Migration<'a> {
+    dialect: Dialect,
+    database: &'a str,
+}
+
+impl Migration<'_> {
+    fn quote(&self, ident: &str) -> String {
+        self.dialect.quote_identifier(ident)
+    }
+
+    fn table(&self, table: &str) -> String {
+        self.dialect.qualified_name(self.database, table)
+    }
+
+    fn list(&self, columns: &[String]) -> String {
+        columns
+            .iter()
+            .map(|c| self.quote(c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn column_definition(&self, column: &ColumnSchema) -> String {
+        let mut definition = format!("{} {}", self.quote(&column.name), column.data_type);
+        if !column.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        if let Some(default) = &column.default {
+            definition.push_str(&format!(" DEFAULT {}", self.default(default)));
+        }
+        definition.trim_end().to_string()
+    }
+
+    /// The default of the source, drawing from the sequence of the same name
+    /// in the target on postgres.
+    fn default(&self, default: &str) -> String {
+        match sequence(default).filter(|_| self.dialect == Dialect::Postgres) {
+            Some(sequence) => format!(
+                "nextval('{}'::regclass)",
+                self.table(sequence).replace('\'', "''")
+            ),
+            None => default.to_string(),
+        }
+    }
+
+    /// `CREATE SEQUENCE` for each postgres sequence the defaults of the
+    /// columns draw from, as the catalog only names them.
+    fn create_sequences(&self, columns: &[ColumnSchema]) -> Vec<String> {
+        if self.dialect != Dialect::Postgres {
+            return Vec::new();
+        }
+        columns
+            .iter()
+            .filter_map(|c| sequence(c.default.as_deref()?))
+            .map(|s| format!("CREATE SEQUENCE IF NOT EXISTS {}", self.table(s)))
+            .collect()
+    }
+
+    fn references(&self, foreign_key: &ForeignKeySchema) -> String {
+        // sqlite only lets a table point to another one of the same schema
+        let table = match self.dialect {
+            Dialect::Sqlite => self.quote(&foreign_key.foreign_table),
+            Dialect::MySql | Dialect::Postgres => self.table(&foreign_key.foreign_table),
+        };
+        format!(
+            "FOREIGN KEY ({}) REFERENCES {} ({})",
+            self.list(&foreign_key.columns),
+            table,
+            self.list(&foreign_key.foreign_columns)
+        )
+    }
+
+    /// `CREATE TABLE`, the foreign keys being added apart except on sqlite.
+    fn create_table(&self, name: &str, schema: &TableSchema) -> String {
+        let mut lines = schema
+            .columns
+            .iter()
+            .map(|c| self.column_definition(c))
+            .collect::<Vec<_>>();
+        if !schema.primary_key.is_empty() {
+            lines.push(format!("PRIMARY KEY ({})", self.list(&schema.primary_key)));
+        }
+        if self.dialect == Dialect::Sqlite {
+            lines.extend(schema.foreign_keys.iter().map(|f| self.references(f)));
+        }
+        format!(
+            "CREATE TABLE {} (\n    {}\n)",
+            self.table(name),
+            lines.join(",\n    ")
+        )
+    }
+
+    fn drop_table(&self, table: &str) -> String {
+        format!("DROP TABLE {}", self.table(table))
+    }
+
+    fn add_column(&self, table: &str, column: &ColumnSchema) -> String {
+        format!(
+            "ALTER TABLE {} ADD COLUMN {}",
+            self.table(table),
+            self.column_definition(column)
+        )
+    }
+
+    fn drop_column(&self, table: &str, column: &str) -> String {
+        format!(
+            "ALTER TABLE {} DROP COLUMN {}",
+            self.table(table),
+            self.quote(column)
+        )
+    }
+
+    fn alter_column(&self, table: &str, from: &ColumnSchema, to: &ColumnSchema) -> Vec<String> {
+        let table = self.table(table);
+        let column = self.quote(&to.name);
+        if self.dialect != Dialect::Postgres {
+            return vec![format!(
+                "ALTER TABLE {} MODIFY COLUMN {}",
+                table,
+                self.column_definition(to)
+            )];
+        }
+
+        let alter = format!("ALTER TABLE {} ALTER COLUMN {}", table, column);
+        let mut statements = Vec::new();
+        if from.data_type != to.data_type {
+            statements.push(format!(
+                "{} TYPE {} USING {}::{}",
+                alter, to.data_type, column, to.data_type
+            ));
+        }
+        if from.nullable != to.nullable {
+            let action = if to.nullable { "DROP" } else { "SET" };
+            statements.push(format!("{} {} NOT NULL", alter, action));
+        }
+        if from.default != to.default {
+            statements.push(match &to.default {
+                Some(default) => format!("{} SET DEFAULT {}", alter, self.default(default)),
+                None => format!("{} DROP DEFAULT", alter),
+            });
+        }
+        statements
+    }
+
+    fn drop_primary_key(&self, table: &str, schema: &TableSchema) -> String {
+        match (self.dialect, &schema.primary_key_name) {
+            (Dialect::Postgres, Some(name)) => format!(
+                "ALTER TABLE {} DROP CONSTRAINT {}",
+                self.table(table),
+                self.quote(name)
+            ),
+            _ => format!("ALTER TABLE {} DROP PRIMARY KEY", self.table(table)),
+        }
+    }
+
+    fn add_primary_key(&self, table: &str, columns: &[String]) -> String {
+        format!(
+            "ALTER TABLE {} ADD PRIMARY KEY ({})",
+            self.table(table),
+            self.list(columns)
+        )
+    }
+
+    fn create_index(&self, table: &str, index: &IndexSchema) -> String {
+        let unique = if index.unique { "UNIQUE " } else { "" };
+        // sqlite wants the schema on the index rather than on the table
+        let (name, table) = match self.dialect {
+            Dialect::Sqlite => (
+                self.dialect.qualified_name(self.database, &index.name),
+                self.quote(table),
+            ),
+            Dialect::MySql | Dialect::Postgres => (self.quote(&index.name), self.table(table)),
+        };
+        format!(
+            "CREATE {}INDEX {} ON {} ({})",
+            unique,
+            name,
+            table,
+            self.list(&index.columns)
+        )
+    }
+
+    fn drop_index(&self, table: &str, index: &IndexSchema) -> String {
+        match self.dialect {
+            Dialect::MySql => format!(
+                "DROP INDEX {} ON {}",
+                self.quote(&index.name),
+                self.table(table)
+            ),
+            Dialect::Postgres | Dialect::Sqlite => format!(
+                "DROP INDEX {}",
+                self.dialect.qualified_name(self.database, &index.name)
+            ),
+        }
+    }
+
+    fn add_foreign_key(&self, table: &str, foreign_key: &ForeignKeySchema) -> String {
+        format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} {}",
+            self.table(table),
+            self.quote(&foreign_key.name),
+            self.references(foreign_key)
+        )
+    }
+
+    fn drop_foreign_key(&self, table: &str, foreign_key: &ForeignKeySchema) -> String {
+        let keyword = match self.dialect {
+            Dialect::MySql => "FOREIGN KEY",
+            Dialect::Postgres | Dialect::Sqlite => "CONSTRAINT",
+        };
+        format!(
+            "ALTER TABLE {} DROP {} {}",
+            self.table(table),
+            keyword,
+            self.quote(&foreign_key.name)
+        )
+    }
+
+    /// Build the table again under a temporary name, copy the rows of the
+    /// columns it keeps and swap it with the old one.
+    fn rebuild_table(&self, table: &str, from: &TableSchema, to: &TableSchema) -> Vec<String> {
+        let temporary = format!("{}__dbv_new", table);
+        let kept = to
+            .columns
+            .iter()
+            .filter(|c| from.column(&c.name).is_some())
+            .map(|c| c.name.clone())
+            .collect::<Vec<_>>();
+        let mut statements = vec![
+            self.create_table(&temporary, to),
+            format!(
+                "INSERT INTO {} ({}) SELECT {} FROM {}",
+                self.table(&temporary),
+                self.list(&kept),
+                self.list(&kept),
+                self.table(table)
+            ),
+            self.drop_table(table),
+            format!(
+                "ALTER TABLE {} RENAME TO {}",
+                self.table(&temporary),
+                self.quote(table)
+            ),
+        ];
+        statements.extend(to.indexes.iter().map(|i| self.create_index(table, i)));
+        statements
+    }
+}
+
+/// Sequence a postgres default draws from, as in
+/// `nextval('users_id_seq'::regclass)`, without its schema.
+fn sequence(default: &str) -> Option<&str> {
+    let (name, _) = default
+        .strip_prefix("nextval('")?
+        .split_once("'::regclass)")?;
+    name.rsplit('.').next().map(|n| n.trim_matches('"'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::any::AnyPoolOptions;
+
+    fn column(name: &str, data_type: &str, nullable: bool, default: Option<&str>) -> ColumnSchema {
+        ColumnSchema {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable,
+            default: default.map(String::from),
+        }
+    }
+
+    fn side(label: &str, dialect: Dialect, database: &str) -> SchemaSide {
+        sqlx::any::install_default_drivers();
+        SchemaSide {
+            label: label.to_string(),
+            pool: AnyPoolOptions::new()
+                .connect_lazy("sqlite::memory:")
+                .unwrap(),
+            dialect,
+            database: database.to_string(),
+            mode: AccessMode::default(),
+        }
+    }
+
+    /// `users` gains an email and a unique index on it, its name becomes
+    /// mandatory, `logs` goes and `posts` comes.
+    fn diff(dialect: Dialect, database: &str) -> SchemaDiff {
+        let users = TableSchema {
+            columns: vec![
+                column("id", "integer", false, None),
+                column("name", "text", true, None),
+            ],
+            primary_key: vec!["id".to_string()],
+            primary_key_name: Some("users_pkey".to_string()),
+            ..Default::default()
+        };
+        let mut wanted = users.clone();
+        wanted.columns[1].nullable = false;
+        wanted
+            .columns
+            .push(column("email", "text", true, Some("''")));
+        wanted.indexes.push(IndexSchema {
+            name: "users_email".to_string(),
+            columns: vec!["email".to_string()],
+            unique: true,
+        });
+        let posts = TableSchema {
+            columns: vec![column(
+                "id",
+                "integer",
+                false,
+                Some("nextval('app.posts_id_seq'::regclass)"),
+            )],
+            primary_key: vec!["id".to_string()],
+            ..Default::default()
+        };
+        let source = Schema {
+            tables: [("users".to_string(), wanted), ("posts".to_string(), posts)].into(),
+        };
+        let target = Schema {
+            tables: [
+                ("users".to_string(), users),
+                ("logs".to_string(), TableSchema::default()),
+            ]
+            .into(),
+        };
+        SchemaDiff {
+            left: side("dev", dialect, database),
+            right: side("prod", dialect, database),
+            left_schema: source,
+            right_schema: target,
+        }
+    }
+
+    #[tokio::test]
+    async fn changes_turn_the_target_into_the_source() {
+        let diff = diff(Dialect::Postgres, "app");
+        assert_eq!(
+            diff.changes(SyncDirection::LeftToRight),
+            [
+                SchemaChange::DropTable("logs".to_string()),
+                SchemaChange::CreateTable("posts".to_string()),
+                SchemaChange::AlterColumn(
+                    "users".to_string(),
+                    column("name", "text", true, None),
+                    column("name", "text", false, None),
+                ),
+                SchemaChange::AddColumn(
+                    "users".to_string(),
+                    column("email", "text", true, Some("''"))
+                ),
+                SchemaChange::AddIndex(
+                    "users".to_string(),
+                    IndexSchema {
+                        name: "users_email".to_string(),
+                        columns: vec!["email".to_string()],
+                        unique: true,
+                    }
+                ),
+            ]
+        );
+        let back = diff.changes(SyncDirection::RightToLeft);
+        assert!(back.contains(&SchemaChange::CreateTable("logs".to_string())));
+        assert!(back.contains(&SchemaChange::DropColumn(
+            "users".to_string(),
+            "email".to_string()
+        )));
+    }
+
+    #[tokio::test]
+    async fn changes_of_the_primary_key() {
+        let mut diff = diff(Dialect::Postgres, "app");
+        let users = diff.left_schema.tables.get_mut("users").unwrap();
+        users.primary_key = vec!["id".to_string(), "name".to_string()];
+        assert!(diff
+            .changes(SyncDirection::LeftToRight)
+            .contains(&SchemaChange::AlterPrimaryKey(
+                "users".to_string(),
+                vec!["id".to_string()],
+                vec!["id".to_string(), "name".to_string()],
+            )));
+        let statements = diff.statements(SyncDirection::LeftToRight);
+        assert_eq!(
+            statements[0],
+            "ALTER TABLE \"app\".\"users\" DROP CONSTRAINT \"users_pkey\""
+        );
+        assert!(statements.contains(
+            &"ALTER TABLE \"app\".\"users\" ADD PRIMARY KEY (\"id\", \"name\")".to_string()
+        ));
+    }
+
+    #[tokio::test]
+    async fn postgres_script_runs_in_a_transaction() {
+        let script = diff(Dialect::Postgres, "app").script(SyncDirection::LeftToRight);
+        assert_eq!(
+            script,
+            "-- Make the schema of prod.app like the one of dev.app\n\
+             BEGIN;\n\
+             CREATE SEQUENCE IF NOT EXISTS \"app\".\"posts_id_seq\";\n\
+             CREATE TABLE \"app\".\"posts\" (\n    \
+             \"id\" integer NOT NULL DEFAULT nextval('\"app\".\"posts_id_seq\"'::regclass),\n    \
+             PRIMARY KEY (\"id\")\n);\n\
+             ALTER TABLE \"app\".\"users\" ALTER COLUMN \"name\" SET NOT NULL;\n\
+             ALTER TABLE \"app\".\"users\" ADD COLUMN \"email\" text DEFAULT '';\n\
+             DROP TABLE \"app\".\"logs\";\n\
+             CREATE UNIQUE INDEX \"users_email\" ON \"app\".\"users\" (\"email\");\n\
+             COMMIT;\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn mysql_script_has_no_transaction() {
+        let script = diff(Dialect::MySql, "app").script(SyncDirection::LeftToRight);
+        assert!(!script.contains("BEGIN;"));
+        assert!(!script.contains("SEQUENCE"));
+        assert!(script.contains("ALTER TABLE `app`.`users` MODIFY COLUMN `name` text NOT NULL;\n"));
+    }
+
+    #[tokio::test]
+    async fn sqlite_rebuilds_altered_tables() {
+        let statements = diff(Dialect::Sqlite, "main").statements(SyncDirection::LeftToRight);
+        assert_eq!(
+            statements[1..],
+            [
+                "CREATE TABLE \"main\".\"users__dbv_new\" (\n    \
+                 \"id\" integer NOT NULL,\n    \
+                 \"name\" text NOT NULL,\n    \
+                 \"email\" text DEFAULT '',\n    \
+                 PRIMARY KEY (\"id\")\n)",
+                "INSERT INTO \"main\".\"users__dbv_new\" (\"id\", \"name\") \
+                 SELECT \"id\", \"name\" FROM \"main\".\"users\"",
+                "DROP TABLE \"main\".\"users\"",
+                "ALTER TABLE \"main\".\"users__dbv_new\" RENAME TO \"users\"",
+                "CREATE UNIQUE INDEX \"main\".\"users_email\" ON \"users\" (\"email\")",
+                "DROP TABLE \"main\".\"logs\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn sequences_of_postgres_defaults() {
+        assert_eq!(
+            sequence("nextval('users_id_seq'::regclass)"),
+            Some("users_id_seq")
+        );
+        assert_eq!(
+            sequence("nextval('app.\"Odd_seq\"'::regclass)"),
+            Some("Odd_seq")
+        );
+        assert_eq!(sequence("0"), None);
+    }
+}
@@ -0,0 +1,511 @@
+/// What running a statement does to the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementKind {
+    Read,
+    Write,
+    /// Drops or empties a whole table or database, or changes every row of
+    /// a table.
+    Destructive,
+}
+
+/// Bits of a statement which tell what it does, literals, quoted
+/// identifiers and comments being skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Keyword or bare identifier, lowercased, with how deep in parentheses
+    /// it is.
+    Word(String, usize),
+    /// Bind parameter as written, like `:id`, `$1` or `?`.
+    Parameter(String),
+    Symbol(char),
+    End,
+}
+
+/// Tokens of `sql` with the byte they start at.
+fn tokens(sql: &str) -> Vec<(usize, Token)> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.char_indices().peekable();
+    let mut depth = 0usize;
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '-' if chars.peek().map(|(_, c)| *c) == Some('-') => {
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek().map(|(_, c)| *c) == Some('*') => {
+                chars.next();
+                let mut previous = ' ';
+                for (_, c) in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '\'' | '"' | '`' => {
+                // a doubled quote is part of the literal, it is read as two
+                // literals next to each other
+                for (_, next) in chars.by_ref() {
+                    if next == c {
+                        break;
+                    }
+                }
+            }
+            '$' => {
+                // postgres dollar quoting, `$$ ... $$` or `$tag$ ... $tag$`
+                let mut tag = String::from("$");
+                while let Some((_, next)) = chars.peek().copied() {
+                    if next.is_alphanumeric() || next == '_' {
+                        tag.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if chars.peek().map(|(_, c)| *c) != Some('$') {
+                    // a positional parameter like `$1`
+                    if tag.len() > 1 && tag[1..].chars().all(|c| c.is_ascii_digit()) {
+                        tokens.push((start, Token::Parameter(tag)));
+                    }
+                    continue;
+                }
+                chars.next();
+                tag.push('$');
+                let body_start = start + tag.len();
+                let end = sql[body_start..]
+                    .find(&tag)
+                    .map(|i| body_start + i + tag.len())
+                    .unwrap_or(sql.len());
+                while chars.peek().is_some_and(|(i, _)| *i < end) {
+                    chars.next();
+                }
+            }
+            // a `::` cast, not a parameter
+            ':' if chars.peek().map(|(_, c)| *c) == Some(':') => {
+                chars.next();
+                tokens.push((start, Token::Symbol(c)));
+            }
+            ':' if chars
+                .peek()
+                .is_some_and(|(_, c)| c.is_alphabetic() || *c == '_') =>
+            {
+                let mut name = String::from(":");
+                while let Some((_, next)) = chars.peek().copied() {
+                    if next.is_alphanumeric() || next == '_' {
+                        name.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push((start, Token::Parameter(name)));
+            }
+            '?' => tokens.push((start, Token::Parameter(c.to_string()))),
+            '(' => {
+                tokens.push((start, Token::Symbol(c)));
+                depth += 1;
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                tokens.push((start, Token::Symbol(c)));
+            }
+            ';' => tokens.push((start, Token::End)),
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = c.to_lowercase().to_string();
+                while let Some((_, next)) = chars.peek().copied() {
+                    if next.is_alphanumeric() || next == '_' {
+                        word.extend(next.to_lowercase());
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push((start, Token::Word(word, depth)));
+            }
+            c if c.is_whitespace() => {}
+            c => tokens.push((start, Token::Symbol(c))),
+        }
+    }
+    tokens
+}
+
+/// Split a script on the `;` ending its statements, leaving out the empty
+/// ones.
+pub fn split(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut has_word = false;
+    let ends = tokens(sql)
+        .into_iter()
+        .filter_map(|(i, token)| match token {
+            Token::End => Some((i, true)),
+            Token::Word(..) => Some((i, false)),
+            _ => None,
+        });
+    for (i, end) in ends.chain(std::iter::once((sql.len(), true))) {
+        if !end {
+            has_word = true;
+            continue;
+        }
+        if has_word {
+            statements.push(sql[start..i].trim().to_string());
+        }
+        start = (i + 1).min(sql.len());
+        has_word = false;
+    }
+    statements
+}
+
+/// The statement `at`, a byte in `sql`, with the byte it starts at.
+pub fn statement_at(sql: &str, at: usize) -> (usize, &str) {
+    let mut start = 0;
+    let mut end = sql.len();
+    for (i, token) in tokens(sql) {
+        if token != Token::End {
+            continue;
+        }
+        if i < at {
+            start = i + 1;
+        } else {
+            end = i;
+            break;
+        }
+    }
+    (start, &sql[start..end])
+}
+
+/// Tables a statement reads or writes, lowercased, with the alias each one
+/// is given.
+pub fn references(statement: &str) -> Vec<(String, Option<String>)> {
+    let tokens = tokens(statement)
+        .into_iter()
+        .map(|(_, token)| token)
+        .collect::<Vec<_>>();
+    let mut references = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let Token::Word(clause, _) = &tokens[i] else {
+            i += 1;
+            continue;
+        };
+        i += 1;
+        if !TABLE_CLAUSES.contains(&clause.as_str()) {
+            continue;
+        }
+        // `from a x, b y` lists several tables
+        while let Some(Token::Word(name, _)) = tokens.get(i) {
+            if CLAUSES.contains(&name.as_str()) {
+                break;
+            }
+            let mut table = name.clone();
+            i += 1;
+            // only the table is kept of `schema.table`
+            while let (Some(Token::Symbol('.')), Some(Token::Word(name, _))) =
+                (tokens.get(i), tokens.get(i + 1))
+            {
+                table = name.clone();
+                i += 2;
+            }
+            if matches!(tokens.get(i), Some(Token::Word(word, _)) if word == "as") {
+                i += 1;
+            }
+            let alias = match tokens.get(i) {
+                Some(Token::Word(alias, _)) if !CLAUSES.contains(&alias.as_str()) => {
+                    i += 1;
+                    Some(alias.clone())
+                }
+                _ => None,
+            };
+            references.push((table, alias));
+            if clause != "from" || tokens.get(i) != Some(&Token::Symbol(',')) {
+                break;
+            }
+            i += 1;
+        }
+    }
+    references
+}
+
+/// Whether a table name is expected at the end of the beginning of a
+/// statement, in its `from` or `join` clause.
+pub fn expects_table(beginning: &str) -> bool {
+    tokens(beginning)
+        .into_iter()
+        .rev()
+        .find_map(|(_, token)| match token {
+            Token::Word(word, _) if TABLE_CLAUSES.contains(&word.as_str()) => Some(true),
+            Token::Word(word, _) if CLAUSES.contains(&word.as_str()) => Some(false),
+            _ => None,
+        })
+        .unwrap_or(false)
+}
+
+/// Bind parameters of a statement as written, with the byte they start at.
+/// Those in literals and comments are left out.
+pub fn parameters(statement: &str) -> Vec<(usize, String)> {
+    tokens(statement)
+        .into_iter()
+        .filter_map(|(i, token)| match token {
+            Token::Parameter(parameter) => Some((i, parameter)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Tell what a single statement does. Anything not known to only read is
+/// taken as a write.
+pub fn kind(statement: &str) -> StatementKind {
+    let tokens = tokens(statement)
+        .into_iter()
+        .map(|(_, token)| token)
+        .collect::<Vec<_>>();
+    kind_of(&tokens)
+}
+
+/// Whether the statement only changes the session, such as the database in
+/// use, a variable or the start of a transaction, leaving the data as it is.
+pub fn changes_session(statement: &str) -> bool {
+    let words = tokens(statement)
+        .into_iter()
+        .filter_map(|(_, token)| match token {
+            Token::Word(word, _) => Some(word),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let second = words.get(1).map(String::as_str);
+    match words.first().map(String::as_str) {
+        Some("use" | "begin") => true,
+        Some("start") => second == Some("transaction"),
+        // `set global` and `set persist` change the server for every session
+        Some("set") => !matches!(second, Some("global" | "persist" | "persist_only")),
+        _ => false,
+    }
+}
+
+fn kind_of(tokens: &[Token]) -> StatementKind {
+    let words = tokens
+        .iter()
+        .filter_map(|token| match token {
+            Token::Word(word, depth) => Some((word.as_str(), *depth)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let has = |wanted: &[&str]| words.iter().any(|(word, _)| wanted.contains(word));
+    let has_top = |wanted: &str| {
+        words
+            .iter()
+            .any(|(word, depth)| *word == wanted && *depth == 0)
+    };
+
+    let Some((first, _)) = words.first() else {
+        return StatementKind::Read;
+    };
+    match *first {
+        "select" if has_top("into") => StatementKind::Write,
+        "with" if has(&["insert", "update", "delete", "merge", "into"]) => StatementKind::Write,
+        "select" | "with" | "show" | "describe" | "desc" | "values" | "table" => {
+            StatementKind::Read
+        }
+        // explain analyze runs the statement it explains
+        "explain" if has(&["analyze", "analyse"]) => {
+            let explained = tokens.iter().position(|token| {
+                matches!(token, Token::Word(word, 0) if STATEMENT_STARTS.contains(&word.as_str()))
+            });
+            match explained {
+                Some(i) => kind_of(&tokens[i..]),
+                None => StatementKind::Write,
+            }
+        }
+        "explain" => StatementKind::Read,
+        // `pragma name = value` and `pragma name(value)` set what they name
+        // for most pragmas, only the ones listing the schema are reads
+        "pragma" if tokens.contains(&Token::Symbol('=')) => StatementKind::Write,
+        "pragma" if tokens.contains(&Token::Symbol('(')) => {
+            match words.get(1).map(|(word, _)| *word) {
+                Some(name) if READ_PRAGMAS.contains(&name) => StatementKind::Read,
+                _ => StatementKind::Write,
+            }
+        }
+        "pragma" => StatementKind::Read,
+        "drop" | "truncate" => StatementKind::Destructive,
+        "update" | "delete" if !has_top("where") => StatementKind::Destructive,
+        _ => StatementKind::Write,
+    }
+}
+
+const STATEMENT_STARTS: [&str; 8] = [
+    "select", "with", "values", "table", "insert", "update", "delete", "merge",
+];
+
+const READ_PRAGMAS: [&str; 8] = [
+    "table_info",
+    "table_xinfo",
+    "table_list",
+    "index_info",
+    "index_xinfo",
+    "index_list",
+    "foreign_key_list",
+    "foreign_key_check",
+];
+
+/// Keywords followed by the tables a statement works on.
+const TABLE_CLAUSES: [&str; 4] = ["from", "join", "update", "into"];
+
+/// Keywords ending the list of tables of a clause.
+const CLAUSES: [&str; 25] = [
+    "select",
+    "where",
+    "join",
+    "inner",
+    "left",
+    "right",
+    "full",
+    "cross",
+    "natural",
+    "outer",
+    "on",
+    "using",
+    "group",
+    "order",
+    "limit",
+    "offset",
+    "set",
+    "values",
+    "union",
+    "except",
+    "intersect",
+    "having",
+    "window",
+    "returning",
+    "default",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(sql: &str) -> Vec<String> {
+        tokens(sql)
+            .into_iter()
+            .filter_map(|(_, token)| match token {
+                Token::Word(word, _) => Some(word),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tokens_skip_literals_and_comments() {
+        let sql = "SELECT 'a;b', \"Drop\" -- delete\n/* truncate; */ FROM `t` $$ x; $$";
+        assert_eq!(words(sql), ["select", "from"]);
+        assert_eq!(
+            tokens(sql)
+                .iter()
+                .filter(|(_, token)| *token == Token::End)
+                .count(),
+            0
+        );
+        assert_eq!(words("select $body$ drop $body$, 1"), ["select"]);
+    }
+
+    #[test]
+    fn tokens_keep_depth_and_positions() {
+        assert_eq!(
+            tokens("Ab (c)"),
+            [
+                (0, Token::Word("ab".into(), 0)),
+                (3, Token::Symbol('(')),
+                (4, Token::Word("c".into(), 1)),
+                (5, Token::Symbol(')')),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_leaves_out_empty_statements() {
+        assert_eq!(
+            split("select ';'; ;\n-- a comment;\ndelete from t;"),
+            ["select ';'", "-- a comment;\ndelete from t"]
+        );
+        assert_eq!(statement_at("select 1; select 2", 12), (9, " select 2"));
+    }
+
+    #[test]
+    fn kinds_of_statements() {
+        assert_eq!(kind("SELECT * FROM t"), StatementKind::Read);
+        assert_eq!(kind("select * into copy from t"), StatementKind::Write);
+        assert_eq!(
+            kind("select * from t where id in (select id into x)"),
+            StatementKind::Read
+        );
+        assert_eq!(
+            kind("with d as (delete from t returning *) select * from d"),
+            StatementKind::Write
+        );
+        assert_eq!(kind("explain select 1"), StatementKind::Read);
+        assert_eq!(
+            kind("explain analyze delete from t"),
+            StatementKind::Destructive
+        );
+        assert_eq!(kind("pragma table_info(t)"), StatementKind::Read);
+        assert_eq!(kind("pragma journal_mode = wal"), StatementKind::Write);
+        assert_eq!(kind("pragma user_version(3)"), StatementKind::Write);
+        assert_eq!(kind("insert into t values (1)"), StatementKind::Write);
+        assert_eq!(
+            kind("update t set a = 1 where id = 2"),
+            StatementKind::Write
+        );
+        assert_eq!(kind("update t set a = 1"), StatementKind::Destructive);
+        assert_eq!(
+            kind("delete from t where id in (select id from u)"),
+            StatementKind::Write
+        );
+        assert_eq!(
+            kind("delete from t using (select 1 where true) u"),
+            StatementKind::Destructive
+        );
+        assert_eq!(kind("drop table t"), StatementKind::Destructive);
+        assert_eq!(kind("TRUNCATE t"), StatementKind::Destructive);
+        assert_eq!(kind("-- nothing"), StatementKind::Read);
+    }
+
+    #[test]
+    fn session_statements() {
+        assert!(changes_session("use app"));
+        assert!(changes_session("BEGIN"));
+        assert!(changes_session("start transaction read only"));
+        assert!(changes_session("set search_path = app"));
+        assert!(!changes_session("set global max_connections = 1"));
+        assert!(!changes_session("start slave"));
+        assert!(!changes_session("select 1"));
+    }
+
+    #[test]
+    fn parameters_leave_out_casts_and_literals() {
+        assert_eq!(
+            parameters("select :id::int, $2, ?, ':no', a::text from t where b = :b_2"),
+            [
+                (7, ":id".to_string()),
+                (17, "$2".to_string()),
+                (21, "?".to_string()),
+                (56, ":b_2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn references_with_aliases() {
+        assert_eq!(
+            references("select * from app.users u join orders as o on o.id = u.id, x"),
+            [
+                ("users".to_string(), Some("u".to_string())),
+                ("orders".to_string(), Some("o".to_string())),
+            ]
+        );
+        assert!(expects_table("select * from "));
+        assert!(!expects_table("select * from t where "));
+    }
+}
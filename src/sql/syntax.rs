@@ -0,0 +1,254 @@
+use std::ops::Range;
+
+/// What a piece of SQL is, to color it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword,
+    Identifier,
+    String,
+    Number,
+    Comment,
+    Operator,
+    Parameter,
+    Whitespace,
+    /// Unterminated literal or comment, or unbalanced parenthesis.
+    Error,
+}
+
+/// Cut `sql` in tokens, every byte of it belonging to one.
+pub fn lex(sql: &str) -> Vec<(Range<usize>, TokenKind)> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.char_indices().peekable();
+    // parentheses not closed yet, as indices in `tokens`
+    let mut open = Vec::new();
+    while let Some((start, c)) = chars.next() {
+        let next = chars.peek().map(|(_, c)| *c);
+        let kind = match c {
+            c if c.is_whitespace() => {
+                while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                TokenKind::Whitespace
+            }
+            '-' if next == Some('-') => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                TokenKind::Comment
+            }
+            '/' if next == Some('*') => {
+                chars.next();
+                let mut previous = ' ';
+                let mut closed = false;
+                for (_, c) in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        closed = true;
+                        break;
+                    }
+                    previous = c;
+                }
+                if closed {
+                    TokenKind::Comment
+                } else {
+                    TokenKind::Error
+                }
+            }
+            '\'' | '"' | '`' => {
+                let mut closed = false;
+                while let Some((_, next)) = chars.next() {
+                    // a doubled quote is part of the literal
+                    if next == c && chars.next_if(|(_, n)| *n == c).is_none() {
+                        closed = true;
+                        break;
+                    }
+                }
+                match (closed, c) {
+                    (false, _) => TokenKind::Error,
+                    (true, '\'') => TokenKind::String,
+                    (true, _) => TokenKind::Identifier,
+                }
+            }
+            '$' => {
+                let mut tag = String::from("$");
+                while let Some((_, next)) = chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_')
+                {
+                    tag.push(next);
+                }
+                if chars.next_if(|(_, c)| *c == '$').is_none() {
+                    TokenKind::Parameter
+                } else {
+                    // postgres dollar quoting, `$$ ... $$` or `$tag$ ... $tag$`
+                    tag.push('$');
+                    let body_start = start + tag.len();
+                    let end = sql[body_start..]
+                        .find(&tag)
+                        .map(|i| body_start + i + tag.len());
+                    while chars
+                        .peek()
+                        .is_some_and(|(i, _)| *i < end.unwrap_or(sql.len()))
+                    {
+                        chars.next();
+                    }
+                    if end.is_some() {
+                        TokenKind::String
+                    } else {
+                        TokenKind::Error
+                    }
+                }
+            }
+            ':' if next == Some(':') => {
+                chars.next();
+                TokenKind::Operator
+            }
+            ':' if next.is_some_and(|c| c.is_alphabetic() || c == '_') => {
+                while chars
+                    .next_if(|(_, c)| c.is_alphanumeric() || *c == '_')
+                    .is_some()
+                {}
+                TokenKind::Parameter
+            }
+            '?' => TokenKind::Parameter,
+            c if c.is_ascii_digit() || (c == '.' && next.is_some_and(|c| c.is_ascii_digit())) => {
+                let mut previous = c;
+                while let Some((_, c)) = chars.next_if(|(_, n)| {
+                    n.is_ascii_alphanumeric()
+                        || *n == '.'
+                        || (matches!(n, '+' | '-') && matches!(previous, 'e' | 'E'))
+                }) {
+                    previous = c;
+                }
+                TokenKind::Number
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while chars
+                    .next_if(|(_, c)| c.is_alphanumeric() || *c == '_')
+                    .is_some()
+                {}
+                let end = chars.peek().map_or(sql.len(), |(i, _)| *i);
+                if KEYWORDS.contains(&sql[start..end].to_lowercase().as_str()) {
+                    TokenKind::Keyword
+                } else {
+                    TokenKind::Identifier
+                }
+            }
+            '(' => {
+                open.push(tokens.len());
+                TokenKind::Operator
+            }
+            ')' if open.pop().is_none() => TokenKind::Error,
+            _ => TokenKind::Operator,
+        };
+        let end = chars.peek().map_or(sql.len(), |(i, _)| *i);
+        tokens.push((start..end, kind));
+    }
+    for index in open {
+        tokens[index].1 = TokenKind::Error;
+    }
+    tokens
+}
+
+/// Words colored as keywords, and offered first when completing.
+pub const KEYWORDS: [&str; 106] = [
+    "select",
+    "from",
+    "where",
+    "and",
+    "or",
+    "not",
+    "null",
+    "is",
+    "in",
+    "between",
+    "like",
+    "ilike",
+    "exists",
+    "as",
+    "distinct",
+    "join",
+    "inner",
+    "left",
+    "right",
+    "full",
+    "outer",
+    "cross",
+    "on",
+    "using",
+    "group",
+    "by",
+    "order",
+    "asc",
+    "desc",
+    "having",
+    "limit",
+    "offset",
+    "union",
+    "all",
+    "insert",
+    "into",
+    "values",
+    "update",
+    "set",
+    "delete",
+    "create",
+    "table",
+    "index",
+    "view",
+    "drop",
+    "alter",
+    "add",
+    "column",
+    "primary",
+    "key",
+    "references",
+    "default",
+    "case",
+    "when",
+    "then",
+    "else",
+    "end",
+    "count",
+    "sum",
+    "avg",
+    "min",
+    "max",
+    "with",
+    "true",
+    "false",
+    "begin",
+    "commit",
+    "rollback",
+    "transaction",
+    "truncate",
+    "explain",
+    "analyze",
+    "pragma",
+    "returning",
+    "unique",
+    "foreign",
+    "constraint",
+    "check",
+    "if",
+    "cascade",
+    "show",
+    "describe",
+    "except",
+    "intersect",
+    "natural",
+    "over",
+    "partition",
+    "cast",
+    "database",
+    "schema",
+    "trigger",
+    "replace",
+    "temporary",
+    "any",
+    "escape",
+    "collate",
+    "nulls",
+    "first",
+    "last",
+    "fetch",
+    "next",
+    "rows",
+    "only",
+    "window",
+    "lateral",
+    "recursive",
+];
@@ -1,18 +1,29 @@
 use crate::application::{UpdateAction, StoreAction};
+use crate::sql::dialect::Dialect;
 use sqlx::{Any, Pool, Row};
 use tokio::{spawn, sync::mpsc::UnboundedSender};
 
 pub struct Tables;
 
 impl Tables {
-    pub fn get_tables(pool: Pool<Any>, sender: UnboundedSender<UpdateAction>, db_name: String) {
+    pub fn get_tables(
+        pool: Pool<Any>,
+        sender: UnboundedSender<UpdateAction>,
+        dialect: Dialect,
+        db_name: String,
+    ) {
         spawn(async move {
-            let query = sqlx::query(&format!(
-                "select table_name from information_schema.tables where table_schema='{}'",
-                db_name
-            ))
-            .fetch_all(&pool)
-            .await;
+            let query = match dialect {
+                Dialect::MySql | Dialect::Postgres => format!(
+                    "select table_name as table_name from information_schema.tables where table_schema = {}",
+                    dialect.placeholder(1)
+                ),
+                Dialect::Sqlite => format!(
+                    "select name as table_name from pragma_table_list where schema = {} and type = 'table' and name not like 'sqlite_%'",
+                    dialect.placeholder(1)
+                ),
+            };
+            let query = sqlx::query(&query).bind(db_name).fetch_all(&pool).await;
 
             match query {
                 Ok(rows) => {
@@ -0,0 +1,176 @@
+use crate::application::{StoreAction, UpdateAction};
+use crate::sql::{connection::Driver, dialect::Dialect};
+use serde::{Deserialize, Serialize};
+use sqlx::{mysql::MySqlConnectOptions, postgres::PgConnectOptions, Any, Executor, Pool, Row};
+use std::str::FromStr;
+use tokio::{spawn, sync::mpsc::UnboundedSender};
+use url::Url;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SslMode {
+    Disable,
+    #[default]
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
+}
+
+impl SslMode {
+    pub const ALL: [SslMode; 5] = [
+        SslMode::Disable,
+        SslMode::Prefer,
+        SslMode::Require,
+        SslMode::VerifyCa,
+        SslMode::VerifyFull,
+    ];
+
+    fn postgres_value(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "disable",
+            SslMode::Prefer => "prefer",
+            SslMode::Require => "require",
+            SslMode::VerifyCa => "verify-ca",
+            SslMode::VerifyFull => "verify-full",
+        }
+    }
+
+    fn mysql_value(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "DISABLED",
+            SslMode::Prefer => "PREFERRED",
+            SslMode::Require => "REQUIRED",
+            SslMode::VerifyCa => "VERIFY_CA",
+            SslMode::VerifyFull => "VERIFY_IDENTITY",
+        }
+    }
+}
+
+impl std::fmt::Display for SslMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.postgres_value())
+    }
+}
+
+impl FromStr for SslMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SslMode::ALL
+            .into_iter()
+            .find(|mode| mode.postgres_value() == s)
+            .ok_or(anyhow::anyhow!("Unknown ssl mode : {}", s))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    #[serde(default, rename = "sslMode")]
+    pub ssl_mode: SslMode,
+    #[serde(default, rename = "caCert")]
+    pub ca_cert: String,
+    #[serde(default, rename = "clientCert")]
+    pub client_cert: String,
+    #[serde(default, rename = "clientKey")]
+    pub client_key: String,
+}
+
+impl TlsConfig {
+    /// Add the tls settings to a connection string, with the parameter names
+    /// the sqlx driver of each engine understands. They come after the
+    /// free-form options so they take precedence.
+    pub fn apply(&self, driver: Driver, connection_string: &str) -> anyhow::Result<String> {
+        let (ssl_mode, ca, cert, key) = match driver {
+            Driver::Postgres => (
+                ("sslmode", self.ssl_mode.postgres_value()),
+                "sslrootcert",
+                "sslcert",
+                "sslkey",
+            ),
+            Driver::MySql => (
+                ("ssl-mode", self.ssl_mode.mysql_value()),
+                "ssl-ca",
+                "ssl-cert",
+                "ssl-key",
+            ),
+            // a sqlite database is a local file
+            Driver::Sqlite => return Ok(connection_string.to_string()),
+        };
+
+        let mut url = Url::parse(connection_string)?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair(ssl_mode.0, ssl_mode.1);
+            for (name, path) in [
+                (ca, &self.ca_cert),
+                (cert, &self.client_cert),
+                (key, &self.client_key),
+            ] {
+                if !path.is_empty() {
+                    query.append_pair(name, path);
+                }
+            }
+        }
+
+        // let sqlx reject what it would not understand, before connecting
+        match driver {
+            Driver::Postgres => {
+                PgConnectOptions::from_str(url.as_str())?;
+            }
+            Driver::MySql => {
+                MySqlConnectOptions::from_str(url.as_str())?;
+            }
+            Driver::Sqlite => {}
+        }
+
+        Ok(url.to_string())
+    }
+}
+
+pub struct Tls;
+
+impl Tls {
+    /// Ask the server whether the session is encrypted, `None` being sent
+    /// when it can't tell. Nothing is sent for a sqlite database.
+    pub fn get_encryption(
+        pool: Pool<Any>,
+        sender: UnboundedSender<UpdateAction>,
+        dialect: Dialect,
+        index: usize,
+    ) {
+        spawn(async move {
+            let Some(encrypted) = Tls::is_encrypted(&pool, dialect).await else {
+                return;
+            };
+
+            sender
+                .send(UpdateAction::SendStoreAction(StoreAction::SendEncryption(
+                    index,
+                    encrypted.ok(),
+                )))
+                .unwrap();
+        });
+    }
+
+    /// `None` for a sqlite database, which has no session to encrypt.
+    pub async fn is_encrypted<'c, E>(executor: E, dialect: Dialect) -> Option<sqlx::Result<bool>>
+    where
+        E: Executor<'c, Database = Any>,
+    {
+        let encrypted = match dialect {
+            Dialect::Postgres => {
+                sqlx::query("select ssl from pg_stat_ssl where pid = pg_backend_pid()")
+                    .fetch_one(executor)
+                    .await
+                    .and_then(|row| row.try_get::<bool, _>(0))
+            }
+            Dialect::MySql => sqlx::query("show session status like 'Ssl_cipher'")
+                .fetch_one(executor)
+                .await
+                .and_then(|row| row.try_get::<String, _>(1))
+                .map(|cipher| !cipher.is_empty()),
+            Dialect::Sqlite => return None,
+        };
+        Some(encrypted)
+    }
+}
@@ -0,0 +1,172 @@
+use crate::application::{StoreAction, UpdateAction};
+use crate::sql::{
+    parameters::BoundStatement,
+    query::Query,
+    statement::{self, StatementKind},
+};
+use sqlx::{Any, Executor, Pool};
+use std::time::{Duration, Instant};
+use tokio::{
+    spawn,
+    sync::mpsc::{unbounded_channel, UnboundedSender},
+};
+
+/// Set before each batch of statements, to undo it alone when one fails.
+const SAVEPOINT: &str = "SAVEPOINT dbv_batch";
+const RELEASE_SAVEPOINT: &str = "RELEASE SAVEPOINT dbv_batch";
+const ROLLBACK_TO_SAVEPOINT: &str = "ROLLBACK TO SAVEPOINT dbv_batch";
+
+#[derive(Debug)]
+enum Request {
+    Run(Vec<BoundStatement>),
+    Commit,
+    Rollback,
+}
+
+/// Transaction held open on a dedicated connection of the pool while the
+/// connection is in manual-commit mode. It is rolled back when dropped.
+#[derive(Debug)]
+pub struct ManualTransaction {
+    requests: UnboundedSender<Request>,
+    started: Instant,
+    /// Statements changing data run since the transaction began.
+    pub pending: usize,
+}
+
+impl ManualTransaction {
+    /// Take a connection from the pool and begin the transaction on it. `id`
+    /// is the one of the connection, to report how many statements are
+    /// pending.
+    pub fn begin(pool: Pool<Any>, sender: UnboundedSender<UpdateAction>, id: String) -> Self {
+        let (requests, mut receiver) = unbounded_channel();
+        spawn(async move {
+            let mut transaction = match pool.begin().await {
+                Ok(transaction) => transaction,
+                Err(e) => {
+                    let _ =
+                        sender.send(UpdateAction::SendStoreAction(StoreAction::SendQueryError(
+                            format!("Unable to begin the transaction : {}", e),
+                        )));
+                    let _ = sender.send(UpdateAction::SendStoreAction(
+                        StoreAction::SendTransactionEnded(id),
+                    ));
+                    return;
+                }
+            };
+
+            let mut pending = 0;
+            // the transaction is dropped, and so rolled back, once the
+            // connection is closed
+            while let Some(request) = receiver.recv().await {
+                let action = match request {
+                    Request::Run(statements) => {
+                        // a failed statement leaves a postgres transaction
+                        // aborted, the statements sent with it are undone
+                        // back to the savepoint for the transaction to go on
+                        let before = pending;
+                        if let Err(e) = transaction.execute(SAVEPOINT).await {
+                            let _ = sender.send(UpdateAction::SendStoreAction(
+                                StoreAction::SendQueryError(e.to_string()),
+                            ));
+                            continue;
+                        }
+                        let mut failed = None;
+                        for statement in &statements {
+                            if let Err(e) =
+                                Query::execute(&mut transaction, statement, &sender).await
+                            {
+                                failed = Some(e);
+                                break;
+                            }
+                            if statement::kind(&statement.sql) != StatementKind::Read {
+                                pending += 1;
+                            }
+                        }
+                        let Some(e) = failed else {
+                            let _ = transaction.execute(RELEASE_SAVEPOINT).await;
+                            let _ = sender.send(UpdateAction::SendStoreAction(
+                                StoreAction::SendTransactionPending(id.clone(), pending),
+                            ));
+                            continue;
+                        };
+                        pending = before;
+                        if let Err(undo) = transaction.execute(ROLLBACK_TO_SAVEPOINT).await {
+                            // unusable, it is rolled back as it is dropped
+                            let _ = sender.send(UpdateAction::SendStoreAction(
+                                StoreAction::SendQueryError(format!(
+                                    "{}, the transaction couldn't go on and was rolled back : {}",
+                                    e, undo
+                                )),
+                            ));
+                            let _ = sender.send(UpdateAction::SendStoreAction(
+                                StoreAction::SendTransactionEnded(id),
+                            ));
+                            return;
+                        }
+                        let _ = sender.send(UpdateAction::SendStoreAction(
+                            StoreAction::SendQueryError(format!(
+                                "{}, the statements sent with it were undone and the transaction goes on.",
+                                e
+                            )),
+                        ));
+                        StoreAction::SendTransactionPending(id.clone(), pending)
+                    }
+                    Request::Commit => {
+                        let action = match transaction.commit().await {
+                            Ok(()) => StoreAction::SendSuccess(format!(
+                                "Transaction committed, {} statement(s) applied.",
+                                pending
+                            )),
+                            Err(e) => StoreAction::SendQueryError(format!(
+                                "Unable to commit the transaction : {}",
+                                e
+                            )),
+                        };
+                        let _ = sender.send(UpdateAction::SendStoreAction(action));
+                        return;
+                    }
+                    Request::Rollback => {
+                        let action = match transaction.rollback().await {
+                            Ok(()) => StoreAction::SendSuccess(format!(
+                                "Transaction rolled back, {} statement(s) undone.",
+                                pending
+                            )),
+                            Err(e) => StoreAction::SendQueryError(format!(
+                                "Unable to roll back the transaction : {}",
+                                e
+                            )),
+                        };
+                        let _ = sender.send(UpdateAction::SendStoreAction(action));
+                        return;
+                    }
+                };
+                let _ = sender.send(UpdateAction::SendStoreAction(action));
+            }
+        });
+
+        ManualTransaction {
+            requests,
+            started: Instant::now(),
+            pending: 0,
+        }
+    }
+
+    /// Run the statements inside the transaction, after the ones sent before.
+    pub fn run(&self, statements: Vec<BoundStatement>) -> anyhow::Result<()> {
+        self.requests
+            .send(Request::Run(statements))
+            .map_err(|_| anyhow::anyhow!("The transaction is not open anymore."))
+    }
+
+    pub fn commit(self) {
+        let _ = self.requests.send(Request::Commit);
+    }
+
+    pub fn rollback(self) {
+        let _ = self.requests.send(Request::Rollback);
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
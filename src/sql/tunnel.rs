@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    net::{Ipv4Addr, SocketAddr, TcpListener},
+    process::Stdio,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::AsyncReadExt,
+    net::TcpStream,
+    process::{Child, Command},
+    sync::Mutex,
+    time::{sleep, timeout},
+};
+
+const SSH_DEFAULT_PORT: u16 = 22;
+
+/// Jump host through which a connection is reached.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshConfig {
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub user: String,
+    /// Private key to authenticate with. The ssh agent is used when empty.
+    #[serde(default, rename = "keyFile")]
+    pub key_file: String,
+}
+
+/// A local port forward kept open by an `ssh -N -L` process. Clones share the
+/// process, which is killed once the last of them is dropped.
+#[derive(Debug, Clone)]
+pub struct Tunnel {
+    pub local_port: u16,
+    process: Arc<Mutex<TunnelProcess>>,
+}
+
+/// The `ssh` process and what it was started with, to start it again.
+#[derive(Debug)]
+struct TunnelProcess {
+    ssh: SshConfig,
+    remote_host: String,
+    remote_port: u16,
+    timeout: Duration,
+    child: Child,
+}
+
+/// A local port nobody listens on, to forward from.
+pub fn free_local_port() -> std::io::Result<u16> {
+    Ok(TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+        .local_addr()?
+        .port())
+}
+
+/// Arguments of the `ssh` command forwarding `local_port`.
+fn ssh_args(
+    ssh: &SshConfig,
+    local_port: u16,
+    remote_host: &str,
+    remote_port: u16,
+    timeout: Duration,
+) -> anyhow::Result<Vec<String>> {
+    // it would be taken as an option of ssh
+    if ssh.host.starts_with('-') {
+        anyhow::bail!("Invalid ssh host : {}", ssh.host);
+    }
+
+    let mut args = vec![
+        "-N".to_string(),
+        // never prompt, the terminal belongs to the tui
+        "-o".to_string(),
+        "BatchMode=yes".to_string(),
+        "-o".to_string(),
+        "ExitOnForwardFailure=yes".to_string(),
+        "-o".to_string(),
+        format!("ConnectTimeout={}", timeout.as_secs().max(1)),
+        "-p".to_string(),
+        ssh.port.unwrap_or(SSH_DEFAULT_PORT).to_string(),
+        "-L".to_string(),
+        format!("127.0.0.1:{}:{}:{}", local_port, remote_host, remote_port),
+    ];
+    if !ssh.user.is_empty() {
+        args.extend(["-l".to_string(), ssh.user.clone()]);
+    }
+    if !ssh.key_file.is_empty() {
+        args.extend([
+            "-i".to_string(),
+            ssh.key_file.clone(),
+            "-o".to_string(),
+            "IdentitiesOnly=yes".to_string(),
+        ]);
+    }
+    args.extend(["--".to_string(), ssh.host.clone()]);
+    Ok(args)
+}
+
+impl Tunnel {
+    /// Forward `local_port` to `remote_host:remote_port` as seen from the jump
+    /// host, and wait up to `timeout` for the forward to accept connections.
+    pub async fn open(
+        ssh: &SshConfig,
+        local_port: u16,
+        remote_host: &str,
+        remote_port: u16,
+        timeout: Duration,
+    ) -> anyhow::Result<Self> {
+        let mut child = spawn_ssh(ssh, local_port, remote_host, remote_port, timeout)?;
+        wait_ready(&mut child, local_port, timeout).await?;
+        let process = TunnelProcess {
+            ssh: ssh.clone(),
+            remote_host: remote_host.to_string(),
+            remote_port,
+            timeout,
+            child,
+        };
+        Ok(Tunnel {
+            local_port,
+            process: Arc::new(Mutex::new(process)),
+        })
+    }
+
+    /// Start `ssh` again on the same local port if it has exited, e.g. when
+    /// the jump host dropped the session. Tells whether it had to.
+    pub async fn reopen_if_exited(&self) -> anyhow::Result<bool> {
+        let mut process = self.process.lock().await;
+        if process.child.try_wait()?.is_none() {
+            return Ok(false);
+        }
+        let process = &mut *process;
+        process.child = spawn_ssh(
+            &process.ssh,
+            self.local_port,
+            &process.remote_host,
+            process.remote_port,
+            process.timeout,
+        )?;
+        wait_ready(&mut process.child, self.local_port, process.timeout).await?;
+        Ok(true)
+    }
+}
+
+fn spawn_ssh(
+    ssh: &SshConfig,
+    local_port: u16,
+    remote_host: &str,
+    remote_port: u16,
+    timeout: Duration,
+) -> anyhow::Result<Child> {
+    Ok(Command::new("ssh")
+        .args(ssh_args(
+            ssh,
+            local_port,
+            remote_host,
+            remote_port,
+            timeout,
+        )?)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?)
+}
+
+async fn wait_ready(child: &mut Child, local_port: u16, wait: Duration) -> anyhow::Result<()> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, local_port));
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                pipe.read_to_string(&mut stderr).await?;
+            }
+            anyhow::bail!("SSH tunnel exited ({}) : {}", status, stderr.trim());
+        }
+        let connect = timeout(Duration::from_millis(200), TcpStream::connect(address));
+        if let Ok(Ok(_)) = connect.await {
+            return Ok(());
+        }
+        if start.elapsed() > wait {
+            anyhow::bail!("SSH tunnel not ready after {:?}.", wait);
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jump_host(host: &str) -> SshConfig {
+        SshConfig {
+            host: host.to_string(),
+            port: Some(2222),
+            user: "dbv".to_string(),
+            key_file: String::new(),
+        }
+    }
+
+    #[test]
+    fn host_comes_last_after_the_options() {
+        let args = ssh_args(
+            &jump_host("bastion"),
+            40000,
+            "db",
+            3306,
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!(args[args.len() - 2..], ["--", "bastion"]);
+        assert!(args.contains(&"127.0.0.1:40000:db:3306".to_string()));
+        assert!(args.contains(&"ConnectTimeout=5".to_string()));
+    }
+
+    #[test]
+    fn host_is_never_an_option() {
+        let host = "-oProxyCommand=touch /tmp/pwned";
+        assert!(ssh_args(&jump_host(host), 40000, "db", 3306, Duration::from_secs(5)).is_err());
+    }
+}
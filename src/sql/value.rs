@@ -0,0 +1,149 @@
+use super::dialect::Dialect;
+use sqlx::{any::AnyRow, Column, Row, TypeInfo, ValueRef};
+
+/// Reads a cell as one type, `None` when it holds another one.
+type Decoder = fn(&AnyRow, usize) -> Option<Option<Value>>;
+
+/// A cell read from any engine, kept with its type so it can be compared and
+/// written back as a literal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl Value {
+    pub fn from_row(row: &AnyRow, index: usize) -> anyhow::Result<Self> {
+        // the type of the value itself, sqlite columns may hold values of
+        // another type than the declared one
+        let first = match row.try_get_raw(index)?.type_info().name() {
+            // sqlx refuses to decode a NULL into most types, even as an Option
+            "NULL" => return Ok(Value::Null),
+            "SMALLINT" | "INTEGER" | "BIGINT" => 0,
+            "REAL" | "DOUBLE" => 1,
+            "BOOLEAN" => 3,
+            "BLOB" => 4,
+            _ => 2,
+        };
+        // the other types are tried when the first one doesn't fit
+        let decoders: [Decoder; 5] = [
+            |row, i| {
+                row.try_get::<Option<i64>, _>(i)
+                    .ok()
+                    .map(|v| v.map(Value::Int))
+            },
+            |row, i| {
+                row.try_get::<Option<f64>, _>(i)
+                    .ok()
+                    .map(|v| v.map(Value::Float))
+            },
+            |row, i| {
+                row.try_get::<Option<String>, _>(i)
+                    .ok()
+                    .map(|v| v.map(Value::Text))
+            },
+            |row, i| {
+                row.try_get::<Option<bool>, _>(i)
+                    .ok()
+                    .map(|v| v.map(Value::Bool))
+            },
+            |row, i| {
+                row.try_get::<Option<Vec<u8>>, _>(i)
+                    .ok()
+                    .map(|v| v.map(Value::Bytes))
+            },
+        ];
+        let order = std::iter::once(first).chain((0..decoders.len()).filter(|&i| i != first));
+        for decoder in order {
+            if let Some(value) = decoders[decoder](row, index) {
+                return Ok(value.unwrap_or(Value::Null));
+            }
+        }
+        anyhow::bail!(
+            "Unable to read the column {} of type {}.",
+            row.column(index).name(),
+            row.column(index).type_info()
+        )
+    }
+
+    /// Whether both values are equal, numbers being compared whatever the
+    /// type each engine gave them.
+    pub fn same(&self, other: &Value) -> bool {
+        match (self.as_f64(), other.as_f64()) {
+            (Some(a), Some(b)) => a == b,
+            _ => self == other,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Bool(b) => Some(*b as u8 as f64),
+            Value::Int(i) => Some(*i as f64),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    /// `column = value`, or `column IS NULL` as NULL equals nothing.
+    pub fn condition(&self, dialect: Dialect, column: &str) -> String {
+        match self {
+            Value::Null => format!("{} IS NULL", dialect.quote_identifier(column)),
+            _ => format!(
+                "{} = {}",
+                dialect.quote_identifier(column),
+                self.literal(dialect)
+            ),
+        }
+    }
+
+    /// The value written as a sql literal of the dialect.
+    pub fn literal(&self, dialect: Dialect) -> String {
+        match self {
+            Value::Null => "NULL".to_string(),
+            Value::Bool(b) => match dialect {
+                Dialect::Postgres => b.to_string().to_uppercase(),
+                Dialect::MySql | Dialect::Sqlite => (*b as u8).to_string(),
+            },
+            Value::Int(i) => i.to_string(),
+            Value::Float(f) if f.is_finite() => f.to_string(),
+            Value::Float(f) => quote_string(&f.to_string(), dialect),
+            Value::Text(s) => quote_string(s, dialect),
+            Value::Bytes(bytes) => {
+                let hex = bytes
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>();
+                match dialect {
+                    Dialect::Postgres => format!("'\\x{}'::bytea", hex),
+                    Dialect::MySql | Dialect::Sqlite => format!("X'{}'", hex),
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Null => write!(f, "NULL"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::Text(s) => write!(f, "'{}'", s),
+            Value::Bytes(bytes) => write!(f, "<{} bytes>", bytes.len()),
+        }
+    }
+}
+
+fn quote_string(s: &str, dialect: Dialect) -> String {
+    let mut quoted = s.replace('\'', "''");
+    // backslashes are escape characters in mysql strings by default
+    if dialect == Dialect::MySql {
+        quoted = quoted.replace('\\', "\\\\");
+    }
+    format!("'{}'", quoted)
+}
@@ -10,3 +10,57 @@ pub fn get_path_app_file(filename: &str) -> String {
     let home = env::var("HOME").expect("An error occured while reading $HOME.");
     format!("{}{}{}", home, APP_PATH, filename)
 }
+
+/// Score of `text` against a fuzzy `pattern`, whose characters have to be
+/// found in it in order, case aside. `None` when they aren't, the higher the
+/// better otherwise : consecutive characters and those starting a word count
+/// more, characters skipped in between count less.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    let mut chars = text.chars().enumerate();
+    let mut before = ' ';
+    for wanted in pattern.chars().filter(|c| !c.is_whitespace()) {
+        let wanted = wanted.to_lowercase().next().unwrap_or(wanted);
+        loop {
+            let (index, c) = chars.next()?;
+            let starts_word = !before.is_alphanumeric();
+            before = c;
+            if c.to_lowercase().next() != Some(wanted) {
+                continue;
+            }
+            score += match previous {
+                Some(p) if p + 1 == index => 8,
+                _ if starts_word => 6,
+                Some(p) => 2 - (index - p).min(10) as i64 / 5,
+                None => 1,
+            };
+            previous = Some(index);
+            break;
+        }
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_score_needs_every_character_in_order() {
+        assert!(fuzzy_score("usr", "Users by signup").is_some());
+        assert!(fuzzy_score("USR", "users").is_some());
+        assert_eq!(fuzzy_score("rsu", "users"), None);
+        assert_eq!(fuzzy_score("usersx", "users"), None);
+        assert_eq!(fuzzy_score(" ", "users"), Some(0));
+    }
+
+    #[test]
+    fn fuzzy_score_ranks_closer_matches_first() {
+        let score = |text| fuzzy_score("ord", text).unwrap();
+        // consecutive characters beat word starts, which beat scattered ones
+        assert!(score("orders") > score("open rows done"));
+        assert!(score("open rows done") > score("other fields"));
+        assert!(score("last orders") > score("colorado"));
+    }
+}
@@ -0,0 +1,119 @@
+use dbv::{
+    application::{AppAction, StoreAction, UpdateAction},
+    sql::{
+        dialect::{quote_identifier, Dialect, QuoteStyle},
+        records::Records,
+        tables::Tables,
+    },
+};
+use sqlx::{any::AnyPoolOptions, Any, Pool};
+use tokio::sync::mpsc::unbounded_channel;
+
+const HOSTILE_NAMES: [&str; 5] = [
+    "order",
+    "with space",
+    "dash-ed",
+    "quo\"te`s]",
+    "x\"; DROP TABLE keep; --",
+];
+
+async fn sqlite_pool() -> Pool<Any> {
+    sqlx::any::install_default_drivers();
+    // a single connection so every query sees the same in-memory database
+    AnyPoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap()
+}
+
+#[test]
+fn quote_identifier_escapes_closing_quote() {
+    assert_eq!(quote_identifier("a`b", QuoteStyle::Backtick), "`a``b`");
+    assert_eq!(
+        quote_identifier("a\"b", QuoteStyle::DoubleQuote),
+        "\"a\"\"b\""
+    );
+    assert_eq!(quote_identifier("a]b[", QuoteStyle::Bracket), "[a]]b[]");
+    assert_eq!(Dialect::MySql.qualified_name("my-db", "t"), "`my-db`.`t`");
+}
+
+#[tokio::test]
+async fn records_of_hostile_table_names() {
+    let pool = sqlite_pool().await;
+    let dialect = Dialect::Sqlite;
+    sqlx::query("create table keep (id integer)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    for name in HOSTILE_NAMES {
+        let table = dialect.quote_identifier(name);
+        sqlx::query(&format!("create table {} (id integer primary key)", table))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(&format!("insert into {} values (1), (2)", table))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (tx, mut rx) = unbounded_channel();
+        Records::get_all(pool.clone(), tx, dialect, "main".into(), name.into(), 100);
+        match rx.recv().await.unwrap() {
+            UpdateAction::SendAppAction(AppAction::SendRecords((rows, total, key))) => {
+                assert_eq!(rows.len(), 2);
+                assert_eq!(total, Some(2));
+                assert_eq!(key, vec!["id".to_string()]);
+            }
+            _ => panic!("no records received for {}", name),
+        }
+    }
+
+    let (tx, mut rx) = unbounded_channel();
+    Tables::get_tables(pool.clone(), tx, dialect, "main".into());
+    match rx.recv().await.unwrap() {
+        UpdateAction::SendStoreAction(StoreAction::SendTablesData(tables)) => {
+            assert!(tables.contains(&"keep".to_string()));
+            for name in HOSTILE_NAMES {
+                assert!(tables.contains(&name.to_string()));
+            }
+        }
+        _ => panic!("no tables received"),
+    }
+}
+
+#[tokio::test]
+async fn tables_of_hostile_schema_name() {
+    let pool = sqlite_pool().await;
+    let dialect = Dialect::Sqlite;
+    let schema = "odd' OR '1'='1";
+
+    sqlx::query(&format!(
+        "attach database ':memory:' as {}",
+        dialect.quote_identifier(schema)
+    ))
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(&format!(
+        "create table {} (id integer)",
+        dialect.qualified_name(schema, "only here")
+    ))
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query("create table elsewhere (id integer)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (tx, mut rx) = unbounded_channel();
+    Tables::get_tables(pool.clone(), tx, dialect, schema.into());
+    match rx.recv().await.unwrap() {
+        UpdateAction::SendStoreAction(StoreAction::SendTablesData(tables)) => {
+            assert_eq!(tables, vec!["only here".to_string()]);
+        }
+        _ => panic!("no tables received"),
+    }
+}
@@ -0,0 +1,70 @@
+use dbv::sql::tunnel::{free_local_port, SshConfig, Tunnel};
+use std::{env, process::Command, time::Duration};
+use tokio::net::TcpStream;
+
+fn var(name: &str, default: &str) -> String {
+    env::var(name).unwrap_or(default.to_string())
+}
+
+#[tokio::test]
+async fn tunnel_reports_an_unreachable_jump_host() {
+    let ssh = SshConfig {
+        host: "127.0.0.1".to_string(),
+        // nothing listens there
+        port: Some(free_local_port().unwrap()),
+        user: "dbv".to_string(),
+        key_file: String::new(),
+    };
+    let opened = Tunnel::open(
+        &ssh,
+        free_local_port().unwrap(),
+        "db",
+        3306,
+        Duration::from_secs(5),
+    )
+    .await;
+    assert!(opened.is_err());
+}
+
+/// Runs against the `bastion` and `mariadb` services of docker/compose.yml,
+/// with `cargo test -- --ignored`. `DBV_TEST_SSH_HOST`, `DBV_TEST_SSH_PORT`,
+/// `DBV_TEST_SSH_USER`, `DBV_TEST_SSH_KEY`, `DBV_TEST_DB_HOST` and
+/// `DBV_TEST_DB_PORT` point it elsewhere.
+#[tokio::test]
+#[ignore]
+async fn tunnel_through_local_sshd() {
+    let ssh = SshConfig {
+        host: var("DBV_TEST_SSH_HOST", "localhost"),
+        port: Some(var("DBV_TEST_SSH_PORT", "2222").parse().unwrap()),
+        user: var("DBV_TEST_SSH_USER", "dbv"),
+        key_file: var("DBV_TEST_SSH_KEY", ""),
+    };
+    let local_port = free_local_port().unwrap();
+    let tunnel = Tunnel::open(
+        &ssh,
+        local_port,
+        &var("DBV_TEST_DB_HOST", "mariadb"),
+        var("DBV_TEST_DB_PORT", "3306").parse().unwrap(),
+        Duration::from_secs(10),
+    )
+    .await
+    .unwrap();
+    assert_eq!(tunnel.local_port, local_port);
+    TcpStream::connect(("127.0.0.1", local_port)).await.unwrap();
+    assert!(!tunnel.reopen_if_exited().await.unwrap());
+
+    // as if the jump host had dropped the session
+    let forward = format!("127.0.0.1:{}:", local_port);
+    let killed = Command::new("pkill")
+        .args(["-f", &forward])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(tunnel.reopen_if_exited().await.unwrap());
+    TcpStream::connect(("127.0.0.1", local_port)).await.unwrap();
+
+    drop(tunnel);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(TcpStream::connect(("127.0.0.1", local_port)).await.is_err());
+}